@@ -0,0 +1,89 @@
+// time attack: as many secrets as possible before the clock runs out
+//
+// every secret is an ordinary game under the same rules, with the time limit set to
+// the length of the whole run and the time of the earlier secrets counted against it.
+// a missed secret only costs the time spent on it, the run ends when the time is up
+// or the player quits.
+
+use std::time::Duration;
+
+use crate::clock::Clock;
+use crate::game::{GameState, Outcome, Player, Rules, play_timed};
+use crate::host::SecretHost;
+
+/// How long a time attack lasts unless told otherwise.
+pub const TIME_ATTACK: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttackScore {
+    pub solved: u32,
+    pub missed: u32,
+    // guesses over every secret, the one that came in too late included
+    pub attempts: u32,
+    pub elapsed: Duration,
+    // the player stopped before the time was up
+    pub quit: bool,
+}
+
+pub struct TimeAttack {
+    rules: Rules,
+    seed: u64,
+}
+
+impl TimeAttack {
+    /// A run of `length` under `rules`, secret `i` drawn from seed `seed + i`.
+    pub fn new(rules: Rules, length: Duration, seed: u64) -> Self {
+        TimeAttack {
+            rules: Rules {
+                time_limit: Some(length),
+                ..rules
+            },
+            seed,
+        }
+    }
+
+    /// The rules of every secret, the time limit is the length of the run.
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Plays secret after secret until the time is up. `new_player` is asked for a
+    /// fresh player for each one, with the score so far and the secret's seed.
+    ///
+    /// Nothing here waits, the run only ends once `clock` gets past the limit.
+    pub fn run(
+        &self,
+        clock: &dyn Clock,
+        new_player: &mut dyn FnMut(&AttackScore, u64) -> Box<dyn Player>,
+    ) -> AttackScore {
+        let length = self.rules.time_limit.unwrap_or_default();
+        let started = clock.now();
+        let mut score = AttackScore::default();
+
+        for round in 0.. {
+            let spent = clock.now() - started;
+            if spent >= length {
+                break;
+            }
+            let seed = self.seed.wrapping_add(round);
+            let mut host = SecretHost::seeded(&self.rules, seed);
+            let mut player = new_player(&score, seed);
+            let mut state = GameState::new(self.rules);
+            let outcome = play_timed(&mut state, &mut host, player.as_mut(), spent, clock);
+            score.attempts += state.attempts();
+
+            match outcome {
+                Outcome::Won { .. } => score.solved += 1,
+                // the guess that came in too late is not a missed secret
+                Outcome::Lost { .. } if clock.now() - started > length => break,
+                Outcome::Lost { .. } => score.missed += 1,
+                Outcome::Quit { .. } | Outcome::Saved { .. } => {
+                    score.quit = true;
+                    break;
+                }
+            }
+        }
+        score.elapsed = (clock.now() - started).min(length);
+        score
+    }
+}
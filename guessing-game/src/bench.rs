@@ -0,0 +1,98 @@
+// plays many seeded games per strategy and compares how many attempts they need
+
+use std::collections::BTreeMap;
+
+use crate::game::{GameState, Outcome, Rules, play};
+use crate::host::{AdversaryHost, SecretHost};
+use crate::solvers::Strategy;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    pub strategy: Strategy,
+    pub games: u32,
+    pub mean: f64,
+    pub worst: u32,
+    // games finished within the attempts the rules allow
+    pub within_limit: u32,
+    // attempts needed -> number of games
+    pub distribution: BTreeMap<u32, u32>,
+    // attempts needed against the adversary host, the true worst case
+    pub against_adversary: u32,
+}
+
+/// The most guesses a bisection can need to find one of `size` numbers.
+///
+/// Every guess rules out the guessed number and at least half of the rest, which
+/// gives `floor(log2(size)) + 1`, the same as `ceil(log2(size + 1))`. Note that
+/// plain `ceil(log2(size))` is one short when `size` is a power of two.
+pub fn bisection_bound(size: u64) -> u32 {
+    64 - size.leading_zeros()
+}
+
+// the benchmark is about how many guesses a strategy needs, so the bots get as many
+// as it takes (every strategy only guesses possible numbers, so this always ends)
+fn unlimited(rules: &Rules) -> Rules {
+    Rules {
+        max_attempts: rules.size().min(u32::MAX as u64) as u32,
+        time_limit: None,
+        ..*rules
+    }
+}
+
+/// How many guesses `strategy` needs against the adversary host.
+///
+/// The adversary always keeps the larger half, so no strategy can do better than
+/// `bisection_bound` here and a worst-case optimal one does exactly that well.
+/// That the adversary's answers always fit some number is checked in
+/// tests/adversary.rs.
+pub fn against_adversary(strategy: Strategy, rules: &Rules, seed: u64) -> u32 {
+    let rules = unlimited(rules);
+    let mut host = AdversaryHost::new(&rules);
+    let mut player = strategy.player(seed);
+    let mut state = GameState::new(rules);
+    play(&mut state, &mut host, player.as_mut());
+    state.attempts()
+}
+
+/// Plays `games` games with `strategy`. Game `i` uses secret seed `seed + i`, so
+/// every strategy is measured on exactly the same secrets.
+pub fn run(strategy: Strategy, rules: &Rules, games: u32, seed: u64) -> BenchReport {
+    let unlimited = unlimited(rules);
+
+    let mut distribution = BTreeMap::new();
+    let mut total = 0u64;
+    let mut within_limit = 0;
+    for game in 0..games {
+        let game_seed = seed.wrapping_add(game as u64);
+        let mut host = SecretHost::seeded(&unlimited, game_seed);
+        // the strategy's own chances must not follow the secret's seed
+        let mut player = strategy.player(game_seed ^ 0xA5A5_A5A5_A5A5_A5A5);
+        let mut state = GameState::new(unlimited);
+
+        let attempts = match play(&mut state, &mut host, player.as_mut()) {
+            Outcome::Won { attempts }
+            | Outcome::Lost { attempts }
+            | Outcome::Quit { attempts }
+            | Outcome::Saved { attempts } => attempts,
+        };
+        *distribution.entry(attempts).or_insert(0) += 1;
+        total += attempts as u64;
+        if state.is_won() && attempts <= rules.max_attempts {
+            within_limit += 1;
+        }
+    }
+
+    BenchReport {
+        strategy,
+        against_adversary: against_adversary(strategy, rules, seed),
+        games,
+        mean: if games == 0 {
+            0.0
+        } else {
+            total as f64 / games as f64
+        },
+        worst: distribution.keys().next_back().copied().unwrap_or(0),
+        within_limit,
+        distribution,
+    }
+}
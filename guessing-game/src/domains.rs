@@ -0,0 +1,1132 @@
+// guessing things that are not whole numbers from 1 to 100: decimals that only have
+// to be close enough, days of the year, letters and points on a grid
+//
+// a Domain knows how to read, draw and compare its values. the ones that lie on a
+// line (everything but the grid) are `Ordered` and answer "too low" or "too high",
+// which is all the generic `Bisection` solver needs. the grid answers with a compass
+// direction instead, and its solver runs one bisection per axis.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::i18n;
+use crate::rng::Rng;
+
+/// Something with values a player can guess, and feedback for every guess.
+pub trait Domain {
+    type Value: Clone + PartialEq + fmt::Debug + fmt::Display;
+    type Feedback: Clone + PartialEq + fmt::Debug + fmt::Display;
+
+    /// A short name for menus and the score board, e.g. "letter".
+    fn name(&self) -> &'static str;
+
+    /// What is being guessed, e.g. "a letter from a to z".
+    fn describe(&self) -> String;
+
+    /// Reads a value the way a player types it. The range is not checked here.
+    fn parse(&self, text: &str) -> Result<Self::Value, ValueError>;
+
+    fn contains(&self, value: &Self::Value) -> bool;
+
+    fn random(&self, rng: &mut Rng) -> Self::Value;
+
+    /// What a player is told about `guess` when the secret is `secret`.
+    fn feedback(&self, secret: &Self::Value, guess: &Self::Value) -> Self::Feedback;
+
+    fn is_solved(&self, feedback: &Self::Feedback) -> bool;
+}
+
+/// Why text is not a value, or two values don't make a domain.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueError {
+    // the range of numbers, whole or not, has nothing in it
+    EmptyRange { low: String, high: String },
+    Tolerance(f64),
+    NotAWholeNumber(String),
+    NotANumber(String),
+    NotADate(String),
+    Year(i64),
+    // a date in the right shape that isn't in the calendar, like 2023-02-29
+    NotADay(String),
+    // days since 1970-01-01 that are outside Date::MIN..=Date::MAX
+    DayOutOfRange(i64),
+    DatesBackwards { first: Date, last: Date },
+    NotLetters { first: char, last: char },
+    NotALetter(String),
+    NotAPoint(String),
+    EmptyGrid { low: Point, high: Point },
+}
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueError::EmptyRange { low, high } => {
+                write!(f, "the range {} to {} is empty", low, high)
+            }
+            ValueError::Tolerance(tolerance) => {
+                write!(f, "the tolerance must be above 0, not {}", tolerance)
+            }
+            ValueError::NotAWholeNumber(text) => write!(f, "'{}' is not a whole number", text),
+            ValueError::NotANumber(text) => write!(f, "'{}' is not a number", text),
+            ValueError::NotADate(text) => write!(f, "'{}' is not a date like 2024-05-01", text),
+            ValueError::Year(year) => write!(
+                f,
+                "{} is not a year from {} to {}",
+                year,
+                YEARS.start(),
+                YEARS.end()
+            ),
+            ValueError::NotADay(text) => write!(f, "{} is not a day", text),
+            ValueError::DayOutOfRange(days) => write!(
+                f,
+                "day {} is not between {} and {}",
+                days,
+                Date::MIN,
+                Date::MAX
+            ),
+            ValueError::DatesBackwards { first, last } => write!(f, "{} is after {}", first, last),
+            ValueError::NotLetters { first, last } => {
+                write!(f, "{} to {} is not a range of letters", first, last)
+            }
+            ValueError::NotALetter(text) => write!(f, "'{}' is not a single letter", text),
+            ValueError::NotAPoint(text) => write!(f, "'{}' is not a point like 3,4", text),
+            ValueError::EmptyGrid { low, high } => {
+                write!(f, "the grid from {} to {} is empty", low, high)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValueError {}
+
+impl ValueError {
+    /// The error in the player's language, `Display` stays English.
+    pub fn localised(&self) -> String {
+        match self {
+            ValueError::EmptyRange { low, high } => {
+                i18n::message("value-empty-range", &[("low", low), ("high", high)])
+            }
+            ValueError::Tolerance(tolerance) => {
+                i18n::message("value-tolerance", &[("tolerance", tolerance)])
+            }
+            ValueError::NotAWholeNumber(text) => {
+                i18n::message("value-not-whole-number", &[("input", text)])
+            }
+            ValueError::NotANumber(text) => i18n::message("value-not-number", &[("input", text)]),
+            ValueError::NotADate(text) => i18n::message("value-not-date", &[("input", text)]),
+            ValueError::Year(year) => i18n::message(
+                "value-year",
+                &[
+                    ("year", year),
+                    ("first", YEARS.start()),
+                    ("last", YEARS.end()),
+                ],
+            ),
+            ValueError::NotADay(text) => i18n::message("value-not-day", &[("input", text)]),
+            ValueError::DayOutOfRange(days) => i18n::message(
+                "value-day-range",
+                &[("days", days), ("first", &Date::MIN), ("last", &Date::MAX)],
+            ),
+            ValueError::DatesBackwards { first, last } => {
+                i18n::message("value-dates-backwards", &[("first", first), ("last", last)])
+            }
+            ValueError::NotLetters { first, last } => {
+                i18n::message("value-not-letters", &[("first", first), ("last", last)])
+            }
+            ValueError::NotALetter(text) => i18n::message("value-not-letter", &[("input", text)]),
+            ValueError::NotAPoint(text) => i18n::message("value-not-point", &[("input", text)]),
+            ValueError::EmptyGrid { low, high } => {
+                i18n::message("value-empty-grid", &[("low", low), ("high", high)])
+            }
+        }
+    }
+}
+
+/// The feedback of every ordered domain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    TooLow,
+    TooHigh,
+    Correct,
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&i18n::text(match self {
+            Order::TooLow => "verdict-too-low",
+            Order::TooHigh => "verdict-too-high",
+            Order::Correct => "order-correct",
+        }))
+    }
+}
+
+/// A domain whose values lie on a line, so a guess splits it in two.
+pub trait Ordered: Domain<Feedback = Order> {
+    /// The smallest and the largest value.
+    fn bounds(&self) -> (Self::Value, Self::Value);
+
+    fn compare(&self, a: &Self::Value, b: &Self::Value) -> Ordering;
+
+    /// A value about halfway from `low` to `high`, both included.
+    fn middle(&self, low: &Self::Value, high: &Self::Value) -> Self::Value;
+
+    /// The smallest value that can still be the secret after `value` was too low.
+    fn above(&self, value: &Self::Value) -> Option<Self::Value>;
+
+    /// The largest value that can still be the secret after `value` was too high.
+    fn below(&self, value: &Self::Value) -> Option<Self::Value>;
+}
+
+// the feedback of an ordered domain without a tolerance
+fn exact_order<D: Ordered>(domain: &D, secret: &D::Value, guess: &D::Value) -> Order {
+    match domain.compare(guess, secret) {
+        Ordering::Less => Order::TooLow,
+        Ordering::Greater => Order::TooHigh,
+        Ordering::Equal => Order::Correct,
+    }
+}
+
+/// Whole numbers, the classic game. The grid uses it for its axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Integers {
+    pub low: i64,
+    pub high: i64,
+}
+
+impl Integers {
+    pub fn new(low: i64, high: i64) -> Result<Self, ValueError> {
+        if low > high {
+            return Err(ValueError::EmptyRange {
+                low: low.to_string(),
+                high: high.to_string(),
+            });
+        }
+        Ok(Integers { low, high })
+    }
+}
+
+impl Domain for Integers {
+    type Value = i64;
+    type Feedback = Order;
+
+    fn name(&self) -> &'static str {
+        "integer"
+    }
+
+    fn describe(&self) -> String {
+        i18n::message(
+            "domain-integer",
+            &[("low", &self.low), ("high", &self.high)],
+        )
+    }
+
+    fn parse(&self, text: &str) -> Result<i64, ValueError> {
+        text.trim()
+            .parse()
+            .map_err(|_| ValueError::NotAWholeNumber(text.trim().to_string()))
+    }
+
+    fn contains(&self, value: &i64) -> bool {
+        (self.low..=self.high).contains(value)
+    }
+
+    fn random(&self, rng: &mut Rng) -> i64 {
+        let size = self.high.abs_diff(self.low).saturating_add(1);
+        self.low.wrapping_add(rng.below(size) as i64)
+    }
+
+    fn feedback(&self, secret: &i64, guess: &i64) -> Order {
+        exact_order(self, secret, guess)
+    }
+
+    fn is_solved(&self, feedback: &Order) -> bool {
+        *feedback == Order::Correct
+    }
+}
+
+impl Ordered for Integers {
+    fn bounds(&self) -> (i64, i64) {
+        (self.low, self.high)
+    }
+
+    fn compare(&self, a: &i64, b: &i64) -> Ordering {
+        a.cmp(b)
+    }
+
+    // half the distance fits an i64 even when the axis spans all of them
+    fn middle(&self, low: &i64, high: &i64) -> i64 {
+        low.wrapping_add((high.abs_diff(*low) / 2) as i64)
+    }
+
+    fn above(&self, value: &i64) -> Option<i64> {
+        value.checked_add(1)
+    }
+
+    fn below(&self, value: &i64) -> Option<i64> {
+        value.checked_sub(1)
+    }
+}
+
+/// Decimal numbers. A guess within `tolerance` of the secret is correct.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Floats {
+    pub low: f64,
+    pub high: f64,
+    pub tolerance: f64,
+}
+
+impl Default for Floats {
+    fn default() -> Self {
+        Floats {
+            low: 0.0,
+            high: 1.0,
+            tolerance: 0.01,
+        }
+    }
+}
+
+impl Floats {
+    pub fn new(low: f64, high: f64, tolerance: f64) -> Result<Self, ValueError> {
+        if !low.is_finite() || !high.is_finite() || low >= high {
+            return Err(ValueError::EmptyRange {
+                low: low.to_string(),
+                high: high.to_string(),
+            });
+        }
+        if !tolerance.is_finite() || tolerance <= 0.0 {
+            return Err(ValueError::Tolerance(tolerance));
+        }
+        Ok(Floats {
+            low,
+            high,
+            tolerance,
+        })
+    }
+
+    // the decimals that matter at this tolerance, a secret never has more
+    fn decimals(&self) -> i32 {
+        (-self.tolerance.log10()).ceil().clamp(0.0, 12.0) as i32
+    }
+
+    fn round(&self, value: f64, decimals: i32) -> f64 {
+        let scale = 10f64.powi(decimals);
+        (value * scale).round() / scale
+    }
+}
+
+impl Domain for Floats {
+    type Value = f64;
+    type Feedback = Order;
+
+    fn name(&self) -> &'static str {
+        "float"
+    }
+
+    fn describe(&self) -> String {
+        i18n::message(
+            "domain-float",
+            &[
+                ("low", &self.low),
+                ("high", &self.high),
+                ("tolerance", &self.tolerance),
+            ],
+        )
+    }
+
+    fn parse(&self, text: &str) -> Result<f64, ValueError> {
+        text.trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| ValueError::NotANumber(text.trim().to_string()))
+    }
+
+    fn contains(&self, value: &f64) -> bool {
+        (self.low..=self.high).contains(value)
+    }
+
+    fn random(&self, rng: &mut Rng) -> f64 {
+        let share = rng.next_u64() as f64 / u64::MAX as f64;
+        let value = self.round(self.low + (self.high - self.low) * share, self.decimals());
+        value.clamp(self.low, self.high)
+    }
+
+    fn feedback(&self, secret: &f64, guess: &f64) -> Order {
+        if (guess - secret).abs() <= self.tolerance {
+            Order::Correct
+        } else if guess < secret {
+            Order::TooLow
+        } else {
+            Order::TooHigh
+        }
+    }
+
+    fn is_solved(&self, feedback: &Order) -> bool {
+        *feedback == Order::Correct
+    }
+}
+
+impl Ordered for Floats {
+    fn bounds(&self) -> (f64, f64) {
+        (self.low, self.high)
+    }
+
+    fn compare(&self, a: &f64, b: &f64) -> Ordering {
+        a.total_cmp(b)
+    }
+
+    // one decimal more than the secrets have keeps the guesses readable
+    fn middle(&self, low: &f64, high: &f64) -> f64 {
+        self.round(low + (high - low) / 2.0, self.decimals() + 1)
+            .clamp(*low, *high)
+    }
+
+    fn above(&self, value: &f64) -> Option<f64> {
+        Some(value + self.tolerance)
+    }
+
+    fn below(&self, value: &f64) -> Option<f64> {
+        Some(value - self.tolerance)
+    }
+}
+
+// the years a date can be in: four digits, which is what Display and FromStr handle,
+// and small enough that the calendar arithmetic below can't overflow
+const YEARS: RangeInclusive<i64> = 0..=9999;
+
+/// A day in the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    // days since 1970-01-01
+    days: i64,
+}
+
+impl Date {
+    /// 0000-01-01, the first day `from_ymd` accepts.
+    pub const MIN: Date = Date { days: -719_528 };
+    /// 9999-12-31, the last day `from_ymd` accepts.
+    pub const MAX: Date = Date { days: 2_932_896 };
+
+    pub fn from_days(days: i64) -> Self {
+        Date { days }
+    }
+
+    /// `None` for days that don't exist, like February 30th, and for years outside
+    /// 0 to 9999.
+    pub fn from_ymd(year: i64, month: u32, day: u32) -> Option<Self> {
+        if !YEARS.contains(&year)
+            || !(1..=12).contains(&month)
+            || day == 0
+            || day > days_in_month(year, month)
+        {
+            return None;
+        }
+        // Howard Hinnant's days-from-civil algorithm
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month_from_march = (month as i64 + 9) % 12;
+        let day_of_year = (153 * month_from_march + 2) / 5 + day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        Some(Date::from_days(era * 146_097 + day_of_era - 719_468))
+    }
+
+    /// Today in UTC.
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs())
+            .unwrap_or(0);
+        Date::from_days((seconds / 86_400) as i64)
+    }
+
+    pub fn days(&self) -> i64 {
+        self.days
+    }
+
+    /// Year, month (1-12) and day (1-31), with Hinnant's civil-from-days algorithm.
+    pub fn ymd(&self) -> (i64, u32, u32) {
+        let z = self.days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year, month, day)
+    }
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = ValueError;
+
+    /// `2024-05-01`.
+    fn from_str(text: &str) -> Result<Self, ValueError> {
+        let invalid = || ValueError::NotADate(text.trim().to_string());
+        let mut parts = text.trim().splitn(3, '-');
+        let mut next = || parts.next().and_then(|part| part.parse::<i64>().ok());
+        let (Some(year), Some(month), Some(day)) = (next(), next(), next()) else {
+            return Err(invalid());
+        };
+        let (Ok(month), Ok(day)) = (u32::try_from(month), u32::try_from(day)) else {
+            return Err(invalid());
+        };
+        if !YEARS.contains(&year) {
+            return Err(ValueError::Year(year));
+        }
+        Date::from_ymd(year, month, day).ok_or_else(|| ValueError::NotADay(text.trim().to_string()))
+    }
+}
+
+/// Guess the day, between two dates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dates {
+    pub first: Date,
+    pub last: Date,
+}
+
+impl Default for Dates {
+    /// Every day of this year.
+    fn default() -> Self {
+        let (year, _, _) = Date::today().ymd();
+        Dates {
+            first: Date::from_ymd(year, 1, 1).expect("January 1st exists"),
+            last: Date::from_ymd(year, 12, 31).expect("December 31st exists"),
+        }
+    }
+}
+
+impl Dates {
+    pub fn new(first: Date, last: Date) -> Result<Self, ValueError> {
+        // a day from from_days can be anywhere, even where it can't be printed
+        for date in [first, last] {
+            if !(Date::MIN..=Date::MAX).contains(&date) {
+                return Err(ValueError::DayOutOfRange(date.days));
+            }
+        }
+        if first > last {
+            return Err(ValueError::DatesBackwards { first, last });
+        }
+        Ok(Dates { first, last })
+    }
+}
+
+impl Domain for Dates {
+    type Value = Date;
+    type Feedback = Order;
+
+    fn name(&self) -> &'static str {
+        "date"
+    }
+
+    fn describe(&self) -> String {
+        i18n::message(
+            "domain-date",
+            &[("first", &self.first), ("last", &self.last)],
+        )
+    }
+
+    fn parse(&self, text: &str) -> Result<Date, ValueError> {
+        text.parse()
+    }
+
+    fn contains(&self, value: &Date) -> bool {
+        (self.first..=self.last).contains(value)
+    }
+
+    fn random(&self, rng: &mut Rng) -> Date {
+        let days = self.last.days.abs_diff(self.first.days).saturating_add(1);
+        Date::from_days(self.first.days.wrapping_add(rng.below(days) as i64))
+    }
+
+    fn feedback(&self, secret: &Date, guess: &Date) -> Order {
+        exact_order(self, secret, guess)
+    }
+
+    fn is_solved(&self, feedback: &Order) -> bool {
+        *feedback == Order::Correct
+    }
+}
+
+impl Ordered for Dates {
+    fn bounds(&self) -> (Date, Date) {
+        (self.first, self.last)
+    }
+
+    fn compare(&self, a: &Date, b: &Date) -> Ordering {
+        a.cmp(b)
+    }
+
+    fn middle(&self, low: &Date, high: &Date) -> Date {
+        Date::from_days(
+            low.days
+                .wrapping_add((high.days.abs_diff(low.days) / 2) as i64),
+        )
+    }
+
+    fn above(&self, value: &Date) -> Option<Date> {
+        value.days.checked_add(1).map(Date::from_days)
+    }
+
+    fn below(&self, value: &Date) -> Option<Date> {
+        value.days.checked_sub(1).map(Date::from_days)
+    }
+}
+
+/// Lowercase letters of the English alphabet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Letters {
+    pub first: char,
+    pub last: char,
+}
+
+impl Default for Letters {
+    fn default() -> Self {
+        Letters {
+            first: 'a',
+            last: 'z',
+        }
+    }
+}
+
+impl Letters {
+    pub fn new(first: char, last: char) -> Result<Self, ValueError> {
+        if !first.is_ascii_lowercase() || !last.is_ascii_lowercase() || first > last {
+            return Err(ValueError::NotLetters { first, last });
+        }
+        Ok(Letters { first, last })
+    }
+}
+
+impl Domain for Letters {
+    type Value = char;
+    type Feedback = Order;
+
+    fn name(&self) -> &'static str {
+        "letter"
+    }
+
+    fn describe(&self) -> String {
+        i18n::message(
+            "domain-letter",
+            &[("first", &self.first), ("last", &self.last)],
+        )
+    }
+
+    fn parse(&self, text: &str) -> Result<char, ValueError> {
+        let mut chars = text.trim().chars();
+        match (chars.next(), chars.next()) {
+            (Some(letter), None) if letter.is_ascii_alphabetic() => Ok(letter.to_ascii_lowercase()),
+            _ => Err(ValueError::NotALetter(text.trim().to_string())),
+        }
+    }
+
+    fn contains(&self, value: &char) -> bool {
+        (self.first..=self.last).contains(value)
+    }
+
+    fn random(&self, rng: &mut Rng) -> char {
+        let offset = rng.below((self.last as u64) - (self.first as u64) + 1) as u8;
+        (self.first as u8 + offset) as char
+    }
+
+    fn feedback(&self, secret: &char, guess: &char) -> Order {
+        exact_order(self, secret, guess)
+    }
+
+    fn is_solved(&self, feedback: &Order) -> bool {
+        *feedback == Order::Correct
+    }
+}
+
+impl Ordered for Letters {
+    fn bounds(&self) -> (char, char) {
+        (self.first, self.last)
+    }
+
+    fn compare(&self, a: &char, b: &char) -> Ordering {
+        a.cmp(b)
+    }
+
+    fn middle(&self, low: &char, high: &char) -> char {
+        (*low as u8 + (*high as u8 - *low as u8) / 2) as char
+    }
+
+    fn above(&self, value: &char) -> Option<char> {
+        char::from_u32(*value as u32 + 1)
+    }
+
+    fn below(&self, value: &char) -> Option<char> {
+        char::from_u32((*value as u32).checked_sub(1)?)
+    }
+}
+
+/// A point on the grid. `y` grows to the north, like on a map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl FromStr for Point {
+    type Err = ValueError;
+
+    /// `3,4`, spaces allowed.
+    fn from_str(text: &str) -> Result<Self, ValueError> {
+        let invalid = || ValueError::NotAPoint(text.trim().to_string());
+        let (x, y) = text.split_once(',').ok_or_else(invalid)?;
+        Ok(Point {
+            x: x.trim().parse().map_err(|_| invalid())?,
+            y: y.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Where the secret is seen from a guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compass {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+    Here,
+}
+
+impl Compass {
+    /// The direction from `from` to `to`.
+    pub fn between(from: Point, to: Point) -> Self {
+        match (to.x.cmp(&from.x), to.y.cmp(&from.y)) {
+            (Ordering::Equal, Ordering::Greater) => Compass::North,
+            (Ordering::Greater, Ordering::Greater) => Compass::NorthEast,
+            (Ordering::Greater, Ordering::Equal) => Compass::East,
+            (Ordering::Greater, Ordering::Less) => Compass::SouthEast,
+            (Ordering::Equal, Ordering::Less) => Compass::South,
+            (Ordering::Less, Ordering::Less) => Compass::SouthWest,
+            (Ordering::Less, Ordering::Equal) => Compass::West,
+            (Ordering::Less, Ordering::Greater) => Compass::NorthWest,
+            (Ordering::Equal, Ordering::Equal) => Compass::Here,
+        }
+    }
+
+    /// The direction split into the two axes, as a guess on each would be judged.
+    pub fn axes(&self) -> (Order, Order) {
+        let east_west = match self {
+            Compass::NorthEast | Compass::East | Compass::SouthEast => Order::TooLow,
+            Compass::NorthWest | Compass::West | Compass::SouthWest => Order::TooHigh,
+            Compass::North | Compass::South | Compass::Here => Order::Correct,
+        };
+        let north_south = match self {
+            Compass::NorthWest | Compass::North | Compass::NorthEast => Order::TooLow,
+            Compass::SouthWest | Compass::South | Compass::SouthEast => Order::TooHigh,
+            Compass::West | Compass::East | Compass::Here => Order::Correct,
+        };
+        (east_west, north_south)
+    }
+}
+
+impl fmt::Display for Compass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&i18n::text(match self {
+            Compass::North => "compass-north",
+            Compass::NorthEast => "compass-north-east",
+            Compass::East => "compass-east",
+            Compass::SouthEast => "compass-south-east",
+            Compass::South => "compass-south",
+            Compass::SouthWest => "compass-south-west",
+            Compass::West => "compass-west",
+            Compass::NorthWest => "compass-north-west",
+            Compass::Here => "compass-here",
+        }))
+    }
+}
+
+/// Every point from `low` to `high`, corners included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grid {
+    pub low: Point,
+    pub high: Point,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Grid {
+            low: Point { x: 0, y: 0 },
+            high: Point { x: 9, y: 9 },
+        }
+    }
+}
+
+impl Grid {
+    pub fn new(low: Point, high: Point) -> Result<Self, ValueError> {
+        if low.x > high.x || low.y > high.y {
+            return Err(ValueError::EmptyGrid { low, high });
+        }
+        Ok(Grid { low, high })
+    }
+
+    /// The x axis and the y axis on their own.
+    pub fn axes(&self) -> (Integers, Integers) {
+        (
+            Integers {
+                low: self.low.x,
+                high: self.high.x,
+            },
+            Integers {
+                low: self.low.y,
+                high: self.high.y,
+            },
+        )
+    }
+}
+
+impl Domain for Grid {
+    type Value = Point;
+    type Feedback = Compass;
+
+    fn name(&self) -> &'static str {
+        "grid"
+    }
+
+    fn describe(&self) -> String {
+        i18n::message("domain-grid", &[("low", &self.low), ("high", &self.high)])
+    }
+
+    fn parse(&self, text: &str) -> Result<Point, ValueError> {
+        text.parse()
+    }
+
+    fn contains(&self, value: &Point) -> bool {
+        let (x_axis, y_axis) = self.axes();
+        x_axis.contains(&value.x) && y_axis.contains(&value.y)
+    }
+
+    fn random(&self, rng: &mut Rng) -> Point {
+        let (x_axis, y_axis) = self.axes();
+        Point {
+            x: x_axis.random(rng),
+            y: y_axis.random(rng),
+        }
+    }
+
+    fn feedback(&self, secret: &Point, guess: &Point) -> Compass {
+        Compass::between(*guess, *secret)
+    }
+
+    fn is_solved(&self, feedback: &Compass) -> bool {
+        *feedback == Compass::Here
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DomainError {
+    OutOfRange(String),
+    GameOver,
+}
+
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainError::OutOfRange(message) => write!(f, "{}", message),
+            DomainError::GameOver => write!(f, "the game is already over"),
+        }
+    }
+}
+
+impl std::error::Error for DomainError {}
+
+impl DomainError {
+    /// The error in the player's language, `Display` stays English for logs and the APIs.
+    pub fn localised(&self) -> String {
+        match self {
+            // the message is made in the player's language already, with the domain's description
+            DomainError::OutOfRange(message) => message.clone(),
+            DomainError::GameOver => i18n::text("game-over"),
+        }
+    }
+}
+
+/// One game over any domain.
+pub struct DomainGame<D: Domain> {
+    domain: D,
+    secret: D::Value,
+    max_guesses: u32,
+    history: Vec<(D::Value, D::Feedback)>,
+}
+
+impl<D: Domain> DomainGame<D> {
+    pub fn new(domain: D, secret: D::Value, max_guesses: u32) -> Self {
+        DomainGame {
+            domain,
+            secret,
+            max_guesses,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn seeded(domain: D, seed: u64, max_guesses: u32) -> Self {
+        let secret = domain.random(&mut Rng::new(seed));
+        DomainGame::new(domain, secret, max_guesses)
+    }
+
+    pub fn domain(&self) -> &D {
+        &self.domain
+    }
+
+    pub fn max_guesses(&self) -> u32 {
+        self.max_guesses
+    }
+
+    pub fn history(&self) -> &[(D::Value, D::Feedback)] {
+        &self.history
+    }
+
+    /// The secret, only meant to be shown once the game is over.
+    pub fn secret(&self) -> &D::Value {
+        &self.secret
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.history
+            .last()
+            .is_some_and(|(_, feedback)| self.domain.is_solved(feedback))
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.history.len() as u32 >= self.max_guesses
+    }
+
+    pub fn guess(&mut self, guess: D::Value) -> Result<D::Feedback, DomainError> {
+        if self.is_over() {
+            return Err(DomainError::GameOver);
+        }
+        if !self.domain.contains(&guess) {
+            return Err(DomainError::OutOfRange(i18n::message(
+                "domain-outside",
+                &[("value", &guess), ("what", &self.domain.describe())],
+            )));
+        }
+        let feedback = self.domain.feedback(&self.secret, &guess);
+        self.history.push((guess, feedback.clone()));
+        Ok(feedback)
+    }
+}
+
+/// The side that guesses, like `Player` for the number game.
+pub trait Guesser<D: Domain> {
+    /// The next guess, or `None` to give up.
+    fn next_guess(&mut self, game: &DomainGame<D>) -> Option<D::Value>;
+
+    fn observe(&mut self, _guess: &D::Value, _feedback: &D::Feedback) {}
+
+    fn rejected(&mut self, _error: &DomainError) {}
+}
+
+/// Runs a game until the secret is found, the guesses run out or the guesser gives up.
+/// Returns whether the secret was found.
+pub fn play<D: Domain>(game: &mut DomainGame<D>, guesser: &mut dyn Guesser<D>) -> bool {
+    while !game.is_over() {
+        let Some(guess) = guesser.next_guess(game) else {
+            return false;
+        };
+        match game.guess(guess.clone()) {
+            Ok(feedback) => guesser.observe(&guess, &feedback),
+            Err(error) => guesser.rejected(&error),
+        }
+    }
+    game.is_won()
+}
+
+/// Halves the values that are left with every guess, on any ordered domain.
+#[derive(Debug, Clone)]
+pub struct Bisection<D: Ordered> {
+    domain: D,
+    // the smallest and largest value the secret can still be, `None` once nothing fits
+    range: Option<(D::Value, D::Value)>,
+}
+
+impl<D: Ordered + Clone> Bisection<D> {
+    pub fn new(domain: &D) -> Self {
+        Bisection {
+            domain: domain.clone(),
+            range: Some(domain.bounds()),
+        }
+    }
+
+    pub fn range(&self) -> Option<&(D::Value, D::Value)> {
+        self.range.as_ref()
+    }
+
+    pub fn guess(&self) -> Option<D::Value> {
+        self.range
+            .as_ref()
+            .map(|(low, high)| self.domain.middle(low, high))
+    }
+
+    /// Keeps only the values that fit `order` for `guess`.
+    pub fn narrow(&mut self, guess: &D::Value, order: Order) {
+        let Some((low, high)) = self.range.take() else {
+            return;
+        };
+        let later = |a: D::Value, b: D::Value| {
+            if self.domain.compare(&a, &b) == Ordering::Greater {
+                a
+            } else {
+                b
+            }
+        };
+        let earlier = |a: D::Value, b: D::Value| {
+            if self.domain.compare(&a, &b) == Ordering::Less {
+                a
+            } else {
+                b
+            }
+        };
+        let narrowed = match order {
+            Order::Correct => Some((guess.clone(), guess.clone())),
+            Order::TooLow => self
+                .domain
+                .above(guess)
+                .map(|above| (later(above, low), high)),
+            Order::TooHigh => self
+                .domain
+                .below(guess)
+                .map(|below| (low, earlier(below, high))),
+        };
+        self.range =
+            narrowed.filter(|(low, high)| self.domain.compare(low, high) != Ordering::Greater);
+    }
+}
+
+impl<D: Ordered + Clone> Guesser<D> for Bisection<D> {
+    fn next_guess(&mut self, _game: &DomainGame<D>) -> Option<D::Value> {
+        self.guess()
+    }
+
+    fn observe(&mut self, guess: &D::Value, feedback: &Order) {
+        self.narrow(guess, *feedback);
+    }
+}
+
+/// A bisection on each axis of the grid, the compass answers both at once.
+#[derive(Debug, Clone)]
+pub struct GridSolver {
+    x: Bisection<Integers>,
+    y: Bisection<Integers>,
+}
+
+impl GridSolver {
+    pub fn new(grid: &Grid) -> Self {
+        let (x_axis, y_axis) = grid.axes();
+        GridSolver {
+            x: Bisection::new(&x_axis),
+            y: Bisection::new(&y_axis),
+        }
+    }
+}
+
+impl Guesser<Grid> for GridSolver {
+    fn next_guess(&mut self, _game: &DomainGame<Grid>) -> Option<Point> {
+        Some(Point {
+            x: self.x.guess()?,
+            y: self.y.guess()?,
+        })
+    }
+
+    fn observe(&mut self, guess: &Point, feedback: &Compass) {
+        let (east_west, north_south) = feedback.axes();
+        self.x.narrow(&guess.x, east_west);
+        self.y.narrow(&guess.y, north_south);
+    }
+}
+
+/// A person typing guesses, for any domain.
+pub struct HumanGuesser<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> HumanGuesser<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        HumanGuesser { input, output }
+    }
+}
+
+impl<D: Domain, R: BufRead, W: Write> Guesser<D> for HumanGuesser<R, W> {
+    fn next_guess(&mut self, game: &DomainGame<D>) -> Option<D::Value> {
+        loop {
+            let prompt = i18n::message(
+                "prompt-numbered",
+                &[
+                    ("number", &(game.history().len() + 1)),
+                    ("total", &game.max_guesses()),
+                ],
+            );
+            let _ = write!(self.output, "{} ", prompt);
+            let _ = self.output.flush();
+
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Ok(0) | Err(_) => return None,
+                Ok(_) => {}
+            }
+            if line.trim().eq_ignore_ascii_case("quit") {
+                return None;
+            }
+            match game.domain().parse(&line) {
+                Ok(value) => return Some(value),
+                Err(error) => {
+                    let _ = writeln!(
+                        self.output,
+                        "{}",
+                        i18n::message("try-again", &[("error", &error.localised())])
+                    );
+                }
+            }
+        }
+    }
+
+    fn observe(&mut self, guess: &D::Value, feedback: &D::Feedback) {
+        let _ = writeln!(self.output, "{}: {}", guess, feedback);
+    }
+
+    fn rejected(&mut self, error: &DomainError) {
+        let _ = writeln!(
+            self.output,
+            "{}",
+            i18n::message("try-again", &[("error", &error.localised())])
+        );
+    }
+}
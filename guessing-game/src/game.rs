@@ -0,0 +1,406 @@
+// the core of the game: the rules, the state of one game and the two sides playing it
+//
+// a Host knows (or pretends to know) the secret and answers guesses,
+// a Player comes up with guesses. GameState sits in between and does the book keeping,
+// so every kind of host and player gets the same attempt counting and verdicts.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::time::Duration;
+
+use crate::clock::{Clock, SystemClock};
+use crate::hints::{HintError, HintKind};
+use crate::i18n;
+
+/// The settings of one game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    pub low: u32,
+    pub high: u32,
+    pub max_attempts: u32,
+    // the whole game has to be done in this time
+    pub time_limit: Option<Duration>,
+    // every single guess has to come in this time, a late one counts as a wrong guess
+    pub guess_limit: Option<Duration>,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            low: 1,
+            high: 100,
+            max_attempts: 7,
+            time_limit: None,
+            guess_limit: None,
+        }
+    }
+}
+
+impl Rules {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.low > self.high {
+            return Err(format!(
+                "the lowest number ({}) must not be larger than the highest ({})",
+                self.low, self.high
+            ));
+        }
+        if self.max_attempts == 0 {
+            return Err("a game needs at least 1 attempt".to_string());
+        }
+        if self.time_limit == Some(Duration::ZERO) {
+            return Err("a time limit must be longer than 0 seconds".to_string());
+        }
+        if self.guess_limit == Some(Duration::ZERO) {
+            return Err("a countdown must be longer than 0 seconds".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, guess: u32) -> bool {
+        (self.low..=self.high).contains(&guess)
+    }
+
+    /// How many numbers the secret can be, as u64 so 0..=u32::MAX fits. 0 for
+    /// rules that don't pass `validate` because low is above high.
+    pub fn size(&self) -> u64 {
+        if self.low > self.high {
+            return 0;
+        }
+        (self.high - self.low) as u64 + 1
+    }
+}
+
+/// What the game says about a guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    TooLow,
+    TooHigh,
+    Correct,
+    // the guess was wrong and it was the last attempt
+    OutOfAttempts,
+    // the guess came in after the time limit
+    OutOfTime,
+    // the countdown for this guess ran out, the attempt is used up
+    TooSlow,
+}
+
+impl Verdict {
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            Verdict::Correct | Verdict::OutOfAttempts | Verdict::OutOfTime
+        )
+    }
+
+    /// A stable name for files, `parse` reads it back.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verdict::TooLow => "too-low",
+            Verdict::TooHigh => "too-high",
+            Verdict::Correct => "correct",
+            Verdict::OutOfAttempts => "out-of-attempts",
+            Verdict::OutOfTime => "out-of-time",
+            Verdict::TooSlow => "too-slow",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Verdict> {
+        match name {
+            "too-low" => Some(Verdict::TooLow),
+            "too-high" => Some(Verdict::TooHigh),
+            "correct" => Some(Verdict::Correct),
+            "out-of-attempts" => Some(Verdict::OutOfAttempts),
+            "out-of-time" => Some(Verdict::OutOfTime),
+            "too-slow" => Some(Verdict::TooSlow),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The side of the game that holds the secret.
+pub trait Host {
+    /// Compares the guess to the secret, `Less` means the guess is too low.
+    fn answer(&mut self, guess: u32) -> Ordering;
+
+    /// The secret, or for a host that never picked one, a number that fits every
+    /// answer so far (`None` if there is no such number).
+    fn reveal(&self) -> Option<u32>;
+
+    /// A hint about the secret. Hosts without a real secret refuse by default.
+    fn hint(&self, _kind: HintKind, _state: &GameState) -> Result<String, HintError> {
+        Err(HintError::NoSecret)
+    }
+}
+
+/// What a player wants to do next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Guess(u32),
+    Quit,
+    // stop now and carry on later, see save.rs
+    Save,
+    // ask for a hint, which costs points if the game is won
+    Hint(HintKind),
+}
+
+/// The side of the game that guesses.
+pub trait Player {
+    fn next_action(&mut self, state: &GameState) -> Action;
+
+    // called after every counted guess, players that learn from the answers hook in here
+    fn observe(&mut self, _guess: u32, _verdict: Verdict, _state: &GameState) {}
+
+    // called when the game refused a guess, e.g. because it is outside the range
+    fn rejected(&mut self, _error: &GuessError) {}
+
+    // called with the hint text, or why there was none
+    fn hinted(&mut self, _kind: HintKind, _hint: &Result<String, HintError>) {}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessError {
+    OutOfRange { guess: u32, low: u32, high: u32 },
+    GameOver,
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuessError::OutOfRange { guess, low, high } => {
+                write!(f, "{} is outside the range {}-{}", guess, low, high)
+            }
+            GuessError::GameOver => write!(f, "the game is already over"),
+        }
+    }
+}
+
+impl std::error::Error for GuessError {}
+
+impl GuessError {
+    /// The error in the player's language, `Display` stays English for logs and the APIs.
+    pub fn localised(&self) -> String {
+        match self {
+            GuessError::OutOfRange { guess, low, high } => i18n::message(
+                "out-of-range",
+                &[("guess", guess), ("low", low), ("high", high)],
+            ),
+            GuessError::GameOver => i18n::text("game-over"),
+        }
+    }
+}
+
+/// Everything that happened in one game so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameState {
+    rules: Rules,
+    history: Vec<(u32, Verdict)>,
+    allowed_hints: Vec<HintKind>,
+    hints: Vec<HintKind>,
+}
+
+impl GameState {
+    /// A new game without hints.
+    pub fn new(rules: Rules) -> Self {
+        GameState {
+            rules,
+            history: Vec::new(),
+            allowed_hints: Vec::new(),
+            hints: Vec::new(),
+        }
+    }
+
+    pub fn with_hints(mut self, allowed: &[HintKind]) -> Self {
+        self.allowed_hints = allowed.to_vec();
+        self
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Every counted guess with its verdict, oldest first.
+    pub fn history(&self) -> &[(u32, Verdict)] {
+        &self.history
+    }
+
+    pub fn allowed_hints(&self) -> &[HintKind] {
+        &self.allowed_hints
+    }
+
+    /// Every hint that was given, oldest first.
+    pub fn hints(&self) -> &[HintKind] {
+        &self.hints
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    pub fn attempts_left(&self) -> u32 {
+        self.rules.max_attempts.saturating_sub(self.attempts())
+    }
+
+    pub fn last_verdict(&self) -> Option<Verdict> {
+        self.history.last().map(|(_, verdict)| *verdict)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.last_verdict()
+            .is_some_and(|verdict| verdict.is_final())
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.last_verdict() == Some(Verdict::Correct)
+    }
+
+    /// Asks the host about one guess and records the verdict.
+    pub fn guess(&mut self, host: &mut dyn Host, guess: u32) -> Result<Verdict, GuessError> {
+        self.check(guess)?;
+        let verdict = match host.answer(guess) {
+            Ordering::Equal => Verdict::Correct,
+            // a wrong guess with the last attempt ends the game
+            _ if self.attempts() + 1 >= self.rules.max_attempts => Verdict::OutOfAttempts,
+            Ordering::Less => Verdict::TooLow,
+            Ordering::Greater => Verdict::TooHigh,
+        };
+        self.history.push((guess, verdict));
+        Ok(verdict)
+    }
+
+    // what every guess has to pass, on time or not: a mistyped guess costs nothing
+    fn check(&self, guess: u32) -> Result<(), GuessError> {
+        if self.is_over() {
+            return Err(GuessError::GameOver);
+        }
+        if !self.rules.contains(guess) {
+            return Err(GuessError::OutOfRange {
+                guess,
+                low: self.rules.low,
+                high: self.rules.high,
+            });
+        }
+        Ok(())
+    }
+
+    /// Asks the host for a hint and records it if one was given.
+    pub fn hint(&mut self, host: &dyn Host, kind: HintKind) -> Result<String, HintError> {
+        if self.is_over() {
+            return Err(HintError::GameOver);
+        }
+        if !self.allowed_hints.contains(&kind) {
+            return Err(HintError::NotAllowed(kind));
+        }
+        let hint = host.hint(kind, self)?;
+        self.hints.push(kind);
+        Ok(hint)
+    }
+
+    // a hint from a transcript, it was allowed and given when the game was played
+    pub(crate) fn record_hint(&mut self, kind: HintKind) {
+        self.hints.push(kind);
+    }
+
+    /// Records a guess that came in after the time limit, which ends the game.
+    pub fn time_up(&mut self, guess: u32) -> Result<Verdict, GuessError> {
+        self.check(guess)?;
+        self.history.push((guess, Verdict::OutOfTime));
+        Ok(Verdict::OutOfTime)
+    }
+
+    /// Records a guess that came in after its countdown ran out. The host is not asked,
+    /// the attempt is lost like a wrong guess and on the last attempt the game is over.
+    pub fn too_slow(&mut self, guess: u32) -> Result<Verdict, GuessError> {
+        self.check(guess)?;
+        if self.attempts() + 1 >= self.rules.max_attempts {
+            return self.time_up(guess);
+        }
+        self.history.push((guess, Verdict::TooSlow));
+        Ok(Verdict::TooSlow)
+    }
+}
+
+/// How a game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Won { attempts: u32 },
+    Lost { attempts: u32 },
+    Quit { attempts: u32 },
+    Saved { attempts: u32 },
+}
+
+/// Runs the game loop until the game is over or the player quits.
+pub fn play(state: &mut GameState, host: &mut dyn Host, player: &mut dyn Player) -> Outcome {
+    play_from(state, host, player, Duration::ZERO)
+}
+
+/// `play` for a game that was already going for `earlier` before it was saved,
+/// that time counts against the time limit too.
+pub fn play_from(
+    state: &mut GameState,
+    host: &mut dyn Host,
+    player: &mut dyn Player,
+    earlier: Duration,
+) -> Outcome {
+    play_timed(state, host, player, earlier, &SystemClock::new())
+}
+
+/// `play_from` with the time read from `clock`, so tests can run the limits
+/// without waiting for them.
+pub fn play_timed(
+    state: &mut GameState,
+    host: &mut dyn Host,
+    player: &mut dyn Player,
+    earlier: Duration,
+    clock: &dyn Clock,
+) -> Outcome {
+    let started = clock.now();
+    // when the countdown for the next guess started
+    let mut asked = started;
+    loop {
+        let attempts = state.attempts();
+        if state.is_won() {
+            return Outcome::Won { attempts };
+        }
+        if state.is_over() {
+            return Outcome::Lost { attempts };
+        }
+
+        match player.next_action(state) {
+            Action::Quit => return Outcome::Quit { attempts },
+            Action::Save => return Outcome::Saved { attempts },
+            Action::Hint(kind) => {
+                let hint = state.hint(host, kind);
+                player.hinted(kind, &hint);
+            }
+            Action::Guess(guess) => {
+                let now = clock.now();
+                let rules = state.rules();
+                let late = rules
+                    .time_limit
+                    .is_some_and(|limit| earlier + (now - started) > limit);
+                let slow = rules.guess_limit.is_some_and(|limit| now - asked > limit);
+                let result = if late {
+                    state.time_up(guess)
+                } else if slow {
+                    state.too_slow(guess)
+                } else {
+                    state.guess(host, guess)
+                };
+                match result {
+                    Ok(verdict) => {
+                        player.observe(guess, verdict, state);
+                        // the next countdown starts once the player has the answer
+                        asked = clock.now();
+                    }
+                    Err(error) => player.rejected(&error),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,207 @@
+// the kinds of hints a game can allow, and what each one costs
+//
+// a hint only needs the secret and the game so far, so adding one is a new variant
+// here with its name, cost and text. the game loop and the players only ever pass
+// a `HintKind` around.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::game::GameState;
+use crate::i18n;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HintKind {
+    // is the secret odd or even
+    Parity,
+    // is the secret within some distance of the last guess
+    Within,
+    // one digit of the secret
+    Digit,
+    // is the last guess closer than the one before
+    HotCold,
+}
+
+impl HintKind {
+    pub const ALL: [HintKind; 4] = [
+        HintKind::Parity,
+        HintKind::Within,
+        HintKind::Digit,
+        HintKind::HotCold,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HintKind::Parity => "parity",
+            HintKind::Within => "within",
+            HintKind::Digit => "digit",
+            HintKind::HotCold => "hot-cold",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<HintKind> {
+        HintKind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// The points a hint takes off the score of a won game.
+    pub fn cost(&self) -> u32 {
+        match self {
+            HintKind::Parity => 100,
+            HintKind::Within => 150,
+            HintKind::Digit => 200,
+            HintKind::HotCold => 50,
+        }
+    }
+
+    /// The hint about `secret`, or why it can't be given yet.
+    pub fn give(&self, secret: u32, state: &GameState) -> Result<String, String> {
+        let rules = state.rules();
+        let last = state.history().last().map(|(guess, _)| *guess);
+        match self {
+            HintKind::Parity => {
+                if secret.is_multiple_of(2) {
+                    Ok(i18n::text("hint-even"))
+                } else {
+                    Ok(i18n::text("hint-odd"))
+                }
+            }
+            HintKind::Within => {
+                let last = last.ok_or_else(|| i18n::text("hint-needs-guess"))?;
+                let distance = within_distance(rules.size());
+                let id = if last.abs_diff(secret) <= distance {
+                    "hint-within"
+                } else {
+                    "hint-not-within"
+                };
+                Ok(i18n::message(
+                    id,
+                    &[("guess", &last), ("distance", &distance)],
+                ))
+            }
+            HintKind::Digit => {
+                // digits are revealed from the right, one more every time
+                let place = state
+                    .hints()
+                    .iter()
+                    .filter(|kind| **kind == HintKind::Digit)
+                    .count() as u32;
+                if place >= digits(rules.high) {
+                    return Err(i18n::text("hint-no-digits"));
+                }
+                let digit = secret / 10u32.pow(place) % 10;
+                Ok(i18n::message(
+                    "hint-digit",
+                    &[("place", &place_name(place)), ("digit", &digit)],
+                ))
+            }
+            HintKind::HotCold => {
+                let history = state.history();
+                let (last, _) = history
+                    .last()
+                    .ok_or_else(|| i18n::text("hint-needs-guess"))?;
+                let distance = last.abs_diff(secret);
+                let temperature = i18n::text(temperature(distance, rules.size()));
+                let [.., (before, _), _] = history else {
+                    return Ok(i18n::message(
+                        "hint-temperature",
+                        &[("guess", last), ("temperature", &temperature)],
+                    ));
+                };
+                let id = match distance.cmp(&before.abs_diff(secret)) {
+                    Ordering::Less => "hint-warmer",
+                    Ordering::Greater => "hint-colder",
+                    Ordering::Equal => "hint-as-warm",
+                };
+                Ok(i18n::message(
+                    id,
+                    &[
+                        ("guess", last),
+                        ("temperature", &temperature),
+                        ("before", before),
+                    ],
+                ))
+            }
+        }
+    }
+}
+
+impl fmt::Display for HintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Why a hint was not given. A refused hint costs nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HintError {
+    NotAllowed(HintKind),
+    // the host has no secret to give hints about
+    NoSecret,
+    NotYet(String),
+    GameOver,
+}
+
+impl fmt::Display for HintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HintError::NotAllowed(kind) => {
+                write!(f, "the {} hint is not allowed in this game", kind)
+            }
+            HintError::NoSecret => write!(f, "this host gives no hints"),
+            HintError::NotYet(message) => write!(f, "{}", message),
+            HintError::GameOver => write!(f, "the game is already over"),
+        }
+    }
+}
+
+impl std::error::Error for HintError {}
+
+impl HintError {
+    /// The error in the player's language, `Display` stays English for logs and the APIs.
+    pub fn localised(&self) -> String {
+        match self {
+            HintError::NotAllowed(kind) => i18n::message("hint-not-allowed", &[("hint", kind)]),
+            HintError::NoSecret => i18n::text("hint-no-secret"),
+            // already in the player's language, `give` wrote it
+            HintError::NotYet(message) => message.clone(),
+            HintError::GameOver => i18n::text("game-over"),
+        }
+    }
+}
+
+// a tenth of the range, so the hint means the same on every difficulty
+fn within_distance(size: u64) -> u32 {
+    size.div_ceil(10).clamp(1, u32::MAX as u64) as u32
+}
+
+fn digits(n: u32) -> u32 {
+    n.checked_ilog10().unwrap_or(0) + 1
+}
+
+fn place_name(place: u32) -> String {
+    match place {
+        0 => i18n::text("place-ones"),
+        1 => i18n::text("place-tens"),
+        2 => i18n::text("place-hundreds"),
+        3 => i18n::text("place-thousands"),
+        _ => i18n::message("place-power", &[("power", &place)]),
+    }
+}
+
+// the id of the word for how close the guess is
+fn temperature(distance: u32, size: u64) -> &'static str {
+    let share = distance as f64 / size as f64;
+    if distance == 0 {
+        "temperature-right-on"
+    } else if share <= 0.01 {
+        "temperature-burning"
+    } else if share <= 0.05 {
+        "temperature-hot"
+    } else if share <= 0.15 {
+        "temperature-warm"
+    } else if share <= 0.30 {
+        "temperature-cool"
+    } else {
+        "temperature-cold"
+    }
+}
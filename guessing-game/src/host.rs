@@ -0,0 +1,164 @@
+// the hosts that come with the game: the plain one picks a secret once and answers
+// honestly, the adversary never picks one at all (the liar has its own module)
+
+use std::cmp::Ordering;
+
+use crate::game::{GameState, Host, Rules};
+use crate::hints::{HintError, HintKind};
+use crate::liar::LiarHost;
+use crate::rng::Rng;
+use crate::solvers::Bounds;
+
+/// Which host runs a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostKind {
+    Fair,
+    Evil,
+    // lies about at most this many answers
+    Liar(u32),
+}
+
+impl HostKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HostKind::Fair => "fair",
+            HostKind::Evil => "evil",
+            HostKind::Liar(_) => "liar",
+        }
+    }
+
+    /// The hosts that can be picked by name, the liar is picked by its number of lies.
+    pub fn parse(name: &str) -> Option<HostKind> {
+        match name {
+            "fair" => Some(HostKind::Fair),
+            "evil" => Some(HostKind::Evil),
+            _ => None,
+        }
+    }
+
+    /// A host for one game. The adversary has no secret, so it ignores the seed.
+    pub fn build(&self, rules: &Rules, seed: u64) -> Box<dyn Host> {
+        match self {
+            HostKind::Fair => Box::new(SecretHost::seeded(rules, seed)),
+            HostKind::Evil => Box::new(AdversaryHost::new(rules)),
+            HostKind::Liar(lies) => Box::new(LiarHost::seeded(rules, seed, *lies)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretHost {
+    secret: u32,
+}
+
+impl SecretHost {
+    pub fn new(secret: u32) -> Self {
+        SecretHost { secret }
+    }
+
+    /// A host whose secret is drawn from `rng` inside the rules' range.
+    pub fn random(rules: &Rules, rng: &mut Rng) -> Self {
+        SecretHost::new(rng.range(rules.low, rules.high))
+    }
+
+    /// The same seed and rules always give the same secret, this is what replays rely on.
+    pub fn seeded(rules: &Rules, seed: u64) -> Self {
+        SecretHost::random(rules, &mut Rng::new(seed))
+    }
+
+    pub fn secret(&self) -> u32 {
+        self.secret
+    }
+}
+
+impl Host for SecretHost {
+    fn answer(&mut self, guess: u32) -> Ordering {
+        guess.cmp(&self.secret)
+    }
+
+    fn reveal(&self) -> Option<u32> {
+        Some(self.secret)
+    }
+
+    fn hint(&self, kind: HintKind, state: &GameState) -> Result<String, HintError> {
+        kind.give(self.secret, state).map_err(HintError::NotYet)
+    }
+}
+
+/// The "evil" host: it never commits to a secret.
+///
+/// It keeps the numbers that still fit all of its answers and answers every guess
+/// so that the larger part survives. It only says "correct" once a single number
+/// is left and that number is guessed, so every guesser is pushed to its worst case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdversaryHost {
+    candidates: Bounds,
+    answers: Vec<(u32, Ordering)>,
+}
+
+impl AdversaryHost {
+    pub fn new(rules: &Rules) -> Self {
+        AdversaryHost {
+            candidates: Bounds::from_rules(rules),
+            answers: Vec::new(),
+        }
+    }
+
+    /// The numbers that still fit every answer.
+    pub fn candidates(&self) -> Bounds {
+        self.candidates
+    }
+
+    /// Every answer so far, as `guess.cmp(secret)`.
+    pub fn answers(&self) -> &[(u32, Ordering)] {
+        &self.answers
+    }
+
+    /// A number that fits every answer given so far, checked against each answer
+    /// rather than taken on trust from the bookkeeping.
+    pub fn witness(&self) -> Option<u32> {
+        if self.candidates.is_empty() {
+            return None;
+        }
+        let secret = self.candidates.at(0);
+        let fits = self
+            .answers
+            .iter()
+            .all(|(guess, answer)| guess.cmp(&secret) == *answer);
+        fits.then_some(secret)
+    }
+}
+
+impl Host for AdversaryHost {
+    fn answer(&mut self, guess: u32) -> Ordering {
+        let guess_at = guess as u64;
+        let candidates = &mut self.candidates;
+
+        let answer = if guess_at < candidates.low {
+            Ordering::Less
+        } else if guess_at >= candidates.high {
+            Ordering::Greater
+        } else {
+            let below = guess_at - candidates.low;
+            let above = candidates.high - 1 - guess_at;
+            if below == 0 && above == 0 {
+                Ordering::Equal
+            } else if below >= above {
+                // keep the numbers below the guess, they are at least as many
+                candidates.high = guess_at;
+                Ordering::Greater
+            } else {
+                candidates.low = guess_at + 1;
+                Ordering::Less
+            }
+        };
+
+        self.answers.push((guess, answer));
+        answer
+    }
+
+    // there is no secret to show, but any number that fits all answers is as good as one
+    fn reveal(&self) -> Option<u32> {
+        self.witness()
+    }
+}
@@ -0,0 +1,415 @@
+// a JSON API over plain HTTP/1.1, so a web page can run games without a terminal
+//
+//   POST /games                 starts a game, the body may set {"min", "max", "attempts", "seed"}
+//   POST /games/{id}/guesses    takes {"guess": n}
+//   GET  /games/{id}            the state of a game
+//
+// every answer is a JSON object, errors look like {"error": "..."}. one request per
+// connection keeps the server simple, and games nobody touched for a while are dropped.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::hash::{BuildHasher, RandomState};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::game::{GameState, GuessError, Rules};
+use crate::host::SecretHost;
+use crate::json::Json;
+use crate::rng::Rng;
+
+/// How long a game is kept without any request for it, unless told otherwise.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+// bodies are tiny JSON objects, anything bigger is a mistake or an attack
+const MAX_BODY: usize = 16 * 1024;
+const MAX_HEADER_LINES: usize = 100;
+// the longest request line or header line that is read, so no line can fill the memory
+const MAX_LINE: u64 = 8 * 1024;
+// what is still read from a refused request before closing, see `serve`
+const MAX_DRAIN: u64 = 64 * 1024;
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+// a client that opens a connection and sends nothing must not hold a thread forever
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An HTTP status with the JSON body that goes with it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Json,
+    // extra headers, like Location or Allow
+    pub headers: Vec<(&'static str, String)>,
+}
+
+impl Response {
+    fn json(status: u16, body: Json) -> Response {
+        Response {
+            status,
+            body,
+            headers: Vec::new(),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Response {
+        Response::json(
+            status,
+            Json::Object(vec![("error".to_string(), Json::String(message.into()))]),
+        )
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            201 => "Created",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            431 => "Request Header Fields Too Large",
+            _ => "Internal Server Error",
+        }
+    }
+
+    fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+        let body = self.body.to_string();
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, self.reason());
+        let _ = write!(
+            head,
+            "Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            body.len()
+        );
+        for (name, value) in &self.headers {
+            let _ = write!(head, "{}: {}\r\n", name, value);
+        }
+        head.push_str("\r\n");
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(body.as_bytes())?;
+        stream.flush()
+    }
+}
+
+struct Game {
+    state: GameState,
+    host: SecretHost,
+    last_used: Instant,
+}
+
+/// The games of one server, kept apart from the sockets so they can be driven directly.
+pub struct Games {
+    rules: Rules,
+    idle_timeout: Duration,
+    // anyone who can guess an id can play that game, so ids are not drawn from Rng
+    // (one output of it gives away all the next ones). they are a counter hashed with
+    // SipHash under a key the OS picked at random, 128 bits so guessing is hopeless too
+    id_key: RandomState,
+    issued: u64,
+    games: HashMap<String, Game>,
+}
+
+impl Games {
+    pub fn new(rules: Rules, idle_timeout: Duration) -> Self {
+        Games {
+            rules,
+            idle_timeout,
+            id_key: RandomState::new(),
+            issued: 0,
+            games: HashMap::new(),
+        }
+    }
+
+    fn next_id(&mut self) -> String {
+        self.issued += 1;
+        format!(
+            "{:016x}{:016x}",
+            self.id_key.hash_one((self.issued, 0u8)),
+            self.id_key.hash_one((self.issued, 1u8))
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    /// Drops the games that were idle for too long.
+    pub fn expire(&mut self, now: Instant) {
+        let timeout = self.idle_timeout;
+        self.games
+            .retain(|_, game| now.duration_since(game.last_used) < timeout);
+    }
+
+    /// Answers one request. `path` is without the query string.
+    pub fn handle(&mut self, method: &str, path: &str, body: &str, now: Instant) -> Response {
+        self.expire(now);
+
+        let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match (method, parts.as_slice()) {
+            ("POST", ["games"]) => self.create(body, now),
+            ("GET", ["games", id]) => match self.games.get_mut(*id) {
+                Some(game) => {
+                    game.last_used = now;
+                    Response::json(200, describe(id, game))
+                }
+                None => not_found(id),
+            },
+            ("POST", ["games", id, "guesses"]) => self.guess(id, body, now),
+            (_, ["games"]) => method_not_allowed("POST"),
+            (_, ["games", _]) => method_not_allowed("GET"),
+            (_, ["games", _, "guesses"]) => method_not_allowed("POST"),
+            _ => Response::error(404, format!("no such path {}", path)),
+        }
+    }
+
+    fn create(&mut self, body: &str, now: Instant) -> Response {
+        // an empty body means the server's own rules
+        let options = if body.trim().is_empty() {
+            Json::Object(Vec::new())
+        } else {
+            match Json::parse(body) {
+                Ok(options @ Json::Object(_)) => options,
+                Ok(_) => return Response::error(400, "the body must be a JSON object"),
+                Err(error) => return Response::error(400, error.to_string()),
+            }
+        };
+
+        let mut rules = self.rules;
+        for (field, target) in [
+            ("min", &mut rules.low),
+            ("max", &mut rules.high),
+            ("attempts", &mut rules.max_attempts),
+        ] {
+            match options.get(field) {
+                None => {}
+                Some(value) => match value.as_u32() {
+                    Some(number) => *target = number,
+                    None => {
+                        return Response::error(
+                            400,
+                            format!("\"{}\" must be a whole number", field),
+                        );
+                    }
+                },
+            }
+        }
+        if let Err(message) = rules.validate() {
+            return Response::error(422, message);
+        }
+        let seed = match options.get("seed") {
+            None => Rng::random_seed(),
+            Some(value) => match value.as_u64() {
+                Some(seed) => seed,
+                None => return Response::error(400, "\"seed\" must be a whole number"),
+            },
+        };
+
+        let id = self.next_id();
+        let game = Game {
+            state: GameState::new(rules),
+            host: SecretHost::seeded(&rules, seed),
+            last_used: now,
+        };
+        let mut response = Response::json(201, describe(&id, &game));
+        response
+            .headers
+            .push(("Location", format!("/games/{}", id)));
+        self.games.insert(id, game);
+        response
+    }
+
+    fn guess(&mut self, id: &str, body: &str, now: Instant) -> Response {
+        let Some(game) = self.games.get_mut(id) else {
+            return not_found(id);
+        };
+        game.last_used = now;
+
+        let guess = match Json::parse(body) {
+            Ok(request) => match request.get("guess").map(Json::as_u32) {
+                Some(Some(guess)) => guess,
+                Some(None) => return Response::error(400, "\"guess\" must be a whole number"),
+                None => return Response::error(400, "the body needs a \"guess\" field"),
+            },
+            Err(error) => return Response::error(400, error.to_string()),
+        };
+
+        match game.state.guess(&mut game.host, guess) {
+            Ok(_) => Response::json(200, describe(id, game)),
+            Err(error @ GuessError::OutOfRange { .. }) => Response::error(422, error.to_string()),
+            Err(error @ GuessError::GameOver) => Response::error(409, error.to_string()),
+        }
+    }
+}
+
+fn not_found(id: &str) -> Response {
+    Response::error(404, format!("no game {}, it may have expired", id))
+}
+
+fn method_not_allowed(allowed: &'static str) -> Response {
+    let mut response = Response::error(405, format!("only {} is allowed here", allowed));
+    response.headers.push(("Allow", allowed.to_string()));
+    response
+}
+
+// the state as the API shows it, the secret only once the game is over
+fn describe(id: &str, game: &Game) -> Json {
+    let state = &game.state;
+    let rules = state.rules();
+    let status = if state.is_won() {
+        "won"
+    } else if state.is_over() {
+        "lost"
+    } else {
+        "playing"
+    };
+    let guesses = state
+        .history()
+        .iter()
+        .map(|(guess, verdict)| {
+            Json::Object(vec![
+                ("guess".to_string(), (*guess).into()),
+                ("verdict".to_string(), verdict.name().into()),
+            ])
+        })
+        .collect();
+    let last_verdict = state.last_verdict().map(|verdict| verdict.name());
+    let secret = state.is_over().then(|| game.host.secret());
+
+    Json::Object(vec![
+        ("id".to_string(), id.into()),
+        ("status".to_string(), status.into()),
+        ("min".to_string(), rules.low.into()),
+        ("max".to_string(), rules.high.into()),
+        ("attempts".to_string(), state.attempts().into()),
+        ("attempts_left".to_string(), state.attempts_left().into()),
+        ("last_verdict".to_string(), last_verdict.into()),
+        ("guesses".to_string(), Json::Array(guesses)),
+        ("secret".to_string(), secret.into()),
+    ])
+}
+
+/// The HTTP server around `Games`.
+pub struct HttpServer {
+    listener: TcpListener,
+    games: Arc<Mutex<Games>>,
+}
+
+impl HttpServer {
+    pub fn bind(
+        addr: impl ToSocketAddrs,
+        rules: Rules,
+        idle_timeout: Duration,
+    ) -> io::Result<HttpServer> {
+        Ok(HttpServer {
+            listener: TcpListener::bind(addr)?,
+            games: Arc::new(Mutex::new(Games::new(rules, idle_timeout))),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts connections until the listener fails, one thread each.
+    pub fn run(self) -> io::Result<()> {
+        for stream in self.listener.incoming() {
+            let stream = stream?;
+            let games = Arc::clone(&self.games);
+            thread::spawn(move || {
+                // a client that hangs up is not the server's problem
+                let _ = serve(stream, &games);
+            });
+        }
+        Ok(())
+    }
+}
+
+fn serve(stream: TcpStream, games: &Mutex<Games>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    match read_request(&mut reader) {
+        Ok((method, path, body)) => {
+            let response = games
+                .lock()
+                .unwrap()
+                .handle(&method, &path, &body, Instant::now());
+            response.write_to(&mut writer)
+        }
+        Err(response) => {
+            response.write_to(&mut writer)?;
+            // the client may still be sending the rest of the request. closing with that
+            // unread would reset the connection and could throw away the answer, so a
+            // little more is read first
+            writer.shutdown(Shutdown::Write)?;
+            reader.get_ref().set_read_timeout(Some(DRAIN_TIMEOUT))?;
+            io::copy(&mut reader.take(MAX_DRAIN), &mut io::sink())?;
+            Ok(())
+        }
+    }
+}
+
+// one line of at most MAX_LINE bytes, None when it is longer than that
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    reader.by_ref().take(MAX_LINE).read_line(&mut line)?;
+    if line.len() as u64 == MAX_LINE && !line.ends_with('\n') {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+// the method, the path without a query string and the body, or the response to send
+// for a request that can't be read
+fn read_request(reader: &mut impl BufRead) -> Result<(String, String, String), Response> {
+    let bad = |message: &str| Response::error(400, message);
+    let line = read_line(reader)
+        .map_err(|_| bad("could not read the request"))?
+        .ok_or_else(|| bad("the request line is too long"))?;
+    let mut words = line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (words.next(), words.next(), words.next())
+    else {
+        return Err(bad("the request line must be METHOD PATH HTTP/1.1"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(bad("only HTTP/1.x is spoken here"));
+    }
+    let path = target.split('?').next().unwrap_or_default().to_string();
+    let method = method.to_string();
+
+    let mut length = 0;
+    for _ in 0..MAX_HEADER_LINES {
+        let header = read_line(reader)
+            .map_err(|_| bad("could not read the headers"))?
+            .ok_or_else(|| Response::error(431, "a header line is too long"))?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            let mut body = vec![0; length];
+            reader
+                .read_exact(&mut body)
+                .map_err(|_| bad("the body is shorter than its Content-Length"))?;
+            let body = String::from_utf8(body).map_err(|_| bad("the body is not UTF-8"))?;
+            return Ok((method, path, body));
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| bad("Content-Length is not a number"))?;
+                if length > MAX_BODY {
+                    return Err(Response::error(413, "the body is too large"));
+                }
+            } else if name.trim().eq_ignore_ascii_case("transfer-encoding") {
+                return Err(bad("send the body with a Content-Length"));
+            }
+        }
+    }
+    Err(Response::error(431, "too many headers"))
+}
@@ -0,0 +1,182 @@
+// what the game says to a player, in the player's language
+//
+// every message has an id. the locale files in locales/ map ids to text, one
+// `id = text` per line with `{name}` where a value goes in. they are built into the
+// binary and read once at startup, a message the chosen locale doesn't have comes
+// out in English. tests/i18n.rs checks that every locale has every id.
+//
+// error messages for the operator (bad options, files that can't be read) and the
+// tables of --stats, --bench and --tournament stay in English.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// The locales that come with the game, English first.
+pub const LOCALES: [&str; 4] = ["en", "es", "de", "hi"];
+
+fn source(locale: &str) -> Option<&'static str> {
+    match locale {
+        "en" => Some(include_str!("../locales/en.txt")),
+        "es" => Some(include_str!("../locales/es.txt")),
+        "de" => Some(include_str!("../locales/de.txt")),
+        "hi" => Some(include_str!("../locales/hi.txt")),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CatalogError {}
+
+/// The messages of one locale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Catalog {
+    locale: String,
+    messages: BTreeMap<String, String>,
+}
+
+impl Catalog {
+    pub fn parse(locale: &str, text: &str) -> Result<Catalog, CatalogError> {
+        let mut messages = BTreeMap::new();
+        for (index, line) in text.lines().enumerate() {
+            let error = |message: String| CatalogError {
+                line: index + 1,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (id, message) = line
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected `id = text`, got '{}'", line)))?;
+            let id = id.trim();
+            if id.is_empty() {
+                return Err(error("the id is missing".to_string()));
+            }
+            if messages
+                .insert(id.to_string(), message.trim().to_string())
+                .is_some()
+            {
+                return Err(error(format!("'{}' is defined twice", id)));
+            }
+        }
+        Ok(Catalog {
+            locale: locale.to_string(),
+            messages,
+        })
+    }
+
+    /// One of the `LOCALES`, `None` for any other name.
+    pub fn builtin(locale: &str) -> Option<Catalog> {
+        let text = source(locale)?;
+        // the files are part of the build, a broken one is a bug, not a user error
+        Some(
+            Catalog::parse(locale, text)
+                .unwrap_or_else(|error| panic!("locales/{}.txt is broken: {}", locale, error)),
+        )
+    }
+
+    pub fn english() -> &'static Catalog {
+        static ENGLISH: OnceLock<Catalog> = OnceLock::new();
+        ENGLISH.get_or_init(|| Catalog::builtin("en").expect("English is built in"))
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.messages.keys().map(String::as_str)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self.messages.get(id).map(String::as_str)
+    }
+}
+
+/// The built-in locale for a `LANG` value like `de_DE.UTF-8`, if there is one.
+pub fn locale_from_env(lang: &str) -> Option<&'static str> {
+    let language = lang.split(['_', '.', '@']).next()?.to_ascii_lowercase();
+    LOCALES.into_iter().find(|locale| *locale == language)
+}
+
+static CURRENT: OnceLock<Catalog> = OnceLock::new();
+
+/// Makes `catalog` the language of every message from now on. Only the first call
+/// counts, the language is picked once at startup.
+pub fn install(catalog: Catalog) {
+    let _ = CURRENT.set(catalog);
+}
+
+/// The installed catalog, English until one is installed.
+pub fn current() -> &'static Catalog {
+    CURRENT.get().unwrap_or_else(|| Catalog::english())
+}
+
+/// The message `id` in the current language.
+pub fn text(id: &str) -> String {
+    message(id, &[])
+}
+
+/// The message `id` in the current language with its `{name}`s filled in.
+///
+/// A message the locale doesn't have comes from English, an id nobody has is
+/// shown as it is so the gap is easy to spot.
+pub fn message(id: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let template = current()
+        .get(id)
+        .or_else(|| Catalog::english().get(id))
+        .unwrap_or(id);
+    fill(template, args)
+}
+
+/// Puts the values into the `{name}`s of `template`. Unknown names stay as they are.
+pub fn fill(template: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let value = after.find('}').and_then(|close| {
+            let name = &after[..close];
+            let (_, value) = args.iter().find(|(arg, _)| *arg == name)?;
+            Some((value.to_string(), close))
+        });
+        match value {
+            Some((value, close)) => {
+                out.push_str(&value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The `{name}`s a template uses, for checking that translations keep them.
+pub fn placeholders(template: &str) -> Vec<&str> {
+    let mut names: Vec<&str> = template
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
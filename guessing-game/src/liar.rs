@@ -0,0 +1,236 @@
+// liar mode, also known as Ulam's game: the host may lie up to K times when it
+// says "too high" or "too low" (it never lies about a correct guess)
+//
+// the solver can't throw numbers away after one answer any more. it counts for
+// every number how many answers it would make a lie, and only drops it once that is
+// more than K. to pick a guess it uses Berlekamp's "volume": a number with `e` lies
+// against it and `q` questions left could still be told apart in sum(C(q, j), j <= K - e)
+// ways, and a good guess splits the total volume of all numbers as evenly as possible.
+
+use std::cmp::Ordering;
+
+use crate::game::{Action, GameState, Host, Player, Rules, Verdict};
+use crate::hints::{HintError, HintKind};
+use crate::host::SecretHost;
+use crate::rng::Rng;
+
+/// The largest range the liar solver keeps a counter for every number of.
+pub const MAX_RANGE: u64 = 1_000_000;
+
+// the chance that the host lies on any one answer, as long as it has lies left
+const LIE_CHANCE: f64 = 0.3;
+
+/// A host with a secret that lies about at most `max_lies` answers.
+#[derive(Debug, Clone)]
+pub struct LiarHost {
+    secret: u32,
+    max_lies: u32,
+    rng: Rng,
+    // 1-based turns that got a lie
+    lies: Vec<usize>,
+    turn: usize,
+}
+
+impl LiarHost {
+    /// The secret comes from `seed` the same way as for the fair host, the lies
+    /// from a separate stream so they don't change which secret a seed gives.
+    pub fn seeded(rules: &Rules, seed: u64, max_lies: u32) -> Self {
+        LiarHost {
+            secret: SecretHost::seeded(rules, seed).secret(),
+            max_lies,
+            rng: Rng::new(seed ^ 0x5EED_11E5_5EED_11E5),
+            lies: Vec::new(),
+            turn: 0,
+        }
+    }
+
+    pub fn secret(&self) -> u32 {
+        self.secret
+    }
+
+    /// The turns the host lied on, as the host itself remembers them.
+    pub fn lies(&self) -> &[usize] {
+        &self.lies
+    }
+}
+
+impl Host for LiarHost {
+    fn answer(&mut self, guess: u32) -> Ordering {
+        self.turn += 1;
+        let truth = guess.cmp(&self.secret);
+        let lies_left = self.lies.len() < self.max_lies as usize;
+        // one random draw per answer, so the lies only depend on the seed and the turn
+        let wants_to_lie = (self.rng.next_u64() as f64 / u64::MAX as f64) < LIE_CHANCE;
+        if truth != Ordering::Equal && lies_left && wants_to_lie {
+            self.lies.push(self.turn);
+            truth.reverse()
+        } else {
+            truth
+        }
+    }
+
+    fn reveal(&self) -> Option<u32> {
+        Some(self.secret)
+    }
+
+    // the lies are about verdicts, hints are always true
+    fn hint(&self, kind: HintKind, state: &GameState) -> Result<String, HintError> {
+        kind.give(self.secret, state).map_err(HintError::NotYet)
+    }
+}
+
+/// The turns (1-based) whose verdict does not fit `secret`, worked out from the
+/// verdicts alone. Used at the end of a game to show which answers were lies.
+pub fn find_lies(secret: u32, history: &[(u32, Verdict)]) -> Vec<usize> {
+    history
+        .iter()
+        .enumerate()
+        .filter(|(_, (guess, verdict))| match verdict {
+            Verdict::TooLow => *guess >= secret,
+            Verdict::TooHigh => *guess <= secret,
+            // the last wrong answer does not say which way it was wrong
+            Verdict::Correct | Verdict::OutOfAttempts | Verdict::OutOfTime => false,
+            // the host was never asked
+            Verdict::TooSlow => false,
+        })
+        .map(|(index, _)| index + 1)
+        .collect()
+}
+
+/// Attempts liar mode gives on top of `attempts_needed`. The bound counts the
+/// questions to tell the numbers apart, the game also wants the secret guessed, and
+/// the solver is a greedy one, so it is never quite reached (tests/liar.rs checks
+/// that this is enough).
+pub const SLACK: u32 = 2;
+
+/// Berlekamp's volume bound: the fewest questions that can possibly find one of
+/// `size` numbers when up to `lies` answers are lies. A real game may need a few more.
+pub fn attempts_needed(size: u64, lies: u32) -> u32 {
+    (1..=u32::MAX)
+        .find(|&questions| {
+            let volume = size as f64 * volume(questions as u64, lies as u64);
+            2f64.powi(questions as i32) >= volume
+        })
+        .unwrap_or(u32::MAX)
+}
+
+// sum of C(q, j) for j in 0..=budget, as f64 since it gets large quickly
+fn volume(questions: u64, budget: u64) -> f64 {
+    let mut total = 0.0;
+    let mut choose = 1.0;
+    for j in 0..=budget.min(questions) {
+        total += choose;
+        choose = choose * (questions - j) as f64 / (j + 1) as f64;
+    }
+    total
+}
+
+/// A solver that survives up to `max_lies` lies.
+#[derive(Debug, Clone)]
+pub struct UlamSolver {
+    max_lies: u32,
+    low: u32,
+    // how many answers each number in low.. would make a lie
+    errors: Vec<u32>,
+}
+
+impl UlamSolver {
+    pub fn new(max_lies: u32) -> Self {
+        UlamSolver {
+            max_lies,
+            low: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    /// The numbers that still fit the answers with at most `max_lies` lies.
+    pub fn candidates(&self) -> Vec<u32> {
+        self.errors
+            .iter()
+            .enumerate()
+            .filter(|(_, errors)| **errors <= self.max_lies)
+            .map(|(offset, _)| self.low + offset as u32)
+            .collect()
+    }
+
+    fn weight(&self, errors: u32, questions: u64) -> f64 {
+        if errors > self.max_lies {
+            0.0
+        } else {
+            volume(questions, (self.max_lies - errors) as u64)
+        }
+    }
+}
+
+impl Player for UlamSolver {
+    fn next_action(&mut self, state: &GameState) -> Action {
+        if self.errors.is_empty() {
+            let rules = state.rules();
+            assert!(
+                rules.size() <= MAX_RANGE,
+                "liar mode supports at most {} numbers",
+                MAX_RANGE
+            );
+            self.low = rules.low;
+            self.errors = vec![0; rules.size() as usize];
+        }
+
+        // after this guess there are `questions` answers left to sort things out
+        let questions = state.attempts_left().saturating_sub(1) as u64;
+        let after_lie: Vec<f64> = self
+            .errors
+            .iter()
+            .map(|&errors| self.weight(errors + 1, questions))
+            .collect();
+        let after_truth: Vec<f64> = self
+            .errors
+            .iter()
+            .map(|&errors| self.weight(errors, questions))
+            .collect();
+
+        // "too low" makes every number below the guess one lie worse, "too high" every
+        // number above it, and the guess itself is out either way (the host never lies
+        // about a correct guess). walk through all guesses once with running sums.
+        let total_truth: f64 = after_truth.iter().sum();
+        let total_lie: f64 = after_lie.iter().sum();
+        let mut truth_below = 0.0;
+        let mut lie_below = 0.0;
+        let mut best: Option<(f64, usize)> = None;
+        for offset in 0..self.errors.len() {
+            if self.errors[offset] <= self.max_lies {
+                let truth_above = total_truth - truth_below - after_truth[offset];
+                let lie_above = total_lie - lie_below - after_lie[offset];
+                let if_too_low = lie_below + truth_above;
+                let if_too_high = truth_below + lie_above;
+                let worst = if_too_low.max(if_too_high);
+                if best.is_none_or(|(best_worst, _)| worst < best_worst) {
+                    best = Some((worst, offset));
+                }
+            }
+            truth_below += after_truth[offset];
+            lie_below += after_lie[offset];
+        }
+
+        match best {
+            Some((_, offset)) => Action::Guess(self.low + offset as u32),
+            // every number needs more lies than allowed, the host broke the rules
+            None => Action::Quit,
+        }
+    }
+
+    fn observe(&mut self, guess: u32, verdict: Verdict, _state: &GameState) {
+        let Some(guess_offset) = guess.checked_sub(self.low).map(|offset| offset as usize) else {
+            return;
+        };
+        let out = self.max_lies + 1;
+        for (offset, errors) in self.errors.iter_mut().enumerate() {
+            *errors = match verdict {
+                // a wrong guess can't be the secret, whatever else is a lie
+                Verdict::TooLow | Verdict::TooHigh if offset == guess_offset => out,
+                Verdict::TooLow if offset < guess_offset => *errors + 1,
+                Verdict::TooHigh if offset > guess_offset => *errors + 1,
+                _ => *errors,
+            };
+        }
+    }
+}
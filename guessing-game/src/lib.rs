@@ -0,0 +1,58 @@
+// the guessing game as a library, so the game loop can be driven by a terminal,
+// by a script or by a bot (or by tests) without any of them knowing about each other
+
+pub mod attack;
+pub mod bench;
+pub mod clock;
+pub mod domains;
+pub mod game;
+pub mod hints;
+pub mod host;
+pub mod http;
+pub mod i18n;
+pub mod json;
+pub mod liar;
+pub mod mastermind;
+pub mod players;
+pub mod profiles;
+pub mod report;
+pub mod rng;
+pub mod save;
+pub mod scores;
+pub mod seal;
+pub mod server;
+pub mod solvers;
+pub mod tournament;
+pub mod transcript;
+pub mod tui;
+pub mod wordle;
+
+pub use attack::{AttackScore, TimeAttack};
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use domains::{
+    Bisection, Compass, Date, Domain, DomainGame, Grid, GridSolver, Guesser, Order, Ordered, Point,
+};
+pub use game::{
+    Action, GameState, GuessError, Host, Outcome, Player, Rules, Verdict, play, play_from,
+    play_timed,
+};
+pub use hints::{HintError, HintKind};
+pub use host::{AdversaryHost, HostKind, SecretHost};
+pub use http::HttpServer;
+pub use i18n::Catalog;
+pub use json::Json;
+pub use liar::{LiarHost, UlamSolver};
+pub use mastermind::{Breaker, Code, Feedback, KnuthSolver, MastermindGame};
+pub use players::{HumanPlayer, ScriptError, ScriptedPlayer};
+pub use profiles::{Profile, ProfileError};
+pub use report::{Format, Reporter};
+pub use rng::Rng;
+pub use save::{SaveError, SavedGame};
+pub use scores::{PlayerStats, ScoreBoard, ScoreEntry};
+pub use seal::SaveKey;
+pub use server::{Client, Server};
+pub use solvers::{BinarySearch, Strategy};
+pub use tournament::{Entrant, Standings, Tournament};
+pub use transcript::{ReplayError, Transcript, TranscriptError};
+pub use tui::TuiPlayer;
+pub use wordle::{Dictionary, EntropySolver, Pattern, WordleGame};
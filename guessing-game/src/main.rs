@@ -1,3 +1,1632 @@
+use std::env;
+use std::io::{self, BufRead, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use guessing_game::attack::TIME_ATTACK;
+use guessing_game::bench::{self, bisection_bound};
+use guessing_game::domains::{self, Dates, Floats, HumanGuesser, Letters};
+use guessing_game::http::{self, HttpServer};
+use guessing_game::i18n;
+use guessing_game::liar::{self, find_lies};
+use guessing_game::mastermind::{self, Breaker, HumanBreaker};
+use guessing_game::profiles::{BUILTIN_NAMES, parse_hints};
+use guessing_game::report::{end_event, start_event};
+use guessing_game::scores::{high_scores, player_stats, score};
+use guessing_game::server::{Reply, Request, Room};
+use guessing_game::wordle::{self, HumanWordGuesser, WordGuesser};
+use guessing_game::{
+    Bisection, Catalog, Client, Dictionary, Domain, DomainGame, EntropySolver, Format, GameState,
+    Grid, GridSolver, Guesser, Host, HostKind, HumanPlayer, KnuthSolver, MastermindGame, Outcome,
+    Player, Profile, Reporter, Rng, Rules, SaveKey, SavedGame, ScoreBoard, ScoreEntry,
+    ScriptedPlayer, Server, Strategy, SystemClock, TimeAttack, Tournament, Transcript, TuiPlayer,
+    UlamSolver, Verdict, WordleGame, play_from,
+};
+
+// which game is played
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Number,
+    Mastermind,
+    Wordle,
+    // the games over other domains
+    Float,
+    Date,
+    Letter,
+    Grid,
+}
+
+impl Mode {
+    fn is_domain(&self) -> bool {
+        matches!(self, Mode::Float | Mode::Date | Mode::Letter | Mode::Grid)
+    }
+}
+
+// which kind of player sits at the keyboard
+enum PlayerKind {
+    Human,
+    Bot,
+}
+
+struct Settings {
+    mode: Mode,
+    profile: Profile,
+    code: mastermind::Config,
+    dictionary: Option<Dictionary>,
+    word_guesses: u32,
+    hard: bool,
+    from: Option<String>,
+    to: Option<String>,
+    tolerance: Option<f64>,
+    domain_guesses: u32,
+    player: PlayerKind,
+    strategy: Strategy,
+    host: HostKind,
+    name: Option<String>,
+    seed: Option<u64>,
+    record: Option<PathBuf>,
+    replay: Option<PathBuf>,
+    scores: Option<PathBuf>,
+    stats: bool,
+    bench: Option<u32>,
+    tournament: Option<u32>,
+    threads: Option<usize>,
+    csv: Option<PathBuf>,
+    serve: Option<String>,
+    connect: Option<String>,
+    http: Option<String>,
+    idle_timeout: Duration,
+    room: Room,
+    script: Option<PathBuf>,
+    format: Format,
+    tui: bool,
+    resume: Option<PathBuf>,
+    save: Option<PathBuf>,
+    lang: Option<String>,
+    time_attack: bool,
+}
+
+const USAGE: &str = "usage: guessing-game [--mode number|mastermind|wordle|float|date|letter|grid]
+                     [--difficulty easy|normal|hard] [--rules FILE]
+                     [--min N] [--max N] [--attempts N] [--player human|bot]
+                     [--strategy binary|random|golden|noisy] [--bench GAMES]
+                     [--tournament GAMES] [--threads N] [--csv FILE]
+                     [--host fair|evil] [--lies K] [--pegs N] [--colours N]
+                     [--dict FILE] [--word-length N] [--hard]
+                     [--from VALUE] [--to VALUE] [--tolerance T]
+                     [--name NAME] [--seed N] [--record FILE] [--replay FILE]
+                     [--scores FILE] [--stats]
+                     [--serve ADDR] [--connect ADDR] [--room solo|race]
+                     [--http ADDR] [--idle-timeout SECONDS]
+                     [--script FILE|-] [--format text|json] [--tui]
+                     [--hints parity,within,digit,hot-cold|none]
+                     [--save FILE] [--resume FILE] [--lang en|es|de|hi]
+                     [--countdown SECONDS] [--time-attack]
+
+--countdown is checked when a guess is entered: nothing happens while the
+player waits, a guess entered too late counts as a miss
+
+exit codes: 0 won, 1 lost, 2 bad input (options, script lines, or a script
+that ran out before the game was over)";
+
+// more lies than this make the games very long and the solver's weights huge
+const MAX_LIES: u32 = 10;
+// the usual word game: five letters, six guesses
+const WORD_LENGTH: usize = 5;
+const WORD_GUESSES: u32 = 6;
+// enough to bisect a year of days or a 100x100 grid with a little room
+const DOMAIN_GUESSES: u32 = 10;
+
 fn main() {
-    println!("Hello, world!");
+    let mut settings = match parse_args(env::args().skip(1)) {
+        Ok(settings) => settings,
+        // no language is picked yet, so this stays in English like the usage
+        Err(message) => {
+            eprintln!("error: {}", message);
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    // --lang wins over LANG, a language without a catalog plays in English
+    let locale = settings.lang.clone().or_else(|| {
+        let lang = env::var("LANG").ok()?;
+        i18n::locale_from_env(&lang).map(str::to_string)
+    });
+    if let Some(catalog) = locale.as_deref().and_then(Catalog::builtin) {
+        i18n::install(catalog);
+    }
+
+    if let Some(path) = &settings.replay {
+        replay(path);
+        return;
+    }
+
+    if let Some(games) = settings.bench {
+        let seed = settings.seed.unwrap_or_else(Rng::random_seed);
+        run_bench(&settings.profile.rules, games, seed);
+        return;
+    }
+
+    if let Some(games) = settings.tournament {
+        let seed = settings.seed.unwrap_or_else(Rng::random_seed);
+        run_tournament(&settings, games, seed);
+        return;
+    }
+
+    if let Some(addr) = &settings.serve {
+        let seed = settings.seed.unwrap_or_else(Rng::random_seed);
+        serve(addr, settings.profile.rules, seed);
+        return;
+    }
+
+    if let Some(addr) = &settings.http {
+        serve_http(addr, settings.profile.rules, settings.idle_timeout);
+        return;
+    }
+
+    if let Some(addr) = &settings.connect {
+        let name = settings.name.clone().unwrap_or_else(user_name);
+        connect(addr, &name, settings.room);
+        return;
+    }
+
+    // scripted games are smoke tests, they only count when a score file is named
+    let board = match (&settings.scores, &settings.script) {
+        (Some(path), _) => Some(ScoreBoard::new(path.clone())),
+        (None, Some(_)) => None,
+        (None, None) => ScoreBoard::default_path().map(ScoreBoard::new),
+    };
+    if settings.stats {
+        match &board {
+            Some(board) => print_stats(board),
+            None => {
+                eprintln!("{}", i18n::text("error-no-data-dir-scores"));
+                process::exit(2);
+            }
+        }
+        return;
+    }
+
+    match settings.mode {
+        Mode::Mastermind => {
+            play_mastermind(&settings, board.as_ref());
+            return;
+        }
+        Mode::Wordle => {
+            play_wordle(&settings, board.as_ref());
+            return;
+        }
+        Mode::Float | Mode::Date | Mode::Letter | Mode::Grid => {
+            if let Err(message) = play_domain_mode(&settings, board.as_ref()) {
+                eprintln!("{}", i18n::message("error", &[("message", &message)]));
+                process::exit(2);
+            }
+            return;
+        }
+        Mode::Number => {}
+    }
+
+    if settings.time_attack {
+        run_time_attack(&settings);
+        return;
+    }
+
+    // a resumed game brings its own rules, seed and host
+    let resumed = settings.resume.as_deref().map(|path| {
+        let saved = SavedGame::load(path, &save_key()).unwrap_or_else(|error| {
+            eprintln!(
+                "{}",
+                i18n::message(
+                    "error-resume",
+                    &[("path", &path.display()), ("error", &error)]
+                )
+            );
+            process::exit(2);
+        });
+        // a copy of a save that was played would give the answer away
+        if SavedGame::spent_dir().is_some_and(|dir| saved.is_spent(&dir)) {
+            let spent = i18n::message("error-resume-spent", &[("path", &path.display())]);
+            eprintln!("{}", spent);
+            process::exit(2);
+        }
+        settings.profile.rules = saved.transcript.rules;
+        settings.profile.name = saved.difficulty.clone();
+        settings.host = saved.transcript.host;
+        settings.seed = Some(saved.transcript.seed);
+        saved
+    });
+    let rules = settings.profile.rules;
+    let seed = settings.seed.unwrap_or_else(Rng::random_seed);
+    let (mut state, mut host, earlier): (GameState, Box<dyn Host>, Duration) = match &resumed {
+        Some(saved) => {
+            let (state, host) = saved.resume().unwrap_or_else(|error| {
+                eprintln!(
+                    "{}",
+                    i18n::message("error-resume-game", &[("error", &error)])
+                );
+                process::exit(2);
+            });
+            (state, host, saved.elapsed)
+        }
+        None => (
+            GameState::new(rules).with_hints(&settings.profile.hints),
+            settings.host.build(&rules, seed),
+            Duration::ZERO,
+        ),
+    };
+    let (mut player, default_name): (Box<dyn Player>, String) = match settings.player {
+        PlayerKind::Human if settings.script.is_some() => {
+            (Box::new(load_script(&settings)), "script".to_string())
+        }
+        PlayerKind::Human if settings.tui => match TuiPlayer::new() {
+            Some(tui) => (Box::new(tui.with_elapsed(earlier)), user_name()),
+            None => {
+                eprintln!("{}", i18n::text("note-line-mode"));
+                (Box::new(HumanPlayer::stdio()), user_name())
+            }
+        },
+        PlayerKind::Human => (Box::new(HumanPlayer::stdio()), user_name()),
+        // against a liar only the solver that expects lies stands a chance
+        PlayerKind::Bot => match settings.host {
+            HostKind::Liar(lies) => (Box::new(UlamSolver::new(lies)), "bot-ulam".to_string()),
+            _ => (
+                settings.strategy.player(seed),
+                format!("bot-{}", settings.strategy.name()),
+            ),
+        },
+    };
+    let name = settings.name.clone().unwrap_or(default_name);
+    let json = settings.format == Format::Json;
+
+    if json {
+        println!("{}", start_event(&rules, seed));
+    } else {
+        println!("{}", i18n::text("title-number"));
+        println!(
+            "{}",
+            i18n::message(
+                "intro-number",
+                &[
+                    ("low", &rules.low),
+                    ("high", &rules.high),
+                    ("attempts", &rules.max_attempts),
+                ],
+            )
+        );
+        if let Some(limit) = rules.time_limit {
+            let seconds = limit.as_secs();
+            println!("{}", i18n::message("intro-time", &[("seconds", &seconds)]));
+        }
+        if let Some(limit) = rules.guess_limit {
+            let seconds = limit.as_secs();
+            println!(
+                "{}",
+                i18n::message("intro-countdown", &[("seconds", &seconds)])
+            );
+        }
+        if let HostKind::Liar(lies) = settings.host {
+            println!("{}", i18n::message("intro-liar", &[("lies", &lies)]));
+        }
+        if resumed.is_some() {
+            let seconds = earlier.as_secs();
+            println!(
+                "{}",
+                i18n::message("welcome-back", &[("seconds", &seconds)])
+            );
+            for (guess, verdict) in state.history() {
+                println!("  {} -> {}", guess, verdict);
+            }
+        }
+        if settings.script.is_none() && matches!(settings.player, PlayerKind::Human) {
+            if state.allowed_hints().is_empty() {
+                println!("{}", i18n::text("intro-save"));
+            } else {
+                println!("{}", i18n::text("intro-hints"));
+            }
+        }
+    }
+
+    let started = Instant::now();
+    // a person sees the verdicts as they play, for everyone else they are printed here
+    let outcome = if matches!(settings.player, PlayerKind::Human) && settings.script.is_none() {
+        play_from(&mut state, host.as_mut(), player.as_mut(), earlier)
+    } else {
+        let mut reporter = Reporter::new(player.as_mut(), io::stdout(), settings.format);
+        play_from(&mut state, host.as_mut(), &mut reporter, earlier)
+    };
+    let elapsed = earlier + started.elapsed();
+    // puts the terminal back before anything else is printed
+    drop(player);
+
+    // a resumed save is used up however the game ends, saving it again writes a new one.
+    // only the first game to use it up may keep its score
+    let first_play = resumed.as_ref().is_none_or(spend_save);
+    if let Outcome::Saved { .. } = outcome {
+        let saved = SavedGame::from_game(
+            seed,
+            settings.host,
+            &state,
+            host.reveal(),
+            elapsed,
+            &settings.profile.name,
+        );
+        save_game(&settings, &saved);
+        return;
+    }
+    // the answer is shown below, so the file it came from is no use any more
+    if let Some(path) = &settings.resume {
+        let _ = std::fs::remove_file(path);
+    }
+
+    // the evil host never picked one, it shows a number that fits all its answers
+    let secret = host
+        .reveal()
+        .map_or_else(|| i18n::text("secret-unknown"), |secret| secret.to_string());
+    let won = if json {
+        println!("{}", end_event(&outcome, host.reveal()));
+        match outcome {
+            Outcome::Won { .. } => Some(true),
+            Outcome::Lost { .. } => Some(false),
+            Outcome::Quit { .. } | Outcome::Saved { .. } => None,
+        }
+    } else {
+        match outcome {
+            Outcome::Won { attempts } => {
+                let points = score(true, attempts, state.hints(), elapsed);
+                println!(
+                    "{}",
+                    i18n::message(
+                        "won-number",
+                        &[
+                            ("secret", &secret),
+                            ("attempts", &attempts),
+                            ("points", &points),
+                        ],
+                    )
+                );
+                Some(true)
+            }
+            Outcome::Lost { .. } => {
+                let id = if state.last_verdict() == Some(Verdict::OutOfTime) {
+                    "lost-time"
+                } else {
+                    "lost-attempts"
+                };
+                println!("{}", i18n::message(id, &[("secret", &secret)]));
+                Some(false)
+            }
+            // a game that was given up is not a score
+            Outcome::Quit { .. } | Outcome::Saved { .. } => {
+                println!("{}", i18n::message("quit-number", &[("secret", &secret)]));
+                None
+            }
+        }
+    };
+
+    if let (HostKind::Liar(lies), Some(secret)) = (settings.host, host.reveal()) {
+        report_lies(secret, lies, &state, json);
+    }
+
+    if let (Some(won), Some(board), true) = (won, &board, first_play) {
+        let entry = ScoreEntry::now(
+            &name,
+            &settings.profile.name,
+            won,
+            state.attempts(),
+            elapsed,
+        )
+        .with_hints(state.hints());
+        record_score(board, &entry);
+    }
+
+    if let Some(path) = &settings.record {
+        if let Err(error) = Transcript::from_game(seed, settings.host, &state).save(path) {
+            eprintln!(
+                "{}",
+                i18n::message(
+                    "error-write",
+                    &[("path", &path.display()), ("error", &error)]
+                )
+            );
+            process::exit(1);
+        }
+        if !json {
+            let recorded = i18n::message("recorded", &[("path", &path.display()), ("seed", &seed)]);
+            println!("{}", recorded);
+        }
+    }
+
+    match outcome {
+        Outcome::Won { .. } | Outcome::Saved { .. } => {}
+        Outcome::Lost { .. } => process::exit(1),
+        // a person may stop whenever they like, a script that stops early is broken
+        Outcome::Quit { .. } => {
+            if settings.script.is_some() {
+                process::exit(2);
+            }
+        }
+    }
+}
+
+// writes over the file the game was resumed from, unless told to go elsewhere
+fn save_game(settings: &Settings, saved: &SavedGame) {
+    let Some(path) = settings
+        .save
+        .clone()
+        .or_else(|| settings.resume.clone())
+        .or_else(SavedGame::default_path)
+    else {
+        eprintln!("{}", i18n::text("error-no-data-dir-save"));
+        process::exit(1);
+    };
+    if let Err(error) = saved.save(&path, &save_key()) {
+        eprintln!(
+            "{}",
+            i18n::message(
+                "error-save",
+                &[("path", &path.display()), ("error", &error)]
+            )
+        );
+        process::exit(1);
+    }
+    println!("{}", i18n::message("saved", &[("path", &path.display())]));
+}
+
+// the key that seals this install's saves, made the first time a game is saved
+fn save_key() -> SaveKey {
+    let Some(path) = SaveKey::default_path() else {
+        eprintln!("{}", i18n::text("error-no-data-dir-key"));
+        process::exit(2);
+    };
+    SaveKey::load_or_create(&path).unwrap_or_else(|error| {
+        eprintln!(
+            "{}",
+            i18n::message(
+                "error-save-key",
+                &[("path", &path.display()), ("error", &error)]
+            )
+        );
+        process::exit(2);
+    })
+}
+
+// marks a resumed save as played, `false` when another game got there first
+fn spend_save(saved: &SavedGame) -> bool {
+    let Some(dir) = SavedGame::spent_dir() else {
+        return true;
+    };
+    saved.spend(&dir).unwrap_or_else(|error| {
+        eprintln!("{}", i18n::message("error-spend", &[("error", &error)]));
+        process::exit(1);
+    })
+}
+
+// the whole script is read and checked before the game starts, `-` reads it from stdin
+fn load_script(settings: &Settings) -> ScriptedPlayer {
+    let path = settings
+        .script
+        .as_deref()
+        .expect("only called with --script");
+    let text = if path == Path::new("-") {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text).map(|_| text)
+    } else {
+        std::fs::read_to_string(path)
+    };
+    let text = text.unwrap_or_else(|error| {
+        eprintln!(
+            "{}",
+            i18n::message(
+                "error-read",
+                &[("path", &path.display()), ("error", &error)]
+            )
+        );
+        process::exit(2);
+    });
+    ScriptedPlayer::parse(&text).unwrap_or_else(|error| {
+        eprintln!(
+            "{}",
+            i18n::message(
+                "error-script",
+                &[("path", &path.display()), ("error", &error)]
+            )
+        );
+        process::exit(2);
+    })
+}
+
+fn serve(addr: &str, rules: Rules, seed: u64) {
+    let server = match Server::bind(addr, rules, seed) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!(
+                "{}",
+                i18n::message("error-listen", &[("addr", &addr), ("error", &error)])
+            );
+            process::exit(1);
+        }
+    };
+    match server.local_addr() {
+        Ok(local) => println!(
+            "{}",
+            i18n::message(
+                "serve-games",
+                &[
+                    ("addr", &local),
+                    ("low", &rules.low),
+                    ("high", &rules.high),
+                    ("attempts", &rules.max_attempts),
+                    ("seed", &seed),
+                ],
+            )
+        ),
+        Err(_) => println!("{}", i18n::message("serve-games-at", &[("addr", &addr)])),
+    }
+    if let Err(error) = server.run() {
+        eprintln!(
+            "{}",
+            i18n::message("error-server-stopped", &[("error", &error)])
+        );
+        process::exit(1);
+    }
+}
+
+fn serve_http(addr: &str, rules: Rules, idle_timeout: Duration) {
+    let server = match HttpServer::bind(addr, rules, idle_timeout) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!(
+                "{}",
+                i18n::message("error-listen", &[("addr", &addr), ("error", &error)])
+            );
+            process::exit(1);
+        }
+    };
+    match server.local_addr() {
+        Ok(local) => println!(
+            "{}",
+            i18n::message(
+                "serve-http",
+                &[
+                    ("addr", &local),
+                    ("low", &rules.low),
+                    ("high", &rules.high),
+                    ("attempts", &rules.max_attempts),
+                    ("seconds", &idle_timeout.as_secs()),
+                ],
+            )
+        ),
+        Err(_) => println!("{}", i18n::message("serve-http-at", &[("addr", &addr)])),
+    }
+    if let Err(error) = server.run() {
+        eprintln!(
+            "{}",
+            i18n::message("error-server-stopped", &[("error", &error)])
+        );
+        process::exit(1);
+    }
+}
+
+// joins a room, prints what the server says from one thread and sends what is typed
+// from this one, so race results show up while the player is still thinking
+fn connect(addr: &str, name: &str, room: Room) {
+    let fail = |error: io::Error| -> ! {
+        eprintln!(
+            "{}",
+            i18n::message(
+                "error-connection-lost",
+                &[("addr", &addr), ("error", &error)]
+            )
+        );
+        process::exit(1);
+    };
+    let mut client = Client::connect(addr).unwrap_or_else(|error| fail(error));
+    let mut replies = client.try_clone().unwrap_or_else(|error| fail(error));
+    let join = Request::Join {
+        name: name.to_string(),
+        room,
+    };
+    client.send(&join).unwrap_or_else(|error| fail(error));
+
+    let printer = thread::spawn(move || {
+        while let Ok(Some(reply)) = replies.receive() {
+            println!("{}", describe(&reply));
+            if reply == Reply::Bye {
+                break;
+            }
+        }
+    });
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        let line = line.trim();
+        let request = if line.eq_ignore_ascii_case("quit") {
+            Request::Quit
+        } else if let Ok(guess) = line.parse() {
+            Request::Guess(guess)
+        } else {
+            println!("{}", i18n::message("not-a-number", &[("input", &line)]));
+            continue;
+        };
+        client.send(&request).unwrap_or_else(|error| fail(error));
+        if request == Request::Quit {
+            break;
+        }
+    }
+    // input ran out without a quit, still say goodbye properly
+    let _ = client.send(&Request::Quit);
+    let _ = printer.join();
+}
+
+fn describe(reply: &Reply) -> String {
+    match reply {
+        Reply::Hello { version } => i18n::message("client-hello", &[("version", version)]),
+        Reply::Welcome {
+            room,
+            low,
+            high,
+            attempts,
+        } => i18n::message(
+            "client-welcome",
+            &[
+                ("room", &room.name()),
+                ("low", low),
+                ("high", high),
+                ("attempts", attempts),
+            ],
+        ),
+        Reply::Verdict {
+            guess,
+            verdict,
+            attempts_left,
+        } => {
+            let id = match verdict {
+                Verdict::TooLow => "client-too-low",
+                Verdict::TooHigh => "client-too-high",
+                Verdict::Correct => "client-correct",
+                Verdict::OutOfAttempts => "client-out-of-attempts",
+                Verdict::OutOfTime => "client-out-of-time",
+                Verdict::TooSlow => "client-too-slow",
+            };
+            i18n::message(id, &[("guess", guess), ("left", attempts_left)])
+        }
+        Reply::Secret(secret) => i18n::message("client-secret", &[("secret", secret)]),
+        Reply::Winner { name, secret } => {
+            i18n::message("client-winner", &[("name", name), ("secret", secret)])
+        }
+        Reply::Round(round) => i18n::message("client-round", &[("round", round)]),
+        Reply::Bye => i18n::text("client-bye"),
+        Reply::Error(message) => i18n::message("error", &[("message", message)]),
+    }
+}
+
+fn record_score(board: &ScoreBoard, entry: &ScoreEntry) {
+    if let Err(error) = board.record(entry) {
+        eprintln!(
+            "{}",
+            i18n::message(
+                "warning-score",
+                &[("path", &board.path().display()), ("error", &error)]
+            )
+        );
+    }
+}
+
+fn play_mastermind(settings: &Settings, board: Option<&ScoreBoard>) {
+    let config = settings.code;
+    let seed = settings.seed.unwrap_or_else(Rng::random_seed);
+    let (mut breaker, default_name): (Box<dyn Breaker>, String) = match settings.player {
+        PlayerKind::Human => (
+            Box::new(HumanBreaker::new(io::stdin().lock(), io::stdout())),
+            user_name(),
+        ),
+        PlayerKind::Bot => match KnuthSolver::new(&config) {
+            Ok(solver) => (Box::new(solver), "bot-knuth".to_string()),
+            Err(message) => {
+                eprintln!("{}", i18n::message("error", &[("message", &message)]));
+                process::exit(2);
+            }
+        },
+    };
+    let name = settings.name.clone().unwrap_or(default_name);
+
+    println!("{}", i18n::text("title-mastermind"));
+    println!(
+        "{}",
+        i18n::message(
+            "intro-mastermind",
+            &[
+                ("pegs", &config.pegs),
+                ("colours", &config.colours),
+                ("guesses", &config.max_guesses),
+            ],
+        )
+    );
+    println!("{}", i18n::text("intro-mastermind-feedback"));
+
+    let started = Instant::now();
+    let mut game = MastermindGame::seeded(config, seed);
+    let won = mastermind::play(&mut game, breaker.as_mut());
+    let elapsed = started.elapsed();
+    let guesses = game.history().len() as u32;
+
+    let secret = game.secret().to_string();
+    let won = if won {
+        let won = i18n::message("won-guesses", &[("secret", &secret), ("guesses", &guesses)]);
+        println!("{}", won);
+        Some(true)
+    } else if game.is_over() {
+        println!("{}", i18n::message("lost-code", &[("secret", &secret)]));
+        Some(false)
+    } else {
+        println!("{}", i18n::message("quit-code", &[("secret", &secret)]));
+        None
+    };
+
+    if let (Some(won), Some(board)) = (won, board) {
+        let difficulty = format!(
+            "mastermind {}x{}/{}",
+            config.pegs, config.colours, config.max_guesses
+        );
+        record_score(
+            board,
+            &ScoreEntry::now(&name, &difficulty, won, guesses, elapsed),
+        );
+    }
+}
+
+fn play_wordle(settings: &Settings, board: Option<&ScoreBoard>) {
+    let dictionary = settings
+        .dictionary
+        .clone()
+        .expect("parse_args loads the dictionary for word games");
+    let seed = settings.seed.unwrap_or_else(Rng::random_seed);
+    let (mut guesser, default_name): (Box<dyn WordGuesser>, String) = match settings.player {
+        PlayerKind::Human => (
+            Box::new(HumanWordGuesser::new(io::stdin().lock(), io::stdout())),
+            user_name(),
+        ),
+        PlayerKind::Bot => (
+            Box::new(EntropySolver::new(&dictionary, settings.hard)),
+            "bot-entropy".to_string(),
+        ),
+    };
+    let name = settings.name.clone().unwrap_or(default_name);
+
+    println!("{}", i18n::text("title-wordle"));
+    println!(
+        "{}",
+        i18n::message(
+            "intro-wordle",
+            &[
+                ("length", &dictionary.length()),
+                ("words", &dictionary.words().len()),
+                ("guesses", &settings.word_guesses),
+            ],
+        )
+    );
+    println!("{}", i18n::text("intro-wordle-feedback"));
+    if settings.hard {
+        println!("{}", i18n::text("intro-wordle-hard"));
+    }
+
+    let started = Instant::now();
+    let mut game = WordleGame::seeded(dictionary, seed, settings.word_guesses, settings.hard);
+    let won = wordle::play(&mut game, guesser.as_mut());
+    let elapsed = started.elapsed();
+    let guesses = game.history().len() as u32;
+    let secret = game.secret().to_ascii_uppercase();
+
+    let won = if won {
+        let won = i18n::message("won-guesses", &[("secret", &secret), ("guesses", &guesses)]);
+        println!("{}", won);
+        Some(true)
+    } else if game.is_over() {
+        println!("{}", i18n::message("lost-word", &[("secret", &secret)]));
+        Some(false)
+    } else {
+        println!("{}", i18n::message("quit-word", &[("secret", &secret)]));
+        None
+    };
+
+    if let (Some(won), Some(board)) = (won, board) {
+        let hard = if settings.hard { " hard" } else { "" };
+        let difficulty = format!(
+            "wordle {}/{}{}",
+            game.dictionary().length(),
+            settings.word_guesses,
+            hard
+        );
+        record_score(
+            board,
+            &ScoreEntry::now(&name, &difficulty, won, guesses, elapsed),
+        );
+    }
+}
+
+// builds the domain from --from, --to and --tolerance, the defaults fill in the rest
+fn play_domain_mode(settings: &Settings, board: Option<&ScoreBoard>) -> Result<(), String> {
+    match settings.mode {
+        Mode::Float => {
+            let default = Floats::default();
+            let (low, high) = domain_bounds(settings, &default, (default.low, default.high))?;
+            let tolerance = settings.tolerance.unwrap_or(default.tolerance);
+            let domain = Floats::new(low, high, tolerance).map_err(|error| error.to_string())?;
+            let difficulty = format!("float {}-{}~{}", low, high, tolerance);
+            let bot = Box::new(Bisection::new(&domain));
+            play_domain(settings, board, domain, bot, &difficulty);
+        }
+        Mode::Date => {
+            let default = Dates::default();
+            let (first, last) = domain_bounds(settings, &default, (default.first, default.last))?;
+            let domain = Dates::new(first, last).map_err(|error| error.to_string())?;
+            let difficulty = format!("date {}..{}", first, last);
+            let bot = Box::new(Bisection::new(&domain));
+            play_domain(settings, board, domain, bot, &difficulty);
+        }
+        Mode::Letter => {
+            let default = Letters::default();
+            let (first, last) = domain_bounds(settings, &default, (default.first, default.last))?;
+            let domain = Letters::new(first, last).map_err(|error| error.to_string())?;
+            let difficulty = format!("letter {}-{}", first, last);
+            let bot = Box::new(Bisection::new(&domain));
+            play_domain(settings, board, domain, bot, &difficulty);
+        }
+        Mode::Grid => {
+            let default = Grid::default();
+            let (low, high) = domain_bounds(settings, &default, (default.low, default.high))?;
+            let domain = Grid::new(low, high).map_err(|error| error.to_string())?;
+            let difficulty = format!("grid {}-{}", low, high);
+            let bot = Box::new(GridSolver::new(&domain));
+            play_domain(settings, board, domain, bot, &difficulty);
+        }
+        Mode::Number | Mode::Mastermind | Mode::Wordle => unreachable!("not a domain game"),
+    }
+    Ok(())
+}
+
+// --from and --to are read the way a guess is typed
+fn domain_bounds<D: Domain>(
+    settings: &Settings,
+    default: &D,
+    (low, high): (D::Value, D::Value),
+) -> Result<(D::Value, D::Value), String> {
+    let read = |text: &Option<String>, flag: &str, fallback: D::Value| match text {
+        Some(text) => default
+            .parse(text)
+            .map_err(|error| format!("{}: {}", flag, error)),
+        None => Ok(fallback),
+    };
+    Ok((
+        read(&settings.from, "--from", low)?,
+        read(&settings.to, "--to", high)?,
+    ))
+}
+
+fn play_domain<D: Domain>(
+    settings: &Settings,
+    board: Option<&ScoreBoard>,
+    domain: D,
+    bot: Box<dyn Guesser<D>>,
+    difficulty: &str,
+) {
+    let seed = settings.seed.unwrap_or_else(Rng::random_seed);
+    let (mut guesser, default_name): (Box<dyn Guesser<D>>, String) = match settings.player {
+        PlayerKind::Human => (
+            Box::new(HumanGuesser::new(io::stdin().lock(), io::stdout())),
+            user_name(),
+        ),
+        PlayerKind::Bot => (bot, "bot-bisection".to_string()),
+    };
+    let name = settings.name.clone().unwrap_or(default_name);
+
+    println!(
+        "{}",
+        i18n::message("title-domain", &[("what", &domain.describe())])
+    );
+    println!(
+        "{}",
+        i18n::message("intro-domain", &[("guesses", &settings.domain_guesses)])
+    );
+
+    let started = Instant::now();
+    let mut game = DomainGame::seeded(domain, seed, settings.domain_guesses);
+    let won = domains::play(&mut game, guesser.as_mut());
+    let elapsed = started.elapsed();
+    let guesses = game.history().len() as u32;
+
+    let secret = game.secret();
+    let won = if won {
+        let won = i18n::message("won-guesses", &[("secret", secret), ("guesses", &guesses)]);
+        println!("{}", won);
+        Some(true)
+    } else if game.is_over() {
+        println!("{}", i18n::message("lost-domain", &[("secret", secret)]));
+        Some(false)
+    } else {
+        println!("{}", i18n::message("quit-domain", &[("secret", secret)]));
+        None
+    };
+
+    if let (Some(won), Some(board)) = (won, board) {
+        let difficulty = format!("{}/{}", difficulty, settings.domain_guesses);
+        record_score(
+            board,
+            &ScoreEntry::now(&name, &difficulty, won, guesses, elapsed),
+        );
+    }
+}
+
+// shows which answers were lies, worked out again from the verdicts and the secret
+fn report_lies(secret: u32, allowed: u32, state: &GameState, json: bool) {
+    let lies = find_lies(secret, state.history());
+    if json {
+        // the verdicts and the secret are in the output, the lies can be worked out from them
+    } else if lies.is_empty() {
+        println!("{}", i18n::text("truthful"));
+    } else {
+        let turns: Vec<String> = lies.iter().map(|turn| turn.to_string()).collect();
+        let id = if lies.len() == 1 { "lied-once" } else { "lied" };
+        println!("{}", i18n::message(id, &[("turns", &turns.join(", "))]));
+    }
+    if lies.len() > allowed as usize {
+        eprintln!(
+            "{}",
+            i18n::message(
+                "error-too-many-lies",
+                &[("lies", &lies.len()), ("allowed", &allowed)]
+            )
+        );
+        process::exit(1);
+    }
+}
+
+// plays a recorded game again and fails loudly if any verdict changed
+fn replay(path: &Path) {
+    let transcript = match Transcript::load(path) {
+        Ok(transcript) => transcript,
+        Err(error) => {
+            eprintln!(
+                "{}",
+                i18n::message(
+                    "error-read",
+                    &[("path", &path.display()), ("error", &error)]
+                )
+            );
+            process::exit(2);
+        }
+    };
+
+    match transcript.replay() {
+        Ok(state) => {
+            for (guess, verdict) in state.history() {
+                println!("{} -> {}", guess, verdict);
+            }
+            println!(
+                "{}",
+                i18n::message(
+                    "replay-matches",
+                    &[
+                        ("path", &path.display()),
+                        ("guesses", &state.attempts()),
+                        ("seed", &transcript.seed),
+                    ],
+                )
+            );
+        }
+        Err(error) => {
+            eprintln!(
+                "{}",
+                i18n::message(
+                    "replay-mismatch",
+                    &[("path", &path.display()), ("error", &error)]
+                )
+            );
+            process::exit(1);
+        }
+    }
+}
+
+fn print_stats(board: &ScoreBoard) {
+    let entries = match board.load() {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!(
+                "{}",
+                i18n::message("error-read-scores", &[("error", &error)])
+            );
+            process::exit(1);
+        }
+    };
+    if entries.is_empty() {
+        let path = board.path().display();
+        println!("{}", i18n::message("stats-empty", &[("path", &path)]));
+        return;
+    }
+
+    println!("{}", i18n::text("stats-high-scores"));
+    for (rank, entry) in high_scores(&entries, 10).iter().enumerate() {
+        // the columns are padded here, the words around them come from the catalog
+        println!(
+            "{}",
+            i18n::message(
+                "stats-high-score",
+                &[
+                    ("rank", &format!("{:>3}", rank + 1)),
+                    ("player", &format!("{:<16}", entry.player)),
+                    ("difficulty", &format!("{:<12}", entry.difficulty)),
+                    ("points", &format!("{:>5}", entry.score)),
+                    ("attempts", &format!("{:>3}", entry.attempts)),
+                    ("hints", &format!("{:>2}", entry.hints)),
+                    ("seconds", &format!("{:>7.1}", entry.elapsed.as_secs_f64())),
+                    ("date", &entry.date),
+                ],
+            )
+        );
+    }
+
+    for stats in player_stats(&entries) {
+        println!();
+        println!(
+            "{}",
+            i18n::message(
+                "stats-player",
+                &[
+                    ("player", &stats.player),
+                    ("difficulty", &stats.difficulty),
+                    ("games", &stats.games),
+                    ("percent", &format!("{:.0}", stats.win_rate() * 100.0)),
+                    ("streak", &stats.best_streak),
+                ],
+            )
+        );
+        let widest = stats.histogram.values().copied().max().unwrap_or(0);
+        for (attempts, count) in &stats.histogram {
+            // bars are scaled so the most common count is 30 characters wide
+            let bar = (count * 30).div_ceil(widest.max(1)) as usize;
+            println!("  {:>3} | {} {}", attempts, "#".repeat(bar), count);
+        }
+    }
+}
+
+// plays the same seeded secrets with every strategy, next to the bisection bound
+// (that binary search keeps to the bound is checked in tests/bench.rs)
+fn run_bench(rules: &Rules, games: u32, seed: u64) {
+    println!(
+        "{}",
+        i18n::message(
+            "bench-header",
+            &[
+                ("games", &games),
+                ("low", &rules.low),
+                ("high", &rules.high),
+                ("seed", &seed),
+                ("attempts", &rules.max_attempts),
+                ("bound", &bisection_bound(rules.size())),
+            ],
+        )
+    );
+    println!(
+        "{:<8} {:>7} {:>6} {:>8} {:>13}  {}",
+        i18n::text("bench-strategy"),
+        i18n::text("bench-mean"),
+        i18n::text("bench-worst"),
+        i18n::text("bench-vs-evil"),
+        i18n::text("bench-within-limit"),
+        i18n::text("bench-distribution")
+    );
+
+    for strategy in Strategy::ALL {
+        let report = bench::run(strategy, rules, games, seed);
+        let distribution: Vec<String> = report
+            .distribution
+            .iter()
+            .map(|(attempts, count)| format!("{}:{}", attempts, count))
+            .collect();
+        println!(
+            "{:<8} {:>7.2} {:>6} {:>8} {:>12.1}%  {}",
+            strategy.name(),
+            report.mean,
+            report.worst,
+            report.against_adversary,
+            report.within_limit as f64 * 100.0 / games.max(1) as f64,
+            distribution.join(" ")
+        );
+    }
+}
+
+// every built-in strategy against every other, on the same secrets
+fn run_tournament(settings: &Settings, games: u32, seed: u64) {
+    let rules = &settings.profile.rules;
+    let threads = settings
+        .threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |threads| threads.get()));
+    let tournament = Tournament::with_strategies(*rules, games, seed);
+    println!(
+        "{}",
+        i18n::message(
+            "tournament-header",
+            &[
+                ("entrants", &tournament.entrants()),
+                ("games", &games),
+                ("low", &rules.low),
+                ("high", &rules.high),
+                ("seed", &seed),
+                ("attempts", &rules.max_attempts),
+            ],
+        )
+    );
+    let standings = tournament.run(threads);
+    print!("{}", standings.to_text());
+
+    if let Some(path) = &settings.csv {
+        if let Err(error) = std::fs::write(path, standings.to_csv()) {
+            eprintln!(
+                "{}",
+                i18n::message(
+                    "error-write",
+                    &[("path", &path.display()), ("error", &error)]
+                )
+            );
+            process::exit(1);
+        }
+        let path = path.display();
+        println!(
+            "{}",
+            i18n::message("tournament-written", &[("path", &path)])
+        );
+    }
+}
+
+// as many numbers as possible before the clock runs out, a fresh secret after each one
+fn run_time_attack(settings: &Settings) {
+    let seed = settings.seed.unwrap_or_else(Rng::random_seed);
+    let attack = TimeAttack::new(settings.profile.rules, TIME_ATTACK, seed);
+    let rules = *attack.rules();
+    let human = matches!(settings.player, PlayerKind::Human);
+
+    println!("{}", i18n::text("title-time-attack"));
+    let seconds = TIME_ATTACK.as_secs();
+    println!(
+        "{}",
+        i18n::message(
+            "intro-time-attack",
+            &[
+                ("low", &rules.low),
+                ("high", &rules.high),
+                ("seconds", &seconds),
+                ("attempts", &rules.max_attempts),
+            ],
+        )
+    );
+    if let Some(limit) = rules.guess_limit {
+        let seconds = limit.as_secs();
+        println!(
+            "{}",
+            i18n::message("intro-countdown", &[("seconds", &seconds)])
+        );
+    }
+
+    let score = attack.run(&SystemClock::new(), &mut |score, seed| {
+        if !human {
+            return settings.strategy.player(seed);
+        }
+        let number = score.solved + score.missed + 1;
+        println!(
+            "{}",
+            i18n::message(
+                "attack-next",
+                &[("number", &number), ("solved", &score.solved)]
+            )
+        );
+        Box::new(HumanPlayer::stdio())
+    });
+
+    let args: [(&str, &dyn std::fmt::Display); 3] = [
+        ("solved", &score.solved),
+        ("missed", &score.missed),
+        ("seconds", &score.elapsed.as_secs()),
+    ];
+    let id = if score.quit {
+        "attack-quit"
+    } else {
+        "attack-over"
+    };
+    println!("{}", i18n::message(id, &args));
+    if score.solved == 0 {
+        process::exit(1);
+    }
+}
+
+fn user_name() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_else(|_| "player".to_string())
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Settings, String> {
+    let mut settings = Settings {
+        mode: Mode::Number,
+        profile: Profile::builtin("normal").expect("normal is a built-in profile"),
+        code: mastermind::Config::default(),
+        dictionary: None,
+        word_guesses: WORD_GUESSES,
+        hard: false,
+        from: None,
+        to: None,
+        tolerance: None,
+        domain_guesses: DOMAIN_GUESSES,
+        player: PlayerKind::Human,
+        strategy: Strategy::BinarySearch,
+        host: HostKind::Fair,
+        name: None,
+        seed: None,
+        record: None,
+        replay: None,
+        scores: None,
+        stats: false,
+        bench: None,
+        tournament: None,
+        threads: None,
+        csv: None,
+        serve: None,
+        connect: None,
+        room: Room::Solo,
+        http: None,
+        idle_timeout: http::DEFAULT_IDLE_TIMEOUT,
+        script: None,
+        format: Format::Text,
+        tui: false,
+        resume: None,
+        save: None,
+        lang: None,
+        time_attack: false,
+    };
+    // single settings win over the profile, whatever order they come in
+    let (mut low, mut high, mut attempts) = (None, None, None);
+    let (mut dictionary, mut word_length) = (None, WORD_LENGTH);
+    let mut profile_given = false;
+    let mut hints = None;
+    let mut countdown = None;
+
+    while let Some(flag) = args.next() {
+        // switches first, everything else takes a value
+        if flag == "--stats" {
+            settings.stats = true;
+            continue;
+        }
+        if flag == "--hard" {
+            settings.hard = true;
+            continue;
+        }
+        if flag == "--tui" {
+            settings.tui = true;
+            continue;
+        }
+        if flag == "--time-attack" {
+            settings.time_attack = true;
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", flag))?;
+        match flag.as_str() {
+            "--mode" => {
+                settings.mode = match value.as_str() {
+                    "number" => Mode::Number,
+                    "mastermind" => Mode::Mastermind,
+                    "wordle" => Mode::Wordle,
+                    "float" => Mode::Float,
+                    "date" => Mode::Date,
+                    "letter" => Mode::Letter,
+                    "grid" => Mode::Grid,
+                    _ => return Err(format!("unknown mode '{}'", value)),
+                }
+            }
+            "--pegs" => settings.code.pegs = parse_number(&flag, &value)?,
+            "--colours" => settings.code.colours = parse_number(&flag, &value)?,
+            "--dict" => dictionary = Some(PathBuf::from(value)),
+            "--word-length" => word_length = parse_number(&flag, &value)?,
+            "--from" => settings.from = Some(value),
+            "--to" => settings.to = Some(value),
+            "--tolerance" => {
+                settings.tolerance = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("--tolerance expects a number, got '{}'", value))?,
+                )
+            }
+            "--difficulty" => {
+                profile_given = true;
+                settings.profile = Profile::builtin(&value).ok_or_else(|| {
+                    format!(
+                        "unknown difficulty '{}', expected one of {}",
+                        value,
+                        BUILTIN_NAMES.join(", ")
+                    )
+                })?
+            }
+            "--rules" => {
+                profile_given = true;
+                settings.profile = Profile::load(Path::new(&value))
+                    .map_err(|error| format!("{}: {}", value, error))?
+            }
+            "--min" => low = Some(parse_number(&flag, &value)?),
+            "--max" => high = Some(parse_number(&flag, &value)?),
+            "--attempts" => attempts = Some(parse_number(&flag, &value)?),
+            "--player" => {
+                settings.player = match value.as_str() {
+                    "human" => PlayerKind::Human,
+                    "bot" => PlayerKind::Bot,
+                    _ => return Err(format!("unknown player '{}'", value)),
+                }
+            }
+            "--strategy" => {
+                settings.strategy = Strategy::parse(&value).ok_or_else(|| {
+                    let names: Vec<&str> = Strategy::ALL.iter().map(|s| s.name()).collect();
+                    format!(
+                        "unknown strategy '{}', expected one of {}",
+                        value,
+                        names.join(", ")
+                    )
+                })?
+            }
+            "--host" => {
+                settings.host = HostKind::parse(&value)
+                    .ok_or_else(|| format!("unknown host '{}', expected fair or evil", value))?
+            }
+            "--lies" => {
+                let lies = parse_number(&flag, &value)?;
+                if lies > MAX_LIES {
+                    return Err(format!("--lies can be at most {}", MAX_LIES));
+                }
+                settings.host = HostKind::Liar(lies)
+            }
+            "--bench" => settings.bench = Some(parse_number(&flag, &value)?),
+            "--tournament" => settings.tournament = Some(parse_number(&flag, &value)?),
+            "--threads" => {
+                let threads = parse_number(&flag, &value)?;
+                if threads == 0 {
+                    return Err("--threads must be at least 1".to_string());
+                }
+                settings.threads = Some(threads)
+            }
+            "--csv" => settings.csv = Some(PathBuf::from(value)),
+            "--serve" => settings.serve = Some(value),
+            "--connect" => settings.connect = Some(value),
+            "--http" => settings.http = Some(value),
+            "--script" => settings.script = Some(PathBuf::from(value)),
+            "--format" => {
+                settings.format = Format::parse(&value)
+                    .ok_or_else(|| format!("unknown format '{}', expected text or json", value))?
+            }
+            "--idle-timeout" => {
+                let seconds = parse_number(&flag, &value)?;
+                if seconds == 0 {
+                    return Err("--idle-timeout must be at least 1 second".to_string());
+                }
+                settings.idle_timeout = Duration::from_secs(seconds)
+            }
+            "--room" => {
+                settings.room = Room::parse(&value)
+                    .ok_or_else(|| format!("unknown room '{}', expected solo or race", value))?
+            }
+            "--name" => settings.name = Some(value),
+            "--seed" => settings.seed = Some(parse_number(&flag, &value)?),
+            "--record" => settings.record = Some(PathBuf::from(value)),
+            "--replay" => settings.replay = Some(PathBuf::from(value)),
+            "--scores" => settings.scores = Some(PathBuf::from(value)),
+            "--hints" => {
+                hints = Some(parse_hints(&value).map_err(|error| format!("--hints: {}", error))?)
+            }
+            "--countdown" => {
+                let seconds = parse_number(&flag, &value)?;
+                if seconds == 0 {
+                    return Err("--countdown must be at least 1 second".to_string());
+                }
+                countdown = Some(Duration::from_secs(seconds))
+            }
+            "--resume" => settings.resume = Some(PathBuf::from(value)),
+            "--save" => settings.save = Some(PathBuf::from(value)),
+            "--lang" => {
+                if !i18n::LOCALES.contains(&value.as_str()) {
+                    return Err(format!(
+                        "unknown language '{}', expected one of {}",
+                        value,
+                        i18n::LOCALES.join(", ")
+                    ));
+                }
+                settings.lang = Some(value)
+            }
+            _ => return Err(format!("unknown option {}", flag)),
+        }
+    }
+
+    if settings.script.is_some() || settings.format == Format::Json {
+        if settings.mode != Mode::Number {
+            return Err("--script and --format are for the number game".to_string());
+        }
+        if settings.script.is_some() && matches!(settings.player, PlayerKind::Bot) {
+            return Err("--script plays the guesses from the file, not a bot".to_string());
+        }
+        // prompts would end up in the middle of the JSON
+        if settings.script.is_none() && matches!(settings.player, PlayerKind::Human) {
+            return Err("--format json needs --script or --player bot".to_string());
+        }
+    }
+    if settings.tui
+        && (settings.mode != Mode::Number
+            || settings.script.is_some()
+            || matches!(settings.player, PlayerKind::Bot))
+    {
+        return Err("--tui is for a person playing the number game".to_string());
+    }
+    if settings.resume.is_some() || settings.save.is_some() {
+        // only a person can type save
+        if settings.mode != Mode::Number
+            || settings.script.is_some()
+            || matches!(settings.player, PlayerKind::Bot)
+        {
+            return Err("--save and --resume are for a person playing the number game".to_string());
+        }
+    }
+    if settings.resume.is_some()
+        && (profile_given
+            || hints.is_some()
+            || low.is_some()
+            || high.is_some()
+            || attempts.is_some()
+            || settings.seed.is_some()
+            || countdown.is_some()
+            || settings.host != HostKind::Fair)
+    {
+        return Err("a resumed game keeps its own rules, seed and host".to_string());
+    }
+    let servers = [&settings.serve, &settings.connect, &settings.http];
+    let servers = servers.iter().filter(|addr| addr.is_some()).count();
+    if servers > 0 {
+        // the servers have their own fair hosts and only play the number game
+        if settings.mode != Mode::Number || settings.host != HostKind::Fair {
+            return Err(
+                "--serve, --connect and --http only play the number game with a fair host"
+                    .to_string(),
+            );
+        }
+        if servers > 1 {
+            return Err("only one of --serve, --connect and --http can be used".to_string());
+        }
+    }
+    if settings.mode != Mode::Number {
+        // the other games have no range, no host and nothing to record or replay yet
+        if low.is_some() || high.is_some() || settings.host != HostKind::Fair {
+            return Err("--min, --max, --host and --lies are for the number game".to_string());
+        }
+        if hints.is_some() || countdown.is_some() || settings.time_attack {
+            return Err(
+                "--hints, --countdown and --time-attack are for the number game".to_string(),
+            );
+        }
+        if settings.record.is_some()
+            || settings.replay.is_some()
+            || settings.bench.is_some()
+            || settings.tournament.is_some()
+        {
+            return Err(
+                "--record, --replay, --bench and --tournament are for the number game".to_string(),
+            );
+        }
+    }
+    if servers > 0 && (countdown.is_some() || settings.time_attack) {
+        return Err(
+            "the servers don't time guesses, drop --countdown and --time-attack".to_string(),
+        );
+    }
+    // a time attack is one person or bot against the clock, nothing to save, record or script
+    if settings.time_attack
+        && (settings.host != HostKind::Fair
+            || settings.script.is_some()
+            || settings.format == Format::Json
+            || settings.tui
+            || settings.resume.is_some()
+            || settings.save.is_some()
+            || settings.record.is_some()
+            || settings.bench.is_some()
+            || settings.tournament.is_some())
+    {
+        return Err(
+            "--time-attack can't be combined with --host, --lies, --script, --format, --tui, \
+             --save, --resume, --record, --bench or --tournament"
+                .to_string(),
+        );
+    }
+    if settings.tournament.is_none() && (settings.threads.is_some() || settings.csv.is_some()) {
+        return Err("--threads and --csv are for --tournament".to_string());
+    }
+    if settings.tournament.is_some() && settings.host != HostKind::Fair {
+        return Err("the tournament plays against a fair host".to_string());
+    }
+    if settings.mode != Mode::Wordle && (dictionary.is_some() || settings.hard) {
+        return Err("--dict, --word-length and --hard are for the word game".to_string());
+    }
+    if !settings.mode.is_domain() && (settings.from.is_some() || settings.to.is_some()) {
+        return Err("--from and --to are for the float, date, letter and grid games".to_string());
+    }
+    if settings.mode != Mode::Float && settings.tolerance.is_some() {
+        return Err("--tolerance is for the float game".to_string());
+    }
+    match settings.mode {
+        Mode::Mastermind => {
+            if let Some(attempts) = attempts {
+                settings.code.max_guesses = attempts;
+            }
+            settings.code.validate()?;
+            return Ok(settings);
+        }
+        Mode::Wordle => {
+            let path = dictionary.ok_or("the word game needs a dictionary, pass --dict FILE")?;
+            settings.dictionary = Some(
+                Dictionary::load(&path, word_length)
+                    .map_err(|error| format!("{}: {}", path.display(), error))?,
+            );
+            settings.word_guesses = attempts.unwrap_or(WORD_GUESSES);
+            if settings.word_guesses == 0 {
+                return Err("a game needs at least 1 guess".to_string());
+            }
+            return Ok(settings);
+        }
+        Mode::Float | Mode::Date | Mode::Letter | Mode::Grid => {
+            settings.domain_guesses = attempts.unwrap_or(DOMAIN_GUESSES);
+            if settings.domain_guesses == 0 {
+                return Err("a game needs at least 1 guess".to_string());
+            }
+            return Ok(settings);
+        }
+        Mode::Number => {}
+    }
+
+    if let Some(hints) = hints {
+        settings.profile.hints = hints;
+    }
+    let rules = &mut settings.profile.rules;
+    if low.is_some() || high.is_some() || attempts.is_some() {
+        rules.low = low.unwrap_or(rules.low);
+        rules.high = high.unwrap_or(rules.high);
+        rules.max_attempts = attempts.unwrap_or(rules.max_attempts);
+        // changed rules are not the named difficulty any more, scores must tell them apart
+        settings.profile.name = format!("{}-{}/{}", rules.low, rules.high, rules.max_attempts);
+    }
+    // the liar's attempts are worked out from the range, which has to make sense first
+    rules.validate()?;
+    if let HostKind::Liar(lies) = settings.host {
+        if rules.size() > liar::MAX_RANGE {
+            return Err(format!(
+                "liar mode supports ranges of at most {} numbers",
+                liar::MAX_RANGE
+            ));
+        }
+        // unless told otherwise, give the player enough attempts to beat the lies
+        if attempts.is_none() {
+            rules.max_attempts = rules
+                .max_attempts
+                .max(liar::attempts_needed(rules.size(), lies) + liar::SLACK);
+        }
+        settings.profile.name = format!("{} liar {}", settings.profile.name, lies);
+    }
+    if let Some(limit) = countdown {
+        rules.guess_limit = Some(limit);
+        settings.profile.name = format!("{} countdown {}s", settings.profile.name, limit.as_secs());
+    }
+    settings.profile.rules.validate()?;
+    Ok(settings)
+}
+
+fn parse_number<T: FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a whole number, got '{}'", flag, value))
 }
@@ -0,0 +1,451 @@
+// code-breaking mode (Mastermind, or bulls and cows with repeated digits allowed)
+//
+// the secret is a row of `pegs` colours out of `colours`, written as digits 1..=colours.
+// every guess is answered with how many pegs are the right colour in the right place
+// (exact) and how many more are the right colour in the wrong place (partial).
+
+use std::fmt;
+use std::io::{BufRead, Write};
+
+use crate::i18n;
+use crate::rng::Rng;
+
+/// Digits are used for colours, so there can't be more than 9.
+pub const MAX_COLOURS: u8 = 9;
+/// The Knuth solver compares every code with every other one, more than this gets slow.
+pub const KNUTH_MAX_CODES: usize = 20_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub pegs: usize,
+    pub colours: u8,
+    pub max_guesses: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            pegs: 4,
+            colours: 6,
+            max_guesses: 10,
+        }
+    }
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.pegs == 0 || self.pegs > 8 {
+            return Err(format!("a code needs 1 to 8 pegs, not {}", self.pegs));
+        }
+        if self.colours < 2 || self.colours > MAX_COLOURS {
+            return Err(format!(
+                "a code needs 2 to {} colours, not {}",
+                MAX_COLOURS, self.colours
+            ));
+        }
+        if self.max_guesses == 0 {
+            return Err("a game needs at least 1 guess".to_string());
+        }
+        Ok(())
+    }
+
+    /// How many different codes there are.
+    pub fn code_count(&self) -> usize {
+        (self.colours as usize).pow(self.pegs as u32)
+    }
+}
+
+/// A row of colours, each in `0..colours` (shown as 1-based digits).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Code(pub Vec<u8>);
+
+impl Code {
+    pub fn parse(text: &str, config: &Config) -> Result<Code, CodeError> {
+        let text = text.trim();
+        if text.chars().count() != config.pegs {
+            return Err(CodeError::Length {
+                text: text.to_string(),
+                pegs: config.pegs,
+            });
+        }
+        text.chars()
+            .map(|c| match c.to_digit(10) {
+                Some(digit) if digit >= 1 && digit <= config.colours as u32 => Ok(digit as u8 - 1),
+                _ => Err(CodeError::Colour {
+                    found: c,
+                    colours: config.colours,
+                }),
+            })
+            .collect::<Result<Vec<u8>, CodeError>>()
+            .map(Code)
+    }
+
+    pub fn random(config: &Config, rng: &mut Rng) -> Code {
+        Code(
+            (0..config.pegs)
+                .map(|_| rng.below(config.colours as u64) as u8)
+                .collect(),
+        )
+    }
+
+    /// Every code, in counting order (1111, 1112, ... for 4 pegs).
+    pub fn all(config: &Config) -> Vec<Code> {
+        (0..config.code_count())
+            .map(|mut index| {
+                let mut pegs = vec![0; config.pegs];
+                for peg in pegs.iter_mut().rev() {
+                    *peg = (index % config.colours as usize) as u8;
+                    index /= config.colours as usize;
+                }
+                Code(pegs)
+            })
+            .collect()
+    }
+}
+
+/// Why typed text is not a code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeError {
+    Length { text: String, pegs: usize },
+    Colour { found: char, colours: u8 },
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodeError::Length { text, pegs } => write!(
+                f,
+                "a code has {} digits, '{}' has {}",
+                pegs,
+                text,
+                text.chars().count()
+            ),
+            CodeError::Colour { found, colours } => write!(
+                f,
+                "'{}' is not a colour, use the digits 1 to {}",
+                found, colours
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CodeError {}
+
+impl CodeError {
+    /// The error in the player's language, `Display` stays English.
+    pub fn localised(&self) -> String {
+        match self {
+            CodeError::Length { text, pegs } => i18n::message(
+                "code-length",
+                &[
+                    ("pegs", pegs),
+                    ("code", text),
+                    ("digits", &text.chars().count()),
+                ],
+            ),
+            CodeError::Colour { found, colours } => {
+                i18n::message("code-colour", &[("digit", found), ("colours", colours)])
+            }
+        }
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for peg in &self.0 {
+            write!(f, "{}", peg + 1)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Feedback {
+    pub exact: u8,
+    pub partial: u8,
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} exact, {} partial", self.exact, self.partial)
+    }
+}
+
+impl Feedback {
+    /// The feedback in the player's language, `Display` stays English.
+    pub fn localised(&self) -> String {
+        i18n::message(
+            "mastermind-feedback",
+            &[("exact", &self.exact), ("partial", &self.partial)],
+        )
+    }
+}
+
+/// Compares a guess to the secret. Repeated colours only count as often as they
+/// are in both codes, so `1122` against `1233` is 1 exact and 1 partial.
+pub fn score(secret: &Code, guess: &Code) -> Feedback {
+    let mut exact = 0;
+    let mut secret_left = [0u8; MAX_COLOURS as usize];
+    let mut guess_left = [0u8; MAX_COLOURS as usize];
+    for (s, g) in secret.0.iter().zip(&guess.0) {
+        if s == g {
+            exact += 1;
+        } else {
+            secret_left[*s as usize] += 1;
+            guess_left[*g as usize] += 1;
+        }
+    }
+    let partial = secret_left
+        .iter()
+        .zip(&guess_left)
+        .map(|(s, g)| s.min(g))
+        .sum();
+    Feedback { exact, partial }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MastermindError {
+    // the guess has the wrong number of pegs or a colour the game doesn't have
+    WrongCode(Code),
+    GameOver,
+}
+
+impl fmt::Display for MastermindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MastermindError::WrongCode(code) => write!(f, "{} is not a code of this game", code),
+            MastermindError::GameOver => write!(f, "the game is already over"),
+        }
+    }
+}
+
+impl std::error::Error for MastermindError {}
+
+impl MastermindError {
+    /// The error in the player's language, `Display` stays English for logs and the APIs.
+    pub fn localised(&self) -> String {
+        match self {
+            MastermindError::WrongCode(code) => {
+                i18n::message("code-not-in-game", &[("code", code)])
+            }
+            MastermindError::GameOver => i18n::text("game-over"),
+        }
+    }
+}
+
+/// One code-breaking game.
+#[derive(Debug, Clone)]
+pub struct MastermindGame {
+    config: Config,
+    secret: Code,
+    history: Vec<(Code, Feedback)>,
+}
+
+impl MastermindGame {
+    pub fn new(config: Config, secret: Code) -> Self {
+        MastermindGame {
+            config,
+            secret,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn seeded(config: Config, seed: u64) -> Self {
+        let secret = Code::random(&config, &mut Rng::new(seed));
+        MastermindGame::new(config, secret)
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn history(&self) -> &[(Code, Feedback)] {
+        &self.history
+    }
+
+    /// The secret, only meant to be shown once the game is over.
+    pub fn secret(&self) -> &Code {
+        &self.secret
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.history
+            .last()
+            .is_some_and(|(_, feedback)| feedback.exact as usize == self.config.pegs)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.history.len() as u32 >= self.config.max_guesses
+    }
+
+    pub fn guess(&mut self, guess: Code) -> Result<Feedback, MastermindError> {
+        if self.is_over() {
+            return Err(MastermindError::GameOver);
+        }
+        let in_range = guess.0.iter().all(|peg| *peg < self.config.colours);
+        if guess.0.len() != self.config.pegs || !in_range {
+            return Err(MastermindError::WrongCode(guess));
+        }
+        let feedback = score(&self.secret, &guess);
+        self.history.push((guess, feedback));
+        Ok(feedback)
+    }
+}
+
+/// The side that tries to break the code, like `Player` for the number game.
+pub trait Breaker {
+    /// The next guess, or `None` to give up.
+    fn next_guess(&mut self, game: &MastermindGame) -> Option<Code>;
+
+    fn observe(&mut self, _guess: &Code, _feedback: Feedback) {}
+}
+
+/// Runs a game until the code is broken, the guesses run out or the breaker gives up.
+/// Returns whether the code was broken.
+pub fn play(game: &mut MastermindGame, breaker: &mut dyn Breaker) -> bool {
+    while !game.is_over() {
+        let Some(guess) = breaker.next_guess(game) else {
+            return false;
+        };
+        match game.guess(guess.clone()) {
+            Ok(feedback) => breaker.observe(&guess, feedback),
+            // the breakers check their own codes, a bad one means they are confused
+            Err(_) => return false,
+        }
+    }
+    game.is_won()
+}
+
+/// Donald Knuth's five-guess algorithm.
+///
+/// Keeps every code that fits all feedback so far, and picks the guess (out of all
+/// codes) whose worst feedback leaves the fewest of them. Ties go to a guess that could
+/// still be the secret, then to the lowest code. For 4 pegs and 6 colours this always
+/// wins within 5 guesses.
+#[derive(Debug, Clone)]
+pub struct KnuthSolver {
+    all: Vec<Code>,
+    candidates: Vec<usize>,
+    // feedback is at most pegs exact and pegs partial, this indexes a flat count table
+    pegs: usize,
+}
+
+impl KnuthSolver {
+    pub fn new(config: &Config) -> Result<Self, String> {
+        if config.code_count() > KNUTH_MAX_CODES {
+            return Err(format!(
+                "the solver handles at most {} codes, {} pegs of {} colours are {}",
+                KNUTH_MAX_CODES,
+                config.pegs,
+                config.colours,
+                config.code_count()
+            ));
+        }
+        let all = Code::all(config);
+        Ok(KnuthSolver {
+            candidates: (0..all.len()).collect(),
+            all,
+            pegs: config.pegs,
+        })
+    }
+
+    /// The codes that still fit every feedback.
+    pub fn candidates(&self) -> impl Iterator<Item = &Code> {
+        self.candidates.iter().map(|index| &self.all[*index])
+    }
+
+    pub fn best_guess(&self) -> Option<Code> {
+        match self.candidates.as_slice() {
+            [] => return None,
+            [only] => return Some(self.all[*only].clone()),
+            _ => {}
+        }
+
+        let size = self.pegs + 1;
+        let mut counts = vec![0usize; size * size];
+        let mut best: Option<(usize, bool, usize)> = None;
+        for (index, guess) in self.all.iter().enumerate() {
+            counts.iter_mut().for_each(|count| *count = 0);
+            for candidate in &self.candidates {
+                let feedback = score(&self.all[*candidate], guess);
+                counts[feedback.exact as usize * size + feedback.partial as usize] += 1;
+            }
+            let worst = counts.iter().copied().max().unwrap_or(0);
+            let possible = self.candidates.binary_search(&index).is_ok();
+
+            let better = match best {
+                None => true,
+                Some((best_worst, best_possible, _)) => {
+                    worst < best_worst || (worst == best_worst && possible && !best_possible)
+                }
+            };
+            if better {
+                best = Some((worst, possible, index));
+            }
+        }
+        best.map(|(_, _, index)| self.all[index].clone())
+    }
+}
+
+impl Breaker for KnuthSolver {
+    fn next_guess(&mut self, _game: &MastermindGame) -> Option<Code> {
+        self.best_guess()
+    }
+
+    fn observe(&mut self, guess: &Code, feedback: Feedback) {
+        let all = &self.all;
+        self.candidates
+            .retain(|candidate| score(&all[*candidate], guess) == feedback);
+    }
+}
+
+/// A person typing codes like `1234`.
+pub struct HumanBreaker<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> HumanBreaker<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        HumanBreaker { input, output }
+    }
+}
+
+impl<R: BufRead, W: Write> Breaker for HumanBreaker<R, W> {
+    fn next_guess(&mut self, game: &MastermindGame) -> Option<Code> {
+        loop {
+            let prompt = i18n::message(
+                "prompt-numbered",
+                &[
+                    ("number", &(game.history().len() + 1)),
+                    ("total", &game.config().max_guesses),
+                ],
+            );
+            let _ = write!(self.output, "{} ", prompt);
+            let _ = self.output.flush();
+
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Ok(0) | Err(_) => return None,
+                Ok(_) => {}
+            }
+            if line.trim().eq_ignore_ascii_case("quit") {
+                return None;
+            }
+            match Code::parse(&line, game.config()) {
+                Ok(code) => return Some(code),
+                Err(error) => {
+                    let _ = writeln!(
+                        self.output,
+                        "{}",
+                        i18n::message("try-again", &[("error", &error.localised())])
+                    );
+                }
+            }
+        }
+    }
+
+    fn observe(&mut self, _guess: &Code, feedback: Feedback) {
+        let _ = writeln!(self.output, "{}", feedback.localised());
+    }
+}
@@ -0,0 +1,192 @@
+// the players that come with the game: a human on a terminal and a list of
+// scripted guesses (the bots live in solvers.rs)
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::game::{Action, GameState, GuessError, Player, Verdict};
+use crate::hints::{HintError, HintKind};
+use crate::i18n;
+
+/// A person typing guesses, one per line.
+///
+/// Reads from any `BufRead` and writes to any `Write`, so it works on a
+/// terminal as well as on in-memory buffers.
+pub struct HumanPlayer<R, W> {
+    input: R,
+    output: W,
+}
+
+impl HumanPlayer<io::StdinLock<'static>, io::Stdout> {
+    pub fn stdio() -> Self {
+        HumanPlayer::new(io::stdin().lock(), io::stdout())
+    }
+}
+
+impl<R: BufRead, W: Write> HumanPlayer<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        HumanPlayer { input, output }
+    }
+
+    // the player only talks to a terminal, if that is gone there is nothing sensible left to do
+    fn say(&mut self, text: &str) {
+        let _ = writeln!(self.output, "{}", text);
+    }
+}
+
+impl<R: BufRead, W: Write> Player for HumanPlayer<R, W> {
+    fn next_action(&mut self, state: &GameState) -> Action {
+        loop {
+            let _ = write!(self.output, "{} ", i18n::text("prompt"));
+            let _ = self.output.flush();
+
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                // stdin was closed (ctrl-d), there is nobody left to play
+                Ok(0) | Err(_) => {
+                    self.say("");
+                    return Action::Quit;
+                }
+                Ok(_) => {}
+            }
+
+            let line = line.trim();
+            if line.eq_ignore_ascii_case("quit") {
+                return Action::Quit;
+            }
+            if line.eq_ignore_ascii_case("save") {
+                return Action::Save;
+            }
+            if let Some(name) = line.strip_prefix("hint") {
+                match HintKind::parse(name.trim()) {
+                    Some(kind) => return Action::Hint(kind),
+                    None => self.list_hints(state),
+                }
+                continue;
+            }
+            // reject anything that is not a number instead of crashing on it
+            match line.parse() {
+                Ok(guess) => return Action::Guess(guess),
+                Err(_) => self.say(&i18n::message("not-a-number", &[("input", &line)])),
+            }
+        }
+    }
+
+    fn observe(&mut self, _guess: u32, verdict: Verdict, state: &GameState) {
+        match verdict {
+            Verdict::TooLow => self.say(&i18n::text("too-low")),
+            Verdict::TooHigh => self.say(&i18n::text("too-high")),
+            Verdict::TooSlow => self.say(&i18n::text("too-slow")),
+            Verdict::Correct | Verdict::OutOfAttempts | Verdict::OutOfTime => return,
+        }
+        self.say(&i18n::message(
+            "attempts-left",
+            &[("left", &state.attempts_left())],
+        ));
+    }
+
+    fn rejected(&mut self, error: &GuessError) {
+        self.say(&i18n::message(
+            "try-again",
+            &[("error", &error.localised())],
+        ));
+    }
+
+    fn hinted(&mut self, kind: HintKind, hint: &Result<String, HintError>) {
+        match hint {
+            Ok(text) => self.say(&i18n::message(
+                "hint-cost",
+                &[("hint", text), ("cost", &kind.cost())],
+            )),
+            Err(error) => self.say(&i18n::message("no-hint", &[("error", &error.localised())])),
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> HumanPlayer<R, W> {
+    fn list_hints(&mut self, state: &GameState) {
+        if state.allowed_hints().is_empty() {
+            self.say(&i18n::text("no-hints"));
+            return;
+        }
+        let hints: Vec<String> = state
+            .allowed_hints()
+            .iter()
+            .map(|kind| format!("{} (-{})", kind, kind.cost()))
+            .collect();
+        self.say(&i18n::message("hint-list", &[("hints", &hints.join(", "))]));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// Plays a fixed list of guesses and quits when it runs out.
+#[derive(Debug, Clone)]
+pub struct ScriptedPlayer {
+    actions: std::vec::IntoIter<Action>,
+}
+
+impl ScriptedPlayer {
+    pub fn new(guesses: Vec<u32>) -> Self {
+        ScriptedPlayer::from_actions(guesses.into_iter().map(Action::Guess).collect())
+    }
+
+    pub fn from_actions(actions: Vec<Action>) -> Self {
+        ScriptedPlayer {
+            actions: actions.into_iter(),
+        }
+    }
+
+    /// Reads a script with one guess per line, or `hint NAME` to ask for a hint.
+    /// Blank lines and `#` comments are skipped and `quit` ends the script early.
+    /// Anything else is an error, found before the game starts rather than halfway
+    /// through it.
+    pub fn parse(text: &str) -> Result<Self, ScriptError> {
+        let mut actions = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.eq_ignore_ascii_case("quit") {
+                break;
+            }
+            if let Some(name) = line.strip_prefix("hint ") {
+                let kind = HintKind::parse(name.trim()).ok_or_else(|| ScriptError {
+                    line: index + 1,
+                    message: format!("unknown hint '{}'", name.trim()),
+                })?;
+                actions.push(Action::Hint(kind));
+                continue;
+            }
+            match line.parse() {
+                Ok(guess) => actions.push(Action::Guess(guess)),
+                Err(_) => {
+                    return Err(ScriptError {
+                        line: index + 1,
+                        message: format!("'{}' is not a whole number", line),
+                    });
+                }
+            }
+        }
+        Ok(ScriptedPlayer::from_actions(actions))
+    }
+}
+
+impl Player for ScriptedPlayer {
+    fn next_action(&mut self, _state: &GameState) -> Action {
+        self.actions.next().unwrap_or(Action::Quit)
+    }
+}
@@ -0,0 +1,232 @@
+// difficulty profiles: a few named ones built in, and rules files for everything else
+//
+// a rules file is one `key = value` setting per line, `#` starts a comment:
+//
+//     # lunch break rules
+//     name = lunch
+//     base = normal          # start from a built-in profile (must come first)
+//     range = 1..500
+//     attempts = 9
+//     time_limit = 2m        # 90, 90s or 2m
+//     guess_limit = 15s      # the countdown for every single guess
+//     hints = parity, within
+//
+// every problem is reported with the line it was found on.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::game::Rules;
+use crate::hints::HintKind;
+
+/// Named rules plus the hints a player may ask for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    pub rules: Rules,
+    pub hints: Vec<HintKind>,
+}
+
+pub const BUILTIN_NAMES: [&str; 3] = ["easy", "normal", "hard"];
+
+impl Profile {
+    /// One of the built-in profiles, by name.
+    pub fn builtin(name: &str) -> Option<Profile> {
+        let (low, high, max_attempts) = match name {
+            "easy" => (1, 10, 5),
+            "normal" => (1, 100, 7),
+            // 14 attempts is exactly what a perfect bisection of 10_000 numbers needs
+            "hard" => (1, 10_000, 14),
+            _ => return None,
+        };
+        Some(Profile {
+            name: name.to_string(),
+            rules: Rules {
+                low,
+                high,
+                max_attempts,
+                time_limit: None,
+                guess_limit: None,
+            },
+            hints: HintKind::ALL.to_vec(),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Profile, ProfileError> {
+        let text = fs::read_to_string(path).map_err(|error| ProfileError {
+            line: 0,
+            message: format!("could not read {}: {}", path.display(), error),
+        })?;
+        let default_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "custom".to_string());
+        Profile::parse(&text, &default_name)
+    }
+
+    pub fn parse(text: &str, default_name: &str) -> Result<Profile, ProfileError> {
+        let mut profile = Profile::builtin("normal").expect("normal is a built-in profile");
+        profile.name = default_name.to_string();
+
+        // the line each key was set on, for duplicates and for errors found at the end
+        let mut seen: HashMap<String, usize> = HashMap::new();
+
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let error = |message: String| ProfileError { line, message };
+
+            let content = raw.split('#').next().unwrap_or("").trim();
+            if content.is_empty() {
+                continue;
+            }
+            let (key, value) = content
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| error(format!("expected `key = value`, found '{}'", content)))?;
+            if let Some(first) = seen.insert(key.to_string(), line) {
+                return Err(error(format!("{} is already set on line {}", key, first)));
+            }
+
+            match key {
+                "base" => {
+                    if seen.len() > 1 {
+                        return Err(error("base must be the first setting".to_string()));
+                    }
+                    let base = Profile::builtin(value).ok_or_else(|| {
+                        error(format!(
+                            "unknown profile '{}', expected one of {}",
+                            value,
+                            BUILTIN_NAMES.join(", ")
+                        ))
+                    })?;
+                    profile.rules = base.rules;
+                    profile.hints = base.hints;
+                }
+                "name" => {
+                    if value.is_empty() {
+                        return Err(error("name must not be empty".to_string()));
+                    }
+                    profile.name = value.to_string();
+                }
+                "range" => {
+                    let (low, high) = parse_range(value).map_err(error)?;
+                    profile.rules.low = low;
+                    profile.rules.high = high;
+                }
+                "attempts" => {
+                    profile.rules.max_attempts = value.parse().map_err(|_| {
+                        error(format!("attempts must be a whole number, got '{}'", value))
+                    })?;
+                }
+                "time_limit" => {
+                    profile.rules.time_limit = parse_time_limit(key, value).map_err(error)?
+                }
+                "guess_limit" => {
+                    profile.rules.guess_limit = parse_time_limit(key, value).map_err(error)?
+                }
+                "hints" => profile.hints = parse_hints(value).map_err(error)?,
+                _ => return Err(error(format!("unknown setting '{}'", key))),
+            }
+        }
+
+        // the rules as a whole can only be checked at the end, point at the line to fix
+        profile.rules.validate().map_err(|message| {
+            let key = if profile.rules.low > profile.rules.high {
+                "range"
+            } else if profile.rules.max_attempts == 0 {
+                "attempts"
+            } else if profile.rules.time_limit == Some(Duration::ZERO) {
+                "time_limit"
+            } else {
+                "guess_limit"
+            };
+            ProfileError {
+                line: seen
+                    .get(key)
+                    .or_else(|| seen.get("base"))
+                    .copied()
+                    .unwrap_or(0),
+                message,
+            }
+        })?;
+        Ok(profile)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileError {
+    // 0 when the problem is not on any line, e.g. the file could not be read
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+// `1..100`, `1..=100` and `1-100` all mean the same thing
+fn parse_range(value: &str) -> Result<(u32, u32), String> {
+    let (low, high) = value
+        .split_once("..=")
+        .or_else(|| value.split_once(".."))
+        .or_else(|| value.split_once('-'))
+        .ok_or_else(|| format!("range must look like 1..100, got '{}'", value))?;
+    let number = |text: &str| {
+        text.trim()
+            .parse::<u32>()
+            .map_err(|_| format!("'{}' in the range is not a whole number", text.trim()))
+    };
+    Ok((number(low)?, number(high)?))
+}
+
+// plain numbers are seconds, `s` and `m` can be added to be explicit, `none` turns it off
+fn parse_time_limit(key: &str, value: &str) -> Result<Option<Duration>, String> {
+    if value == "none" {
+        return Ok(None);
+    }
+    let (number, unit) = match value.strip_suffix('m') {
+        Some(minutes) => (minutes, 60),
+        None => (value.strip_suffix('s').unwrap_or(value), 1),
+    };
+    let seconds: u64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("{} must look like 90, 90s or 2m, got '{}'", key, value))?;
+    let seconds = seconds
+        .checked_mul(unit)
+        .ok_or_else(|| format!("{} '{}' is too long", key, value))?;
+    Ok(Some(Duration::from_secs(seconds)))
+}
+
+/// A comma separated list of hint names, or `none`.
+pub fn parse_hints(value: &str) -> Result<Vec<HintKind>, String> {
+    if value == "none" {
+        return Ok(Vec::new());
+    }
+    let mut hints = Vec::new();
+    for name in value.split(',').map(str::trim) {
+        let hint = HintKind::parse(name).ok_or_else(|| {
+            let known: Vec<&str> = HintKind::ALL.iter().map(|kind| kind.name()).collect();
+            format!(
+                "unknown hint '{}', expected one of {}",
+                name,
+                known.join(", ")
+            )
+        })?;
+        if !hints.contains(&hint) {
+            hints.push(hint);
+        }
+    }
+    Ok(hints)
+}
@@ -0,0 +1,177 @@
+// what a game prints when nobody is watching it live: one line per verdict, either
+// as text or as a JSON object per line for scripts to read
+
+use std::fmt;
+use std::io::Write;
+
+use crate::game::{Action, GameState, GuessError, Outcome, Player, Rules, Verdict};
+use crate::hints::{HintError, HintKind};
+use crate::json::Json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl Format {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Text => "text",
+            Format::Json => "json",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(
+        fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+    )
+}
+
+/// `{"event": "start", ...}`, the rules of the game about to be played.
+pub fn start_event(rules: &Rules, seed: u64) -> Json {
+    object(vec![
+        ("event", "start".into()),
+        ("min", rules.low.into()),
+        ("max", rules.high.into()),
+        ("attempts", rules.max_attempts.into()),
+        ("seed", seed.into()),
+    ])
+}
+
+/// `{"event": "verdict", ...}` for a counted guess.
+pub fn verdict_event(guess: u32, verdict: Verdict, state: &GameState) -> Json {
+    object(vec![
+        ("event", "verdict".into()),
+        ("turn", state.attempts().into()),
+        ("guess", guess.into()),
+        ("verdict", verdict.name().into()),
+        ("attempts_left", state.attempts_left().into()),
+    ])
+}
+
+/// `{"event": "rejected", ...}` for a guess the game did not count.
+pub fn rejected_event(error: &GuessError) -> Json {
+    let guess = match error {
+        GuessError::OutOfRange { guess, .. } => Some(*guess),
+        GuessError::GameOver => None,
+    };
+    object(vec![
+        ("event", "rejected".into()),
+        ("guess", guess.into()),
+        ("error", error.to_string().into()),
+    ])
+}
+
+/// `{"event": "hint", ...}` with the hint text, or the error if none was given.
+pub fn hint_event(kind: HintKind, hint: &Result<String, HintError>) -> Json {
+    let (text, error, cost) = match hint {
+        Ok(text) => (Some(text.clone()), None, kind.cost()),
+        Err(error) => (None, Some(error.to_string()), 0),
+    };
+    object(vec![
+        ("event", "hint".into()),
+        ("hint", kind.name().into()),
+        ("text", text.into()),
+        ("error", error.into()),
+        ("cost", cost.into()),
+    ])
+}
+
+/// `{"event": "end", ...}` with the result, `secret` is null if the host never had one.
+pub fn end_event(outcome: &Outcome, secret: Option<u32>) -> Json {
+    let (result, attempts) = match outcome {
+        Outcome::Won { attempts } => ("won", attempts),
+        Outcome::Lost { attempts } => ("lost", attempts),
+        Outcome::Quit { attempts } => ("quit", attempts),
+        Outcome::Saved { attempts } => ("saved", attempts),
+    };
+    object(vec![
+        ("event", "end".into()),
+        ("result", result.into()),
+        ("attempts", (*attempts).into()),
+        ("secret", secret.into()),
+    ])
+}
+
+/// Wraps a player and writes a line for everything the game says to it.
+pub struct Reporter<'a, W> {
+    player: &'a mut dyn Player,
+    output: W,
+    format: Format,
+}
+
+impl<'a, W: Write> Reporter<'a, W> {
+    pub fn new(player: &'a mut dyn Player, output: W, format: Format) -> Self {
+        Reporter {
+            player,
+            output,
+            format,
+        }
+    }
+}
+
+impl<W: Write> Player for Reporter<'_, W> {
+    fn next_action(&mut self, state: &GameState) -> Action {
+        self.player.next_action(state)
+    }
+
+    fn observe(&mut self, guess: u32, verdict: Verdict, state: &GameState) {
+        // a closed pipe must not stop the game, the exit code still tells how it ended
+        let _ = match self.format {
+            Format::Text => writeln!(
+                self.output,
+                "{:>3}. {} -> {}, {} attempts left",
+                state.attempts(),
+                guess,
+                verdict,
+                state.attempts_left()
+            ),
+            Format::Json => writeln!(self.output, "{}", verdict_event(guess, verdict, state)),
+        };
+        self.player.observe(guess, verdict, state);
+    }
+
+    fn rejected(&mut self, error: &GuessError) {
+        let _ = match self.format {
+            Format::Text => writeln!(self.output, "     rejected: {}", error),
+            Format::Json => writeln!(self.output, "{}", rejected_event(error)),
+        };
+        self.player.rejected(error);
+    }
+
+    fn hinted(&mut self, kind: HintKind, hint: &Result<String, HintError>) {
+        let _ = match (self.format, hint) {
+            (Format::Text, Ok(text)) => {
+                writeln!(
+                    self.output,
+                    "     hint {}: {} (-{} points)",
+                    kind,
+                    text,
+                    kind.cost()
+                )
+            }
+            (Format::Text, Err(error)) => writeln!(self.output, "     no {} hint: {}", kind, error),
+            (Format::Json, _) => writeln!(self.output, "{}", hint_event(kind, hint)),
+        };
+        self.player.hinted(kind, hint);
+    }
+}
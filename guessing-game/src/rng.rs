@@ -0,0 +1,60 @@
+// a small seedable random number generator, so the crate needs no dependency
+// and every game can be reproduced from its seed
+//
+// this is xorshift64* (Marsaglia's xorshift with a multiplication on the output),
+// more than good enough to pick secrets, NOT fit for anything security related
+
+use std::hash::{BuildHasher, RandomState};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck on a zero state and similar seeds give similar first
+        // outputs, so the seed is scrambled with one round of splitmix64 first
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        Rng {
+            state: if z == 0 { 0x9E37_79B9_7F4A_7C15 } else { z },
+        }
+    }
+
+    /// A seed that is different on every run, for games that don't ask for one.
+    pub fn random_seed() -> u64 {
+        // RandomState is seeded randomly by the OS for every new instance,
+        // so hashing anything with it gives us a random u64
+        RandomState::new().hash_one(0u8)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A number in 0..n without modulo bias, `n` must not be 0.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "Rng::below needs a non-empty range");
+        // throw away the top values that would make some results more likely than others
+        let zone = u64::MAX - (u64::MAX % n);
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+
+    /// A number in low..=high.
+    pub fn range(&mut self, low: u32, high: u32) -> u32 {
+        low + self.below((high - low) as u64 + 1) as u32
+    }
+}
@@ -0,0 +1,298 @@
+// saved games: a game stopped halfway with `save` and picked up again with --resume
+//
+// a save file is a transcript with a few more lines, the time played so far, the
+// difficulty name for the score board and a signature over all of it. the seed gives
+// away the secret, so it is sealed together with the secret instead of written out:
+//
+//     # guessing-game transcript
+//     sealed 6f1e...c2 9a07...3b    (a nonce, then the seed and the secret encrypted)
+//     range 1 100
+//     attempts 7
+//     guess 50 too-low
+//     elapsed-ms 12345
+//     difficulty normal
+//     hints-allowed parity,within   (`none` when the game has no hints)
+//     signature 8a3c...e1
+//
+// sealing and signing use the install's SaveKey (see seal.rs), which never goes into
+// a save. every save gets a new nonce, which also names it once it was played: a
+// resumed save is used up however that game ends, so no copy of it can be resumed
+// by someone who has seen the answer.
+//
+// the random state is the seed: every host draws from a stream that only depends on
+// the seed and the turn, so playing the history again puts it exactly where it was.
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::game::{GameState, Host};
+use crate::hints::HintKind;
+use crate::host::HostKind;
+use crate::profiles::parse_hints;
+use crate::scores::{ScoreBoard, write_atomic};
+use crate::seal::{SaveKey, from_hex, random_bytes, to_hex};
+use crate::transcript::{Transcript, TranscriptError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedGame {
+    pub transcript: Transcript,
+    pub difficulty: String,
+    pub allowed_hints: Vec<HintKind>,
+    // what the host revealed when the game was saved
+    pub secret: Option<u32>,
+    pub elapsed: Duration,
+    // new for every save, see the top of this module
+    pub nonce: [u8; 16],
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    // the signature does not fit the rest of the file, or another install made it
+    Tampered,
+    // the file is intact but does not describe a game that can go on
+    Mismatch(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(error) => write!(f, "{}", error),
+            SaveError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            SaveError::Tampered => write!(
+                f,
+                "the file was changed after the game was saved, or saved by another install"
+            ),
+            SaveError::Mismatch(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(error: io::Error) -> Self {
+        SaveError::Io(error)
+    }
+}
+
+impl From<TranscriptError> for SaveError {
+    fn from(error: TranscriptError) -> Self {
+        match error {
+            TranscriptError::Io(error) => SaveError::Io(error),
+            TranscriptError::Parse { line, message } => SaveError::Parse { line, message },
+        }
+    }
+}
+
+// the seed, then 1 and the secret or 0 and nothing for a host without one
+const SEALED_LENGTH: usize = 13;
+
+fn seal(key: &SaveKey, nonce: &[u8], seed: u64, secret: Option<u32>) -> String {
+    let mut data = Vec::with_capacity(SEALED_LENGTH);
+    data.extend_from_slice(&seed.to_be_bytes());
+    match secret {
+        Some(secret) => {
+            data.push(1);
+            data.extend_from_slice(&secret.to_be_bytes());
+        }
+        None => data.extend_from_slice(&[0; 5]),
+    }
+    key.apply_keystream(nonce, &mut data);
+    format!("sealed {} {}", to_hex(nonce), to_hex(&data))
+}
+
+// the nonce, the seed and the secret from the value of a sealed line
+fn unseal(key: &SaveKey, value: &str) -> Option<([u8; 16], u64, Option<u32>)> {
+    let (nonce, data) = value.split_once(' ')?;
+    let nonce: [u8; 16] = from_hex(nonce)?.try_into().ok()?;
+    let mut data: [u8; SEALED_LENGTH] = from_hex(data)?.try_into().ok()?;
+    key.apply_keystream(&nonce, &mut data);
+
+    let seed = u64::from_be_bytes(data[..8].try_into().ok()?);
+    let secret = u32::from_be_bytes(data[9..].try_into().ok()?);
+    let secret = match data[8] {
+        0 => None,
+        1 => Some(secret),
+        _ => return None,
+    };
+    Some((nonce, seed, secret))
+}
+
+impl SavedGame {
+    pub fn from_game(
+        seed: u64,
+        host: HostKind,
+        state: &GameState,
+        secret: Option<u32>,
+        elapsed: Duration,
+        difficulty: &str,
+    ) -> Self {
+        SavedGame {
+            transcript: Transcript::from_game(seed, host, state),
+            difficulty: difficulty.to_string(),
+            allowed_hints: state.allowed_hints().to_vec(),
+            secret,
+            elapsed,
+            nonce: random_bytes(),
+        }
+    }
+
+    /// Where `save` writes when no file is named, next to the score board.
+    pub fn default_path() -> Option<PathBuf> {
+        ScoreBoard::default_path().map(|path| path.with_file_name("saved-game.txt"))
+    }
+
+    /// Where the saves that were played are remembered, next to the score board.
+    pub fn spent_dir() -> Option<PathBuf> {
+        ScoreBoard::default_path().map(|path| path.with_file_name("spent-saves"))
+    }
+
+    pub fn to_text(&self, key: &SaveKey) -> String {
+        // the seed line becomes the sealed one, so the line numbers stay the same
+        let seed_line = format!("seed {}\n", self.transcript.seed);
+        let sealed = seal(key, &self.nonce, self.transcript.seed, self.secret);
+        let mut text = self
+            .transcript
+            .to_text()
+            .replacen(&seed_line, &format!("{}\n", sealed), 1);
+        text.push_str(&format!("elapsed-ms {}\n", self.elapsed.as_millis()));
+        text.push_str(&format!("difficulty {}\n", self.difficulty));
+        let allowed: Vec<&str> = self.allowed_hints.iter().map(|hint| hint.name()).collect();
+        if allowed.is_empty() {
+            text.push_str("hints-allowed none\n");
+        } else {
+            text.push_str(&format!("hints-allowed {}\n", allowed.join(",")));
+        }
+        let signature = key.sign(&text);
+        text.push_str(&format!("signature {}\n", to_hex(&signature)));
+        text
+    }
+
+    pub fn parse(text: &str, key: &SaveKey) -> Result<Self, SaveError> {
+        // the signature is the last line and covers every byte before it
+        let trimmed = text.trim_end();
+        let body_end = trimmed.rfind('\n').map_or(0, |at| at + 1);
+        let (body, last) = trimmed.split_at(body_end);
+        let last_line = trimmed.lines().count();
+        let signature = last
+            .strip_prefix("signature ")
+            .ok_or_else(|| SaveError::Parse {
+                line: last_line,
+                message: "the save has no signature line".to_string(),
+            })?;
+        let signature = from_hex(signature.trim()).ok_or_else(|| SaveError::Parse {
+            line: last_line,
+            message: format!("'{}' is not a signature", signature.trim()),
+        })?;
+        if !key.verify(body, &signature) {
+            return Err(SaveError::Tampered);
+        }
+
+        let mut elapsed = None;
+        let mut difficulty = None;
+        let mut allowed_hints = None;
+        let mut sealed = None;
+        // the extra lines are blanked out so the transcript reports the right line numbers,
+        // and the sealed line is put back as the seed line it was
+        let mut lines = Vec::new();
+        for (index, line) in body.lines().enumerate() {
+            let error = |message: String| SaveError::Parse {
+                line: index + 1,
+                message,
+            };
+            let trimmed = line.trim();
+            if let Some(value) = trimmed.strip_prefix("elapsed-ms ") {
+                let ms = value
+                    .parse()
+                    .map_err(|_| error(format!("'{}' is not a valid elapsed-ms value", value)))?;
+                elapsed = Some(Duration::from_millis(ms));
+            } else if let Some(value) = trimmed.strip_prefix("difficulty ") {
+                difficulty = Some(value.to_string());
+            } else if let Some(value) = trimmed.strip_prefix("hints-allowed ") {
+                allowed_hints = Some(parse_hints(value).map_err(error)?);
+            } else if let Some(value) = trimmed.strip_prefix("sealed ") {
+                let (nonce, seed, secret) = unseal(key, value)
+                    .ok_or_else(|| error(format!("'{}' is not a sealed game", value)))?;
+                sealed = Some((nonce, secret));
+                lines.push(format!("seed {}", seed));
+                continue;
+            } else {
+                lines.push(line.to_string());
+                continue;
+            }
+            lines.push(String::new());
+        }
+
+        let missing = |what: &str| SaveError::Parse {
+            line: last_line,
+            message: format!("the save has no {} line", what),
+        };
+        let (nonce, secret) = sealed.ok_or_else(|| missing("sealed"))?;
+        let transcript = Transcript::parse(&lines.join("\n"))?;
+        Ok(SavedGame {
+            elapsed: elapsed.ok_or_else(|| missing("elapsed-ms"))?,
+            difficulty: difficulty.ok_or_else(|| missing("difficulty"))?,
+            allowed_hints: allowed_hints.ok_or_else(|| missing("hints-allowed"))?,
+            secret,
+            transcript,
+            nonce,
+        })
+    }
+
+    /// Writes the save to a temp file and renames it into place, like the score board.
+    pub fn save(&self, path: &Path, key: &SaveKey) -> Result<(), SaveError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        write_atomic(path, &self.to_text(key))?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path, key: &SaveKey) -> Result<Self, SaveError> {
+        SavedGame::parse(&fs::read_to_string(path)?, key)
+    }
+
+    /// Whether this save, or a copy of it, was already resumed and played.
+    pub fn is_spent(&self, dir: &Path) -> bool {
+        dir.join(to_hex(&self.nonce)).exists()
+    }
+
+    /// Remembers that this save was played, so no copy of it can be resumed again.
+    /// `false` when that had already happened, e.g. the same save was played twice at once.
+    pub fn spend(&self, dir: &Path) -> io::Result<bool> {
+        fs::create_dir_all(dir)?;
+        let marker = dir.join(to_hex(&self.nonce));
+        match OpenOptions::new().write(true).create_new(true).open(marker) {
+            Ok(_) => Ok(true),
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// The game and its host as they were when the game was saved.
+    ///
+    /// The host is rebuilt from the seed and asked every guess again, so it has to
+    /// give the same verdicts and end up with the saved secret.
+    pub fn resume(&self) -> Result<(GameState, Box<dyn Host>), SaveError> {
+        let (state, host) = self
+            .transcript
+            .rebuild()
+            .map_err(|error| SaveError::Mismatch(error.to_string()))?;
+        if host.reveal() != self.secret {
+            return Err(SaveError::Mismatch(
+                "the secret does not belong to this game".to_string(),
+            ));
+        }
+        if state.is_over() {
+            return Err(SaveError::Mismatch(
+                "the saved game is already over".to_string(),
+            ));
+        }
+        Ok((state.with_hints(&self.allowed_hints), host))
+    }
+}
@@ -0,0 +1,410 @@
+// the local high-score file
+//
+// every finished game is one line of tab separated values, appended in the order
+// the games were played:
+//
+//     player  difficulty  won  attempts  elapsed_ms  date  hints  score
+//
+// files from before hints have no last two columns, those games used no hints.
+//
+// several games can finish at the same time (a whole team on one machine), so
+// writers take a lock file, write the new content to a temp file and rename it over
+// the old one. a reader never sees half a file and no writer loses another's line.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::domains::Date;
+use crate::hints::HintKind;
+
+const HEADER: &str = "# player\tdifficulty\twon\tattempts\telapsed_ms\tdate\thints\tscore";
+
+// a won game starts with this many points, every attempt after the first and every
+// second on the clock take some off, and so does every hint (see HintKind::cost)
+const FULL_SCORE: u32 = 1000;
+const ATTEMPT_COST: u32 = 50;
+const SECOND_COST: u32 = 1;
+
+/// The points for a game, 0 for a lost one and never below 0.
+pub fn score(won: bool, attempts: u32, hints: &[HintKind], elapsed: Duration) -> u32 {
+    if !won {
+        return 0;
+    }
+    let hints: u32 = hints.iter().map(|hint| hint.cost()).sum();
+    let seconds = u32::try_from(elapsed.as_secs()).unwrap_or(u32::MAX);
+    FULL_SCORE
+        .saturating_sub(attempts.saturating_sub(1).saturating_mul(ATTEMPT_COST))
+        .saturating_sub(hints)
+        .saturating_sub(seconds.saturating_mul(SECOND_COST))
+}
+
+// how long a writer waits for the lock, and when a lock counts as left over by a crash
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const STALE_LOCK: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreEntry {
+    pub player: String,
+    pub difficulty: String,
+    pub won: bool,
+    pub attempts: u32,
+    pub elapsed: Duration,
+    // UTC, formatted like 2024-05-01T12:30:00Z
+    pub date: String,
+    pub hints: u32,
+    pub score: u32,
+}
+
+impl ScoreEntry {
+    /// An entry for a game that finished just now, without hints.
+    pub fn now(
+        player: &str,
+        difficulty: &str,
+        won: bool,
+        attempts: u32,
+        elapsed: Duration,
+    ) -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs())
+            .unwrap_or(0);
+        ScoreEntry {
+            player: clean_field(player),
+            difficulty: clean_field(difficulty),
+            won,
+            attempts,
+            elapsed,
+            date: format_utc(seconds),
+            hints: 0,
+            score: score(won, attempts, &[], elapsed),
+        }
+    }
+
+    /// The same entry with the hints the player took, which lowers the score.
+    pub fn with_hints(mut self, hints: &[HintKind]) -> Self {
+        self.hints = hints.len() as u32;
+        self.score = score(self.won, self.attempts, hints, self.elapsed);
+        self
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.player,
+            self.difficulty,
+            if self.won { "won" } else { "lost" },
+            self.attempts,
+            self.elapsed.as_millis(),
+            self.date,
+            self.hints,
+            self.score
+        )
+    }
+
+    fn parse_line(line: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let (player, difficulty, won, attempts, elapsed, date, scored) = match fields.as_slice() {
+            [player, difficulty, won, attempts, elapsed, date] => {
+                (player, difficulty, won, attempts, elapsed, date, None)
+            }
+            [
+                player,
+                difficulty,
+                won,
+                attempts,
+                elapsed,
+                date,
+                hints,
+                score,
+            ] => (
+                player,
+                difficulty,
+                won,
+                attempts,
+                elapsed,
+                date,
+                Some((hints, score)),
+            ),
+            _ => return Err(format!("expected 6 or 8 fields, found {}", fields.len())),
+        };
+        let won = match *won {
+            "won" => true,
+            "lost" => false,
+            other => return Err(format!("'{}' should be won or lost", other)),
+        };
+        let attempts = attempts
+            .parse()
+            .map_err(|_| format!("'{}' is not a number of attempts", attempts))?;
+        let elapsed = Duration::from_millis(
+            elapsed
+                .parse()
+                .map_err(|_| format!("'{}' is not a time in milliseconds", elapsed))?,
+        );
+        let (hints, score) = match scored {
+            Some((hints, points)) => (
+                hints
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a number of hints", hints))?,
+                points
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a score", points))?,
+            ),
+            None => (0, score(won, attempts, &[], elapsed)),
+        };
+        Ok(ScoreEntry {
+            player: player.to_string(),
+            difficulty: difficulty.to_string(),
+            won,
+            attempts,
+            elapsed,
+            date: date.to_string(),
+            hints,
+            score,
+        })
+    }
+}
+
+// tabs and newlines would break the file format
+fn clean_field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+pub struct ScoreBoard {
+    path: PathBuf,
+}
+
+impl ScoreBoard {
+    pub fn new(path: PathBuf) -> Self {
+        ScoreBoard { path }
+    }
+
+    /// `scores.tsv` in the user's data dir, if we can find one.
+    pub fn default_path() -> Option<PathBuf> {
+        let data_dir = env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))?;
+        Some(data_dir.join("guessing-game").join("scores.tsv"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every recorded game, oldest first. A missing file is an empty board.
+    pub fn load(&self) -> io::Result<Vec<ScoreEntry>> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+
+        let mut entries = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = ScoreEntry::parse_line(line).map_err(|message| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{} line {}: {}", self.path.display(), index + 1, message),
+                )
+            })?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    /// Adds one game to the file.
+    pub fn record(&self, entry: &ScoreEntry) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let _lock = FileLock::acquire(&self.path.with_extension("lock"))?;
+
+        // the old lines are copied as they are, even ones this version can't read
+        let mut text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == ErrorKind::NotFound => format!("{}\n", HEADER),
+            Err(error) => return Err(error),
+        };
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&entry.to_line());
+        text.push('\n');
+
+        write_atomic(&self.path, &text)
+    }
+}
+
+/// Writes `text` to a temp file next to `path` and renames it over `path`, so a
+/// reader finds the old content or the new one and never half of it.
+pub(crate) fn write_atomic(path: &Path, text: &str) -> io::Result<()> {
+    let temp = path.with_extension(format!("tmp.{}", process::id()));
+    let written = write_synced(&temp, text).and_then(|_| fs::rename(&temp, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    written
+}
+
+fn write_synced(path: &Path, text: &str) -> io::Result<()> {
+    let mut file = fs::File::create(path)?;
+    file.write_all(text.as_bytes())?;
+    // make sure the data is on disk before the rename makes it the real file
+    file.sync_all()
+}
+
+// a lock file that only one process can create, removed again on drop
+struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    fn acquire(path: &Path) -> io::Result<Self> {
+        let started = SystemTime::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(_) => {
+                    return Ok(FileLock {
+                        path: path.to_path_buf(),
+                    });
+                }
+                Err(error) if error.kind() == ErrorKind::AlreadyExists => {}
+                Err(error) => return Err(error),
+            }
+
+            // a writer that crashed never removes its lock, so very old locks are broken
+            let age = fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok());
+            if age.is_some_and(|age| age > STALE_LOCK) {
+                break_stale(path);
+                continue;
+            }
+            if started.elapsed().unwrap_or_default() > LOCK_TIMEOUT {
+                return Err(io::Error::new(
+                    ErrorKind::TimedOut,
+                    format!("{} is locked by another game", path.display()),
+                ));
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+// two waiters can both see the same old lock, and a plain remove by the slower one
+// would delete the lock the faster one has just created. so the lock is first moved
+// to a name only this thread uses, and only removed if what was moved is still old.
+fn break_stale(path: &Path) {
+    let moved = path.with_extension(format!(
+        "stale.{}.{:?}",
+        process::id(),
+        thread::current().id()
+    ));
+    if fs::rename(path, &moved).is_err() {
+        // somebody else got to it first
+        return;
+    }
+    let still_stale = fs::metadata(&moved)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > STALE_LOCK);
+    if !still_stale {
+        // a fresh lock was moved away from its owner, so it goes back. a hard link never
+        // replaces a file, if yet another lock was created meanwhile that one stays
+        let _ = fs::hard_link(&moved, path);
+    }
+    let _ = fs::remove_file(&moved);
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// What one player did over all recorded games of one difficulty or mode.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PlayerStats {
+    pub player: String,
+    // the difficulty or game mode, attempts in different ones can't be compared
+    pub difficulty: String,
+    pub games: u32,
+    pub wins: u32,
+    pub best_streak: u32,
+    // attempts needed -> how many won games needed that many
+    pub histogram: BTreeMap<u32, u32>,
+}
+
+impl PlayerStats {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.games as f64
+        }
+    }
+}
+
+/// Stats for every player and difficulty, sorted by name and then difficulty.
+/// Entries must be oldest first for the streaks.
+pub fn player_stats(entries: &[ScoreEntry]) -> Vec<PlayerStats> {
+    let mut by_player: BTreeMap<(&str, &str), (PlayerStats, u32)> = BTreeMap::new();
+
+    for entry in entries {
+        let (stats, streak) = by_player
+            .entry((&entry.player, &entry.difficulty))
+            .or_default();
+        stats.games += 1;
+        if entry.won {
+            stats.wins += 1;
+            *stats.histogram.entry(entry.attempts).or_insert(0) += 1;
+            *streak += 1;
+            stats.best_streak = stats.best_streak.max(*streak);
+        } else {
+            *streak = 0;
+        }
+    }
+
+    by_player
+        .into_iter()
+        .map(|((player, difficulty), (mut stats, _))| {
+            stats.player = player.to_string();
+            stats.difficulty = difficulty.to_string();
+            stats
+        })
+        .collect()
+}
+
+/// The best won games: highest score first, then fewest attempts, then the fastest.
+pub fn high_scores(entries: &[ScoreEntry], count: usize) -> Vec<&ScoreEntry> {
+    let mut wins: Vec<&ScoreEntry> = entries.iter().filter(|entry| entry.won).collect();
+    wins.sort_by_key(|entry| (Reverse(entry.score), entry.attempts, entry.elapsed));
+    wins.truncate(count);
+    wins
+}
+
+// turns seconds since 1970 into an ISO date and time
+fn format_utc(seconds: u64) -> String {
+    let date = Date::from_days((seconds / 86_400) as i64);
+    let time = seconds % 86_400;
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        date,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
@@ -0,0 +1,228 @@
+// what keeps a save file honest: a key that belongs to this install and never goes
+// into a save, SHA-256 and HMAC to sign a file with it and a keystream to hide
+// the seed and the secret
+//
+// the crate has no dependencies, so SHA-256 (FIPS 180-4) and HMAC (RFC 2104) are
+// written out here. the key lives next to the score board. someone who digs it out
+// can still read their own saves, but the answer is no longer in the save itself.
+
+use std::fmt;
+use std::fs;
+use std::hash::{BuildHasher, RandomState};
+use std::io::{self, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use crate::scores::ScoreBoard;
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// The SHA-256 digest of `data`.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    // the message, a 1 bit, zeros up to 56 bytes into a block and the length in bits
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choose = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(choose)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(majority);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (state, word) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *state = state.wrapping_add(word);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (bytes, word) in digest.chunks_exact_mut(4).zip(h) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+/// HMAC-SHA256 of the parts one after the other.
+pub fn hmac_sha256(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if key.len() > 64 {
+        block[..32].copy_from_slice(&sha256(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner: Vec<u8> = block.iter().map(|byte| byte ^ 0x36).collect();
+    for part in parts {
+        inner.extend_from_slice(part);
+    }
+    let mut outer: Vec<u8> = block.iter().map(|byte| byte ^ 0x5c).collect();
+    outer.extend_from_slice(&sha256(&inner));
+    sha256(&outer)
+}
+
+/// Random bytes from the OS, for keys and nonces.
+pub fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    let from_os = fs::File::open("/dev/urandom").and_then(|mut file| file.read_exact(&mut bytes));
+    if from_os.is_err() {
+        // no /dev/urandom (Windows): RandomState is keyed by the OS's random source,
+        // so hashing a counter with it gives random bytes too
+        let state = RandomState::new();
+        for (i, chunk) in bytes.chunks_mut(8).enumerate() {
+            chunk.copy_from_slice(&state.hash_one(i).to_le_bytes()[..chunk.len()]);
+        }
+    }
+    bytes
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|at| u8::from_str_radix(&text[at..at + 2], 16).ok())
+        .collect()
+}
+
+/// The key that seals the save files of one install.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SaveKey([u8; 32]);
+
+// the key never shows up in logs or test output
+impl fmt::Debug for SaveKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SaveKey(..)")
+    }
+}
+
+impl SaveKey {
+    pub fn random() -> SaveKey {
+        SaveKey(random_bytes())
+    }
+
+    /// `save.key` next to the score board.
+    pub fn default_path() -> Option<PathBuf> {
+        ScoreBoard::default_path().map(|path| path.with_file_name("save.key"))
+    }
+
+    /// Reads the key at `path`, making one the first time it is needed.
+    pub fn load_or_create(path: &Path) -> io::Result<SaveKey> {
+        match fs::read_to_string(path) {
+            Ok(text) => return SaveKey::parse(&text, path),
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // the whole key is written first and then linked into place, a link never
+        // replaces a file, so when two games make a key at once both end up with one
+        let temp = path.with_extension(format!("tmp.{}", process::id()));
+        fs::write(&temp, format!("{}\n", to_hex(&SaveKey::random().0)))?;
+        let linked = fs::hard_link(&temp, path);
+        let _ = fs::remove_file(&temp);
+        match linked {
+            Ok(()) => {}
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => {}
+            Err(error) => return Err(error),
+        }
+        SaveKey::parse(&fs::read_to_string(path)?, path)
+    }
+
+    fn parse(text: &str, path: &Path) -> io::Result<SaveKey> {
+        from_hex(text.trim())
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(SaveKey)
+            .ok_or_else(|| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{} is not a save key", path.display()),
+                )
+            })
+    }
+
+    // one key per use, so the signature and the keystream have nothing in common
+    fn derive(&self, purpose: &str) -> [u8; 32] {
+        hmac_sha256(&self.0, &[purpose.as_bytes()])
+    }
+
+    /// The signature of `text`.
+    pub fn sign(&self, text: &str) -> [u8; 32] {
+        hmac_sha256(&self.derive("sign"), &[text.as_bytes()])
+    }
+
+    /// Whether `signature` belongs to `text`, without stopping at the first wrong byte.
+    pub fn verify(&self, text: &str, signature: &[u8]) -> bool {
+        let expected = self.sign(text);
+        signature.len() == expected.len()
+            && expected
+                .iter()
+                .zip(signature)
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+
+    /// Encrypts or decrypts `data` (it is the same) with a keystream that depends on
+    /// `nonce`, which must be different for every save.
+    pub fn apply_keystream(&self, nonce: &[u8], data: &mut [u8]) {
+        let key = self.derive("keystream");
+        for (counter, chunk) in data.chunks_mut(32).enumerate() {
+            let stream = hmac_sha256(&key, &[nonce, &(counter as u64).to_be_bytes()]);
+            for (byte, mask) in chunk.iter_mut().zip(stream) {
+                *byte ^= mask;
+            }
+        }
+    }
+}
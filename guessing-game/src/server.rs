@@ -0,0 +1,599 @@
+// a small TCP server so several people can play at once, and the client for it
+//
+// the protocol is one command per line in each direction. a client sends
+//   JOIN <name> solo|race, GUESS <n> or QUIT
+// and the server answers with
+//   HELLO guessing-game 1                      right after connecting
+//   WELCOME <room> <low> <high> <attempts>     after JOIN
+//   VERDICT <guess> <verdict> <attempts left>  after every GUESS
+//   SECRET <n>                                 when a solo game is lost, and to every
+//                                              racer once all of them are out of a round
+//   WINNER <name> <secret>                     to every racer when someone found the secret
+//   ROUND <n>                                  a new race round with a new secret has started
+//   BYE                                        after QUIT
+//   ERROR <message>                            for anything that went wrong
+//
+// every connection gets its own thread. solo games belong to their connection, the
+// race room is shared: all racers guess the same secret, and whoever finds it first
+// wins the round for everyone. when nobody found it, the round ends once every racer
+// is out of attempts.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use crate::game::{GameState, Rules, Verdict};
+use crate::host::SecretHost;
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// names are sent as one word, and should fit on a line of the race results
+const MAX_NAME_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Room {
+    Solo,
+    Race,
+}
+
+impl Room {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Room::Solo => "solo",
+            Room::Race => "race",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Room> {
+        match name {
+            "solo" => Some(Room::Solo),
+            "race" => Some(Room::Race),
+            _ => None,
+        }
+    }
+}
+
+/// A line sent by a client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    Join { name: String, room: Room },
+    Guess(u32),
+    Quit,
+}
+
+impl Request {
+    pub fn parse(line: &str) -> Result<Request, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["JOIN", name, room] => {
+                let room = Room::parse(room)
+                    .ok_or_else(|| format!("unknown room '{}', expected solo or race", room))?;
+                if name.len() > MAX_NAME_LENGTH {
+                    return Err(format!(
+                        "names can have at most {} characters",
+                        MAX_NAME_LENGTH
+                    ));
+                }
+                Ok(Request::Join {
+                    name: name.to_string(),
+                    room,
+                })
+            }
+            ["GUESS", number] => number
+                .parse()
+                .map(Request::Guess)
+                .map_err(|_| format!("'{}' is not a whole number", number)),
+            ["QUIT"] => Ok(Request::Quit),
+            [] => Err("empty line".to_string()),
+            [command, ..] => Err(format!("unknown command '{}'", command)),
+        }
+    }
+}
+
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Request::Join { name, room } => write!(f, "JOIN {} {}", name, room.name()),
+            Request::Guess(guess) => write!(f, "GUESS {}", guess),
+            Request::Quit => write!(f, "QUIT"),
+        }
+    }
+}
+
+/// A line sent by the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    Hello {
+        version: u32,
+    },
+    Welcome {
+        room: Room,
+        low: u32,
+        high: u32,
+        attempts: u32,
+    },
+    Verdict {
+        guess: u32,
+        verdict: Verdict,
+        attempts_left: u32,
+    },
+    Secret(u32),
+    Winner {
+        name: String,
+        secret: u32,
+    },
+    Round(u32),
+    Bye,
+    Error(String),
+}
+
+impl Reply {
+    pub fn parse(line: &str) -> Result<Reply, String> {
+        let number = |word: &str| {
+            word.parse::<u32>()
+                .map_err(|_| format!("'{}' is not a whole number", word))
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["HELLO", "guessing-game", version] => Ok(Reply::Hello {
+                version: number(version)?,
+            }),
+            ["WELCOME", room, low, high, attempts] => Ok(Reply::Welcome {
+                room: Room::parse(room).ok_or_else(|| format!("unknown room '{}'", room))?,
+                low: number(low)?,
+                high: number(high)?,
+                attempts: number(attempts)?,
+            }),
+            ["VERDICT", guess, verdict, attempts_left] => Ok(Reply::Verdict {
+                guess: number(guess)?,
+                verdict: Verdict::parse(verdict)
+                    .ok_or_else(|| format!("unknown verdict '{}'", verdict))?,
+                attempts_left: number(attempts_left)?,
+            }),
+            ["SECRET", secret] => Ok(Reply::Secret(number(secret)?)),
+            ["WINNER", name, secret] => Ok(Reply::Winner {
+                name: name.to_string(),
+                secret: number(secret)?,
+            }),
+            ["ROUND", round] => Ok(Reply::Round(number(round)?)),
+            ["BYE"] => Ok(Reply::Bye),
+            ["ERROR", ..] => Ok(Reply::Error(
+                line.trim_start()["ERROR".len()..].trim().to_string(),
+            )),
+            _ => Err(format!("unexpected line '{}'", line.trim())),
+        }
+    }
+}
+
+impl fmt::Display for Reply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reply::Hello { version } => write!(f, "HELLO guessing-game {}", version),
+            Reply::Welcome {
+                room,
+                low,
+                high,
+                attempts,
+            } => write!(f, "WELCOME {} {} {} {}", room.name(), low, high, attempts),
+            Reply::Verdict {
+                guess,
+                verdict,
+                attempts_left,
+            } => write!(f, "VERDICT {} {} {}", guess, verdict.name(), attempts_left),
+            Reply::Secret(secret) => write!(f, "SECRET {}", secret),
+            Reply::Winner { name, secret } => write!(f, "WINNER {} {}", name, secret),
+            Reply::Round(round) => write!(f, "ROUND {}", round),
+            Reply::Bye => write!(f, "BYE"),
+            Reply::Error(message) => write!(f, "ERROR {}", message),
+        }
+    }
+}
+
+fn send(stream: &mut impl Write, reply: &Reply) -> io::Result<()> {
+    writeln!(stream, "{}", reply)?;
+    stream.flush()
+}
+
+// the shared room: one secret per round and a line to every racer for the results
+struct Race {
+    round: u32,
+    host: SecretHost,
+    racers: Vec<Racer>,
+}
+
+struct Racer {
+    id: u64,
+    stream: Arc<TcpStream>,
+    // out of attempts (or time) in the current round
+    out: bool,
+}
+
+impl Race {
+    fn all_out(&self) -> bool {
+        !self.racers.is_empty() && self.racers.iter().all(|racer| racer.out)
+    }
+
+    // starts the next round after `ending` and hands back what every racer has to be told
+    fn next_round(&mut self, shared: &Shared, ending: Reply) -> Results {
+        self.round += 1;
+        self.host = SecretHost::seeded(&shared.rules, shared.next_seed());
+        for racer in &mut self.racers {
+            racer.out = false;
+        }
+        Results {
+            replies: [ending, Reply::Round(self.round)],
+            racers: self
+                .racers
+                .iter()
+                .map(|racer| (racer.id, Arc::clone(&racer.stream)))
+                .collect(),
+        }
+    }
+}
+
+// the end of a round, sent once the room is unlocked so one slow client can't block it
+struct Results {
+    replies: [Reply; 2],
+    racers: Vec<(u64, Arc<TcpStream>)>,
+}
+
+impl Results {
+    fn send(self, shared: &Shared) {
+        let gone: Vec<u64> = self
+            .racers
+            .iter()
+            .filter(|(_, stream)| {
+                let mut stream: &TcpStream = stream;
+                self.replies
+                    .iter()
+                    .any(|reply| send(&mut stream, reply).is_err())
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        // racers that went away are dropped here rather than failing whoever ended the round
+        if !gone.is_empty() {
+            let mut race = shared.race.lock().unwrap();
+            race.racers.retain(|racer| !gone.contains(&racer.id));
+        }
+    }
+}
+
+struct Shared {
+    rules: Rules,
+    seed: u64,
+    // every solo game and race round takes the next number as its secret's seed
+    games: AtomicU64,
+    race: Mutex<Race>,
+}
+
+impl Shared {
+    fn next_seed(&self) -> u64 {
+        self.seed
+            .wrapping_add(self.games.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A game server, see the top of this module for the protocol.
+pub struct Server {
+    listener: TcpListener,
+    shared: Arc<Shared>,
+}
+
+impl Server {
+    /// Listens on `addr` (port 0 picks a free one). Secrets are drawn from `seed`,
+    /// in the order games are started.
+    pub fn bind(addr: impl ToSocketAddrs, rules: Rules, seed: u64) -> io::Result<Server> {
+        let listener = TcpListener::bind(addr)?;
+        let shared = Shared {
+            rules,
+            seed,
+            games: AtomicU64::new(1),
+            race: Mutex::new(Race {
+                round: 1,
+                host: SecretHost::seeded(&rules, seed),
+                racers: Vec::new(),
+            }),
+        };
+        Ok(Server {
+            listener,
+            shared: Arc::new(shared),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts connections until the listener fails, one thread each.
+    pub fn run(self) -> io::Result<()> {
+        for (id, stream) in (1u64..).zip(self.listener.incoming()) {
+            let stream = stream?;
+            let shared = Arc::clone(&self.shared);
+            thread::spawn(move || {
+                let mut session = Session {
+                    id,
+                    shared,
+                    game: None,
+                };
+                // a client that hangs up is not the server's problem
+                let _ = session.serve(stream);
+                session.leave();
+            });
+        }
+        Ok(())
+    }
+}
+
+// what one connection is playing
+enum Game {
+    Solo {
+        state: GameState,
+        host: SecretHost,
+        started: Instant,
+    },
+    Race {
+        name: String,
+        // the round `state` belongs to, a new round starts a new state
+        round: u32,
+        state: GameState,
+        started: Instant,
+    },
+}
+
+struct Session {
+    id: u64,
+    shared: Arc<Shared>,
+    game: Option<Game>,
+}
+
+impl Session {
+    fn serve(&mut self, stream: TcpStream) -> io::Result<()> {
+        let mut writer = stream.try_clone()?;
+        send(
+            &mut writer,
+            &Reply::Hello {
+                version: PROTOCOL_VERSION,
+            },
+        )?;
+
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            let request = match Request::parse(&line) {
+                Ok(request) => request,
+                Err(message) => {
+                    send(&mut writer, &Reply::Error(message))?;
+                    continue;
+                }
+            };
+            match request {
+                Request::Join { name, room } => self.join(&mut writer, name, room)?,
+                Request::Guess(guess) => self.guess(&mut writer, guess)?,
+                Request::Quit => {
+                    send(&mut writer, &Reply::Bye)?;
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn join(&mut self, writer: &mut TcpStream, name: String, room: Room) -> io::Result<()> {
+        // a finished solo game may be followed by another one, anything else stays put
+        let can_join = match &self.game {
+            None => true,
+            Some(Game::Solo { state, .. }) => state.is_over(),
+            Some(Game::Race { .. }) => false,
+        };
+        if !can_join {
+            return send(
+                writer,
+                &Reply::Error("you are already in a game".to_string()),
+            );
+        }
+
+        let rules = self.shared.rules;
+        let now = Instant::now();
+        self.game = Some(match room {
+            Room::Solo => Game::Solo {
+                state: GameState::new(rules),
+                host: SecretHost::seeded(&rules, self.shared.next_seed()),
+                started: now,
+            },
+            Room::Race => {
+                let mut race = self.shared.race.lock().unwrap();
+                race.racers.push(Racer {
+                    id: self.id,
+                    stream: Arc::new(writer.try_clone()?),
+                    out: false,
+                });
+                Game::Race {
+                    name,
+                    round: race.round,
+                    state: GameState::new(rules),
+                    started: now,
+                }
+            }
+        });
+        send(
+            writer,
+            &Reply::Welcome {
+                room,
+                low: rules.low,
+                high: rules.high,
+                attempts: rules.max_attempts,
+            },
+        )
+    }
+
+    fn guess(&mut self, writer: &mut TcpStream, guess: u32) -> io::Result<()> {
+        let shared = Arc::clone(&self.shared);
+        let id = self.id;
+        match &mut self.game {
+            None => send(writer, &Reply::Error("JOIN a room first".to_string())),
+            Some(Game::Solo {
+                state,
+                host,
+                started,
+            }) => {
+                let verdict = match guess_in_time(state, host, guess, *started) {
+                    Ok(verdict) => verdict,
+                    Err(message) => return send(writer, &Reply::Error(message)),
+                };
+                send(writer, &verdict_reply(state, guess, verdict))?;
+                if verdict.is_final() && verdict != Verdict::Correct {
+                    send(writer, &Reply::Secret(host.secret()))?;
+                }
+                Ok(())
+            }
+            Some(Game::Race {
+                name,
+                round,
+                state,
+                started,
+            }) => {
+                // the verdict and the end of the round happen under the lock, so nobody
+                // can win twice, the replies are sent after it
+                let mut race = shared.race.lock().unwrap();
+                if *round != race.round {
+                    *round = race.round;
+                    *state = GameState::new(shared.rules);
+                    *started = Instant::now();
+                }
+                if state.is_over() {
+                    drop(race);
+                    return send(
+                        writer,
+                        &Reply::Error("you are out of this round, wait for the next".to_string()),
+                    );
+                }
+                let verdict = match guess_in_time(state, &mut race.host, guess, *started) {
+                    Ok(verdict) => verdict,
+                    Err(message) => {
+                        drop(race);
+                        return send(writer, &Reply::Error(message));
+                    }
+                };
+
+                let secret = race.host.secret();
+                let results = if verdict == Verdict::Correct {
+                    let winner = Reply::Winner {
+                        name: name.clone(),
+                        secret,
+                    };
+                    Some(race.next_round(&shared, winner))
+                } else if verdict.is_final() {
+                    if let Some(racer) = race.racers.iter_mut().find(|racer| racer.id == id) {
+                        racer.out = true;
+                    }
+                    // nobody can find it any more, so everyone gets to see it
+                    race.all_out()
+                        .then(|| race.next_round(&shared, Reply::Secret(secret)))
+                } else {
+                    None
+                };
+                drop(race);
+
+                send(writer, &verdict_reply(state, guess, verdict))?;
+                if let Some(results) = results {
+                    results.send(&shared);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn leave(&mut self) {
+        if let Some(Game::Race { .. }) = self.game {
+            let mut race = self.shared.race.lock().unwrap();
+            race.racers.retain(|racer| racer.id != self.id);
+            // the racer who left may have been the last one still in the round
+            let secret = race.host.secret();
+            let results = race
+                .all_out()
+                .then(|| race.next_round(&self.shared, Reply::Secret(secret)));
+            drop(race);
+            if let Some(results) = results {
+                results.send(&self.shared);
+            }
+        }
+    }
+}
+
+fn guess_in_time(
+    state: &mut GameState,
+    host: &mut SecretHost,
+    guess: u32,
+    started: Instant,
+) -> Result<Verdict, String> {
+    let late = state
+        .rules()
+        .time_limit
+        .is_some_and(|limit| started.elapsed() > limit);
+    let result = if late {
+        state.time_up(guess)
+    } else {
+        state.guess(host, guess)
+    };
+    result.map_err(|error| error.to_string())
+}
+
+fn verdict_reply(state: &GameState, guess: u32, verdict: Verdict) -> Reply {
+    Reply::Verdict {
+        guess,
+        verdict,
+        attempts_left: state.attempts_left(),
+    }
+}
+
+/// The client side of the protocol.
+pub struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Client {
+    /// Connects and reads the server's greeting.
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Client> {
+        let writer = TcpStream::connect(addr)?;
+        let mut client = Client {
+            reader: BufReader::new(writer.try_clone()?),
+            writer,
+        };
+        match client.receive()? {
+            Some(Reply::Hello { version }) if version == PROTOCOL_VERSION => Ok(client),
+            other => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("not a guessing-game server (it said {:?})", other),
+            )),
+        }
+    }
+
+    pub fn send(&mut self, request: &Request) -> io::Result<()> {
+        writeln!(self.writer, "{}", request)?;
+        self.writer.flush()
+    }
+
+    /// The next line from the server, `None` once it hung up.
+    pub fn receive(&mut self) -> io::Result<Option<Reply>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Reply::parse(&line)
+            .map(Some)
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))
+    }
+
+    /// A second handle on the connection, to read and write from different threads.
+    /// Lines the original already buffered stay there, so only one of them should read.
+    pub fn try_clone(&self) -> io::Result<Client> {
+        Ok(Client {
+            reader: BufReader::new(self.writer.try_clone()?),
+            writer: self.writer.try_clone()?,
+        })
+    }
+}
@@ -0,0 +1,231 @@
+// bots that play the game on their own, one Player per guessing strategy
+//
+// all of them keep track of the numbers that are still possible and only ever
+// guess one of those, they differ in where inside that range they guess.
+
+use crate::game::{Action, GameState, Player, Rules, Verdict};
+use crate::rng::Rng;
+
+/// The numbers still consistent with every verdict so far, as `low..high`.
+///
+/// `high` is excluded and both ends are u64, so an empty range and the full
+/// u32 range need no special cases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub low: u64,
+    pub high: u64,
+}
+
+impl Bounds {
+    pub fn from_rules(rules: &Rules) -> Self {
+        Bounds {
+            low: rules.low as u64,
+            high: rules.high as u64 + 1,
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.high.saturating_sub(self.low)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The guess `offset` numbers above the lowest possible one.
+    pub fn at(&self, offset: u64) -> u32 {
+        (self.low + offset.min(self.len().saturating_sub(1))) as u32
+    }
+
+    pub fn narrow(&mut self, guess: u32, verdict: Verdict) {
+        match verdict {
+            Verdict::TooLow => self.low = self.low.max(guess as u64 + 1),
+            Verdict::TooHigh => self.high = self.high.min(guess as u64),
+            Verdict::Correct | Verdict::OutOfAttempts | Verdict::OutOfTime | Verdict::TooSlow => {}
+        }
+    }
+}
+
+/// The strategies that come with the crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    BinarySearch,
+    Random,
+    GoldenSection,
+    NoisyBisection,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 4] = [
+        Strategy::BinarySearch,
+        Strategy::Random,
+        Strategy::GoldenSection,
+        Strategy::NoisyBisection,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::BinarySearch => "binary",
+            Strategy::Random => "random",
+            Strategy::GoldenSection => "golden",
+            Strategy::NoisyBisection => "noisy",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Strategy> {
+        Strategy::ALL
+            .into_iter()
+            .find(|strategy| strategy.name() == name)
+    }
+
+    /// A fresh player for one game, `seed` only matters for the strategies that use chance.
+    pub fn player(&self, seed: u64) -> Box<dyn Player> {
+        match self {
+            Strategy::BinarySearch => Box::new(BinarySearch::new()),
+            Strategy::Random => Box::new(RandomGuess::new(seed)),
+            Strategy::GoldenSection => Box::new(GoldenSection::new()),
+            Strategy::NoisyBisection => Box::new(NoisyBisection::new(seed)),
+        }
+    }
+}
+
+// every strategy starts from the rules and narrows the bounds with each verdict,
+// only the choice of the next guess inside the bounds is different
+fn next_action(
+    bounds: &mut Option<Bounds>,
+    state: &GameState,
+    pick: impl FnOnce(&Bounds) -> u32,
+) -> Action {
+    let bounds = bounds.get_or_insert_with(|| Bounds::from_rules(state.rules()));
+    if bounds.is_empty() {
+        // the answers contradict each other, nothing left to guess
+        return Action::Quit;
+    }
+    Action::Guess(pick(bounds))
+}
+
+fn observe(bounds: &mut Option<Bounds>, guess: u32, verdict: Verdict) {
+    if let Some(bounds) = bounds {
+        bounds.narrow(guess, verdict);
+    }
+}
+
+/// Always guesses the middle of the possible numbers.
+#[derive(Debug, Clone, Default)]
+pub struct BinarySearch {
+    bounds: Option<Bounds>,
+}
+
+impl BinarySearch {
+    pub fn new() -> Self {
+        BinarySearch::default()
+    }
+}
+
+impl Player for BinarySearch {
+    fn next_action(&mut self, state: &GameState) -> Action {
+        next_action(&mut self.bounds, state, |bounds| {
+            bounds.at((bounds.len() - 1) / 2)
+        })
+    }
+
+    fn observe(&mut self, guess: u32, verdict: Verdict, _state: &GameState) {
+        observe(&mut self.bounds, guess, verdict);
+    }
+}
+
+/// Guesses any of the possible numbers at random.
+#[derive(Debug, Clone)]
+pub struct RandomGuess {
+    rng: Rng,
+    bounds: Option<Bounds>,
+}
+
+impl RandomGuess {
+    pub fn new(seed: u64) -> Self {
+        RandomGuess {
+            rng: Rng::new(seed),
+            bounds: None,
+        }
+    }
+}
+
+impl Player for RandomGuess {
+    fn next_action(&mut self, state: &GameState) -> Action {
+        let rng = &mut self.rng;
+        next_action(&mut self.bounds, state, |bounds| {
+            bounds.at(rng.below(bounds.len()))
+        })
+    }
+
+    fn observe(&mut self, guess: u32, verdict: Verdict, _state: &GameState) {
+        observe(&mut self.bounds, guess, verdict);
+    }
+}
+
+/// Splits the possible numbers at the golden ratio instead of the middle.
+///
+/// Golden-section search is the right tool for finding the peak of a function,
+/// here it is a deliberately lopsided bisection to compare against.
+#[derive(Debug, Clone, Default)]
+pub struct GoldenSection {
+    bounds: Option<Bounds>,
+}
+
+impl GoldenSection {
+    // 1 - 1/phi, the smaller of the two golden parts
+    const RATIO: f64 = 0.381_966_011_250_105;
+
+    pub fn new() -> Self {
+        GoldenSection::default()
+    }
+}
+
+impl Player for GoldenSection {
+    fn next_action(&mut self, state: &GameState) -> Action {
+        next_action(&mut self.bounds, state, |bounds| {
+            bounds.at(((bounds.len() - 1) as f64 * GoldenSection::RATIO).round() as u64)
+        })
+    }
+
+    fn observe(&mut self, guess: u32, verdict: Verdict, _state: &GameState) {
+        observe(&mut self.bounds, guess, verdict);
+    }
+}
+
+/// Bisects like a person does: aims for the middle but misses by up to a
+/// fifth of the range either way.
+#[derive(Debug, Clone)]
+pub struct NoisyBisection {
+    rng: Rng,
+    bounds: Option<Bounds>,
+}
+
+impl NoisyBisection {
+    // how far off the middle a guess may land, as a part of the possible numbers
+    const NOISE: f64 = 0.2;
+
+    pub fn new(seed: u64) -> Self {
+        NoisyBisection {
+            rng: Rng::new(seed),
+            bounds: None,
+        }
+    }
+}
+
+impl Player for NoisyBisection {
+    fn next_action(&mut self, state: &GameState) -> Action {
+        let rng = &mut self.rng;
+        next_action(&mut self.bounds, state, |bounds| {
+            let middle = (bounds.len() - 1) / 2;
+            let spread = (bounds.len() as f64 * NoisyBisection::NOISE) as u64;
+            // a random offset in -spread..=spread around the middle
+            let shifted = middle + rng.below(2 * spread + 1);
+            bounds.at(shifted.saturating_sub(spread))
+        })
+    }
+
+    fn observe(&mut self, guess: u32, verdict: Verdict, _state: &GameState) {
+        observe(&mut self.bounds, guess, verdict);
+    }
+}
@@ -0,0 +1,294 @@
+// bots against each other in a round robin
+//
+// every entrant plays the same seeded secrets, then every pair of entrants is
+// compared game by game: whoever found the secret in fewer attempts wins that game.
+// the games run on a small pool of worker threads. each game's result goes into its
+// own slot, so the standings never depend on which thread finished first.
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::game::{GameState, Player, Rules, play};
+use crate::host::SecretHost;
+use crate::solvers::Strategy;
+
+// points for a won and a drawn game, a draw is half a win
+const WIN_POINTS: u32 = 2;
+const DRAW_POINTS: u32 = 1;
+
+/// Anything that can sit at the table, a fresh player for every game.
+pub trait Entrant: Send + Sync {
+    fn name(&self) -> String;
+
+    /// A player for one game, `seed` is fixed per game so reruns play the same.
+    fn player(&self, seed: u64) -> Box<dyn Player>;
+}
+
+impl Entrant for Strategy {
+    fn name(&self) -> String {
+        Strategy::name(self).to_string()
+    }
+
+    fn player(&self, seed: u64) -> Box<dyn Player> {
+        Strategy::player(self, seed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct GameResult {
+    won: bool,
+    attempts: u32,
+}
+
+impl GameResult {
+    // a found secret beats a missed one, fewer attempts beat more
+    fn beats(&self, other: &GameResult) -> bool {
+        self.won && (!other.won || self.attempts < other.attempts)
+    }
+}
+
+/// One row of the table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    // games against other entrants, each shared secret counts once per opponent
+    pub played: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub points: u32,
+    // secrets found within the attempts the rules allow
+    pub solved: u32,
+    pub mean_attempts: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standings {
+    pub games: u32,
+    pub seed: u64,
+    // best first
+    pub rows: Vec<Standing>,
+}
+
+impl Standings {
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "{:>4}  {:<12} {:>6} {:>5} {:>5} {:>6} {:>6} {:>7} {:>9}\n",
+            "rank", "entrant", "played", "won", "drawn", "lost", "points", "solved", "mean"
+        );
+        for (rank, row) in self.rows.iter().enumerate() {
+            let _ = writeln!(
+                text,
+                "{:>4}  {:<12} {:>6} {:>5} {:>5} {:>6} {:>6} {:>7} {:>9.2}",
+                rank + 1,
+                row.name,
+                row.played,
+                row.wins,
+                row.draws,
+                row.losses,
+                row.points,
+                row.solved,
+                row.mean_attempts
+            );
+        }
+        text
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            "rank,entrant,played,won,drawn,lost,points,solved,mean_attempts\n".to_string();
+        for (rank, row) in self.rows.iter().enumerate() {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{:.4}",
+                rank + 1,
+                csv_field(&row.name),
+                row.played,
+                row.wins,
+                row.draws,
+                row.losses,
+                row.points,
+                row.solved,
+                row.mean_attempts
+            );
+        }
+        csv
+    }
+}
+
+// quotes a field with a comma, a quote or a line break in it, as RFC 4180 asks
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub struct Tournament {
+    rules: Rules,
+    games: u32,
+    seed: u64,
+    entrants: Vec<Box<dyn Entrant>>,
+}
+
+impl Tournament {
+    /// `games` secrets, secret `i` drawn from seed `seed + i` like the benchmark does.
+    /// Time limits are dropped, a slow machine must not change the results.
+    pub fn new(rules: Rules, games: u32, seed: u64) -> Self {
+        Tournament {
+            rules: Rules {
+                time_limit: None,
+                ..rules
+            },
+            games,
+            seed,
+            entrants: Vec::new(),
+        }
+    }
+
+    /// A tournament between every built-in strategy.
+    pub fn with_strategies(rules: Rules, games: u32, seed: u64) -> Self {
+        let mut tournament = Tournament::new(rules, games, seed);
+        for strategy in Strategy::ALL {
+            tournament.register(strategy);
+        }
+        tournament
+    }
+
+    pub fn register(&mut self, entrant: impl Entrant + 'static) {
+        self.entrants.push(Box::new(entrant));
+    }
+
+    pub fn entrants(&self) -> usize {
+        self.entrants.len()
+    }
+
+    /// Plays every game on `threads` workers and ranks the entrants.
+    pub fn run(&self, threads: usize) -> Standings {
+        let results = self.play_all(threads);
+        let games = self.games as usize;
+        let results: Vec<&[GameResult]> = if games == 0 {
+            vec![&[]; self.entrants.len()]
+        } else {
+            results.chunks(games).collect()
+        };
+
+        let mut rows: Vec<Standing> = self
+            .entrants
+            .iter()
+            .zip(&results)
+            .map(|(entrant, own)| {
+                let attempts: u64 = own.iter().map(|result| result.attempts as u64).sum();
+                Standing {
+                    name: entrant.name(),
+                    played: 0,
+                    wins: 0,
+                    draws: 0,
+                    losses: 0,
+                    points: 0,
+                    solved: own.iter().filter(|result| result.won).count() as u32,
+                    mean_attempts: if own.is_empty() {
+                        0.0
+                    } else {
+                        attempts as f64 / own.len() as f64
+                    },
+                }
+            })
+            .collect();
+
+        // every pair once, on every shared secret
+        for a in 0..rows.len() {
+            for b in a + 1..rows.len() {
+                for (ours, theirs) in results[a].iter().zip(results[b]) {
+                    let (a_points, b_points) = if ours.beats(theirs) {
+                        rows[a].wins += 1;
+                        rows[b].losses += 1;
+                        (WIN_POINTS, 0)
+                    } else if theirs.beats(ours) {
+                        rows[a].losses += 1;
+                        rows[b].wins += 1;
+                        (0, WIN_POINTS)
+                    } else {
+                        rows[a].draws += 1;
+                        rows[b].draws += 1;
+                        (DRAW_POINTS, DRAW_POINTS)
+                    };
+                    rows[a].played += 1;
+                    rows[b].played += 1;
+                    rows[a].points += a_points;
+                    rows[b].points += b_points;
+                }
+            }
+        }
+
+        // the stable sort keeps registration order for a full tie
+        rows.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then(b.solved.cmp(&a.solved))
+                .then(a.mean_attempts.total_cmp(&b.mean_attempts))
+        });
+        Standings {
+            games: self.games,
+            seed: self.seed,
+            rows,
+        }
+    }
+
+    // one job per entrant and secret, handed out in order to whichever worker is free
+    fn play_all(&self, threads: usize) -> Vec<GameResult> {
+        let jobs = self.entrants.len() * self.games as usize;
+        let next = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..threads.clamp(1, jobs.max(1)) {
+                let sender = sender.clone();
+                let next = &next;
+                scope.spawn(move || {
+                    loop {
+                        let job = next.fetch_add(1, Ordering::Relaxed);
+                        if job >= jobs {
+                            break;
+                        }
+                        let result = self.play_one(job);
+                        if sender.send((job, result)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        drop(sender);
+
+        let mut results = vec![
+            GameResult {
+                won: false,
+                attempts: 0
+            };
+            jobs
+        ];
+        for (job, result) in receiver {
+            results[job] = result;
+        }
+        results
+    }
+
+    fn play_one(&self, job: usize) -> GameResult {
+        let entrant = &self.entrants[job / self.games as usize];
+        let game = (job % self.games as usize) as u64;
+        let game_seed = self.seed.wrapping_add(game);
+        let mut host = SecretHost::seeded(&self.rules, game_seed);
+        // the entrant's own chances must not follow the secret's seed
+        let mut player = entrant.player(game_seed ^ 0xA5A5_A5A5_A5A5_A5A5);
+        let mut state = GameState::new(self.rules);
+        play(&mut state, &mut host, player.as_mut());
+        GameResult {
+            won: state.is_won(),
+            attempts: state.attempts(),
+        }
+    }
+}
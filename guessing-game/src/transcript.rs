@@ -0,0 +1,279 @@
+// a transcript is everything needed to play a game again: the seed the secret came
+// from, the rules and every guess with the verdict it got
+//
+// the file is plain text, one record per line, so it is easy to read and diff:
+//
+//     # guessing-game transcript
+//     seed 42
+//     range 1 100
+//     attempts 7
+//     time-limit-ms 60000   (only when the game had one)
+//     guess-limit-ms 10000  (only when every guess had a countdown)
+//     host evil             (only when it was not the fair host, `host liar 2` for a liar)
+//     guess 50 too-low
+//     guess 75 correct
+//     hint parity           (one line per hint the player took)
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::game::{GameState, GuessError, Host, Rules, Verdict};
+use crate::hints::HintKind;
+use crate::host::HostKind;
+
+const HEADER: &str = "# guessing-game transcript";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    pub seed: u64,
+    pub rules: Rules,
+    pub host: HostKind,
+    pub turns: Vec<(u32, Verdict)>,
+    pub hints: Vec<HintKind>,
+}
+
+#[derive(Debug)]
+pub enum TranscriptError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptError::Io(error) => write!(f, "{}", error),
+            TranscriptError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for TranscriptError {}
+
+impl From<io::Error> for TranscriptError {
+    fn from(error: io::Error) -> Self {
+        TranscriptError::Io(error)
+    }
+}
+
+/// Why a replay did not end up where the recording did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    // the game gave a different verdict than the recorded one
+    Mismatch {
+        turn: usize,
+        guess: u32,
+        recorded: Verdict,
+        replayed: Verdict,
+    },
+    // the game did not even accept the recorded guess
+    Rejected {
+        turn: usize,
+        error: GuessError,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Mismatch {
+                turn,
+                guess,
+                recorded,
+                replayed,
+            } => write!(
+                f,
+                "turn {}: guess {} was recorded as {} but now is {}",
+                turn, guess, recorded, replayed
+            ),
+            ReplayError::Rejected { turn, error } => write!(f, "turn {}: {}", turn, error),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl Transcript {
+    pub fn from_game(seed: u64, host: HostKind, state: &GameState) -> Self {
+        Transcript {
+            seed,
+            host,
+            rules: *state.rules(),
+            turns: state.history().to_vec(),
+            hints: state.hints().to_vec(),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "{}\nseed {}\nrange {} {}\nattempts {}\n",
+            HEADER, self.seed, self.rules.low, self.rules.high, self.rules.max_attempts
+        );
+        if let Some(limit) = self.rules.time_limit {
+            text.push_str(&format!("time-limit-ms {}\n", limit.as_millis()));
+        }
+        if let Some(limit) = self.rules.guess_limit {
+            text.push_str(&format!("guess-limit-ms {}\n", limit.as_millis()));
+        }
+        if self.host != HostKind::Fair {
+            text.push_str(&format!("host {}", self.host.name()));
+            if let HostKind::Liar(lies) = self.host {
+                text.push_str(&format!(" {}", lies));
+            }
+            text.push('\n');
+        }
+        for (guess, verdict) in &self.turns {
+            text.push_str(&format!("guess {} {}\n", guess, verdict));
+        }
+        for hint in &self.hints {
+            text.push_str(&format!("hint {}\n", hint));
+        }
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self, TranscriptError> {
+        let mut seed = None;
+        let mut range = None;
+        let mut attempts = None;
+        let mut time_limit = None;
+        let mut guess_limit = None;
+        let mut host = HostKind::Fair;
+        let mut turns = Vec::new();
+        let mut hints = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_no = index + 1;
+            let error = |message: String| TranscriptError::Parse {
+                line: line_no,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["seed", value] => seed = Some(parse_field(value, "seed").map_err(error)?),
+                ["range", low, high] => {
+                    range = Some((
+                        parse_field(low, "range").map_err(error)?,
+                        parse_field(high, "range").map_err(error)?,
+                        line_no,
+                    ))
+                }
+                ["attempts", value] => {
+                    attempts = Some(parse_field(value, "attempts").map_err(error)?)
+                }
+                ["time-limit-ms", value] => {
+                    time_limit = Some(Duration::from_millis(
+                        parse_field(value, "time-limit-ms").map_err(error)?,
+                    ))
+                }
+                ["guess-limit-ms", value] => {
+                    guess_limit = Some(Duration::from_millis(
+                        parse_field(value, "guess-limit-ms").map_err(error)?,
+                    ))
+                }
+                ["host", name] => {
+                    host = HostKind::parse(name)
+                        .ok_or_else(|| error(format!("unknown host '{}'", name)))?
+                }
+                ["host", "liar", lies] => {
+                    host = HostKind::Liar(parse_field(lies, "lies").map_err(error)?)
+                }
+                ["guess", guess, verdict] => {
+                    let guess = parse_field(guess, "guess").map_err(error)?;
+                    let verdict = Verdict::parse(verdict)
+                        .ok_or_else(|| error(format!("unknown verdict '{}'", verdict)))?;
+                    turns.push((guess, verdict));
+                }
+                ["hint", name] => hints.push(
+                    HintKind::parse(name)
+                        .ok_or_else(|| error(format!("unknown hint '{}'", name)))?,
+                ),
+                _ => return Err(error(format!("cannot read '{}'", line))),
+            }
+        }
+
+        let missing = |what: &str| TranscriptError::Parse {
+            line: text.lines().count(),
+            message: format!("the transcript has no {} line", what),
+        };
+        let (low, high, range_line) = range.ok_or_else(|| missing("range"))?;
+        let rules = Rules {
+            low,
+            high,
+            max_attempts: attempts.ok_or_else(|| missing("attempts"))?,
+            time_limit,
+            guess_limit,
+        };
+        rules.validate().map_err(|message| TranscriptError::Parse {
+            line: range_line,
+            message,
+        })?;
+
+        Ok(Transcript {
+            seed: seed.ok_or_else(|| missing("seed"))?,
+            rules,
+            host,
+            turns,
+            hints,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), TranscriptError> {
+        fs::write(path, self.to_text())?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, TranscriptError> {
+        Transcript::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Plays every recorded guess again against a host built from the same seed
+    /// and checks that each one gets the recorded verdict.
+    ///
+    /// Time can't be replayed, so a guess recorded as too late or too slow is taken as
+    /// that again.
+    pub fn replay(&self) -> Result<GameState, ReplayError> {
+        self.rebuild().map(|(state, _)| state)
+    }
+
+    /// Like `replay`, but also hands back the host as the last guess left it.
+    pub fn rebuild(&self) -> Result<(GameState, Box<dyn Host>), ReplayError> {
+        let mut host = self.host.build(&self.rules, self.seed);
+        let mut state = GameState::new(self.rules);
+
+        for (index, (guess, recorded)) in self.turns.iter().enumerate() {
+            let turn = index + 1;
+            let replayed = match recorded {
+                Verdict::OutOfTime => state.time_up(*guess),
+                Verdict::TooSlow => state.too_slow(*guess),
+                _ => state.guess(host.as_mut(), *guess),
+            };
+            let replayed = replayed.map_err(|error| ReplayError::Rejected { turn, error })?;
+            if replayed != *recorded {
+                return Err(ReplayError::Mismatch {
+                    turn,
+                    guess: *guess,
+                    recorded: *recorded,
+                    replayed,
+                });
+            }
+        }
+        // hints don't change any verdict, only the score
+        for hint in &self.hints {
+            state.record_hint(*hint);
+        }
+        Ok((state, host))
+    }
+}
+
+fn parse_field<T: std::str::FromStr>(value: &str, what: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid {} value", value, what))
+}
@@ -0,0 +1,564 @@
+// a full-screen terminal UI with nothing but ANSI escape codes and termios
+//
+// the terminal is put into raw mode so every key arrives on its own and a read gives
+// up after 100ms, which is what keeps the timer ticking while nobody types. Ctrl-C
+// arrives as a key too (ISIG is off), so quitting always goes through the clean-up.
+// a panic hook puts the terminal back as well, the program must never leave a
+// broken shell behind. a terminal that goes away (a closed window, a dropped ssh
+// session) counts as quitting, see `TuiPlayer::read`.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+
+use crate::game::{Action, GameState, GuessError, Player, Verdict};
+use crate::hints::{HintError, HintKind};
+use crate::i18n;
+
+const BAR_WIDTH: usize = 50;
+// an input longer than any u32 is a typo
+const MAX_INPUT: usize = 10;
+// a read with nothing typed waits 100ms, one that comes back empty much sooner than
+// that this many times in a row means the terminal hung up
+const HANGUP_READ: Duration = Duration::from_millis(50);
+const HANGUP_READS: u32 = 10;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+
+fn colour(verdict: Verdict) -> &'static str {
+    match verdict {
+        Verdict::TooLow => "\x1b[34m",
+        Verdict::TooHigh => "\x1b[31m",
+        Verdict::Correct => "\x1b[32m",
+        Verdict::OutOfAttempts | Verdict::OutOfTime | Verdict::TooSlow => "\x1b[35m",
+    }
+}
+
+fn describe(verdict: Verdict) -> String {
+    i18n::text(match verdict {
+        Verdict::TooLow => "verdict-too-low",
+        Verdict::TooHigh => "verdict-too-high",
+        Verdict::Correct => "verdict-correct",
+        Verdict::OutOfAttempts => "verdict-out-of-attempts",
+        Verdict::OutOfTime => "verdict-out-of-time",
+        Verdict::TooSlow => "verdict-too-slow",
+    })
+}
+
+/// The numbers the verdicts so far leave open. Empty (low > high) if they contradict
+/// each other, which a lying host can do.
+pub fn open_range(state: &GameState) -> (u64, u64) {
+    let rules = state.rules();
+    let (mut low, mut high) = (rules.low as u64, rules.high as u64);
+    for (guess, verdict) in state.history() {
+        match verdict {
+            Verdict::TooLow => low = low.max(*guess as u64 + 1),
+            Verdict::TooHigh => high = high.min((*guess as u64).saturating_sub(1)),
+            Verdict::Correct => (low, high) = (*guess as u64, *guess as u64),
+            Verdict::OutOfAttempts | Verdict::OutOfTime | Verdict::TooSlow => {}
+        }
+    }
+    (low, high)
+}
+
+/// The range as a bar, `=` for the numbers still open and `.` for the ones ruled out.
+pub fn range_bar(state: &GameState, width: usize) -> String {
+    let rules = state.rules();
+    let (low, high) = open_range(state);
+    let size = rules.size();
+    (0..width as u64)
+        .map(|cell| {
+            // the numbers this cell stands for, at least one when the range is narrow
+            let first = cell * size / width as u64;
+            let last = ((cell + 1) * size / width as u64).max(first + 1) - 1;
+            let (first, last) = (rules.low as u64 + first, rules.low as u64 + last);
+            if first <= high && last >= low {
+                '='
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+/// One frame of the screen. Kept apart from the terminal so it is easy to look at.
+pub fn render(state: &GameState, elapsed: Duration, input: &str, message: Option<&str>) -> String {
+    let rules = state.rules();
+    let mut screen = String::new();
+    // home and clear, the whole frame is drawn again every time
+    screen.push_str("\x1b[H\x1b[2J");
+
+    let seconds = elapsed.as_secs();
+    let clock = match rules.time_limit {
+        Some(limit) => {
+            let left = limit.as_secs().saturating_sub(seconds);
+            let time = format!("{:02}:{:02}", left / 60, left % 60);
+            i18n::message("tui-time-left", &[("time", &time)])
+        }
+        None => {
+            let time = format!("{:02}:{:02}", seconds / 60, seconds % 60);
+            i18n::message("tui-time", &[("time", &time)])
+        }
+    };
+    let title = i18n::text("title-number");
+    // counted in characters, not bytes, for the scripts that need several per letter
+    let _ = writeln!(
+        screen,
+        " {BOLD}{}{RESET}{:>width$}",
+        title,
+        clock,
+        width = (BAR_WIDTH + 12).saturating_sub(title.chars().count())
+    );
+    screen.push('\n');
+
+    let _ = writeln!(
+        screen,
+        " {:>5} [{}] {}",
+        rules.low,
+        range_bar(state, BAR_WIDTH),
+        rules.high
+    );
+    let (low, high) = open_range(state);
+    if low <= high {
+        let possible = i18n::message("tui-possible", &[("low", &low), ("high", &high)]);
+        let _ = writeln!(screen, "         {DIM}{}{RESET}", possible);
+    } else {
+        let _ = writeln!(
+            screen,
+            "         {DIM}{}{RESET}",
+            i18n::text("tui-contradiction")
+        );
+    }
+    screen.push('\n');
+
+    let left = format!("{BOLD}{}{RESET}", state.attempts_left());
+    let attempts = i18n::message(
+        "tui-attempts-left",
+        &[("left", &left), ("max", &rules.max_attempts)],
+    );
+    let _ = writeln!(screen, " {}", attempts);
+    if !state.hints().is_empty() {
+        let cost: u32 = state.hints().iter().map(|kind| kind.cost()).sum();
+        let used = i18n::message(
+            "tui-hints-used",
+            &[("count", &state.hints().len()), ("cost", &cost)],
+        );
+        let _ = writeln!(screen, " {}", used);
+    }
+    screen.push('\n');
+
+    let _ = writeln!(screen, " {}", i18n::text("tui-history"));
+    // only the most recent guesses fit, older ones scroll away
+    let history = state.history();
+    let skip = history.len().saturating_sub(10);
+    for (turn, (guess, verdict)) in history.iter().enumerate().skip(skip) {
+        let _ = writeln!(
+            screen,
+            " {:>4}. {:>10}  {}{}{RESET}",
+            turn + 1,
+            guess,
+            colour(*verdict),
+            describe(*verdict)
+        );
+    }
+    if history.is_empty() {
+        let _ = writeln!(
+            screen,
+            "       {DIM}{}{RESET}",
+            i18n::text("tui-no-guesses")
+        );
+    }
+    screen.push('\n');
+
+    if let Some(message) = message {
+        let _ = writeln!(screen, " {}", message);
+    } else {
+        let _ = writeln!(screen, " {DIM}{}{RESET}", i18n::text("tui-keys"));
+    }
+    if !state.is_over() {
+        let _ = write!(screen, " > {}", input);
+    }
+    screen
+}
+
+/// A person playing on a full-screen terminal.
+///
+/// `new` returns `None` when stdin or stdout is not a terminal (or raw mode is not
+/// available here), the caller then falls back to the plain line mode.
+pub struct TuiPlayer {
+    _raw: RawMode,
+    started: Instant,
+    input: String,
+    message: Option<String>,
+    // the hint menu is open, the next digit picks one
+    choosing_hint: bool,
+    // empty reads in a row that came back too quickly to have waited
+    quick_reads: u32,
+}
+
+// what one read of the terminal gave
+enum Input {
+    Key(u8),
+    // nothing typed, time to draw the timer again
+    Nothing,
+    // the terminal is gone, nothing will ever be typed again
+    Gone,
+}
+
+impl TuiPlayer {
+    pub fn new() -> Option<TuiPlayer> {
+        let raw = RawMode::enable()?;
+        let _ = write!(io::stdout(), "\x1b[?1049h\x1b[?25h");
+        Some(TuiPlayer {
+            _raw: raw,
+            started: Instant::now(),
+            input: String::new(),
+            message: None,
+            choosing_hint: false,
+            quick_reads: 0,
+        })
+    }
+
+    /// Starts the timer at `elapsed` instead of zero, for a resumed game.
+    pub fn with_elapsed(mut self, elapsed: Duration) -> Self {
+        self.started = Instant::now().checked_sub(elapsed).unwrap_or(self.started);
+        self
+    }
+
+    fn draw(&self, state: &GameState) {
+        let frame = render(
+            state,
+            self.started.elapsed(),
+            &self.input,
+            self.message.as_deref(),
+        );
+        let mut out = io::stdout().lock();
+        let _ = out.write_all(frame.as_bytes());
+        let _ = out.flush();
+    }
+
+    fn read(&mut self) -> Input {
+        let started = Instant::now();
+        match sys::read_key() {
+            Ok(Some(key)) => {
+                self.quick_reads = 0;
+                Input::Key(key)
+            }
+            Ok(None) if started.elapsed() < HANGUP_READ => {
+                self.quick_reads += 1;
+                if self.quick_reads >= HANGUP_READS {
+                    Input::Gone
+                } else {
+                    Input::Nothing
+                }
+            }
+            Ok(None) => {
+                self.quick_reads = 0;
+                Input::Nothing
+            }
+            Err(_) => Input::Gone,
+        }
+    }
+
+    // quits on a gone terminal, putting its settings back right away
+    fn hang_up(&self) -> Action {
+        restore();
+        Action::Quit
+    }
+
+    fn wait_for_key(&mut self) {
+        loop {
+            match self.read() {
+                Input::Nothing => {}
+                Input::Key(_) => return,
+                Input::Gone => {
+                    self.hang_up();
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Player for TuiPlayer {
+    fn next_action(&mut self, state: &GameState) -> Action {
+        loop {
+            self.draw(state);
+            let key = match self.read() {
+                Input::Key(key) => key,
+                Input::Nothing => continue,
+                Input::Gone => return self.hang_up(),
+            };
+            if self.choosing_hint {
+                self.choosing_hint = false;
+                self.message = None;
+                let picked = (key as char)
+                    .to_digit(10)
+                    .and_then(|digit| state.allowed_hints().get((digit as usize).checked_sub(1)?));
+                if let Some(kind) = picked {
+                    return Action::Hint(*kind);
+                }
+                continue;
+            }
+            match key {
+                b'0'..=b'9' if self.input.len() < MAX_INPUT => self.input.push(key as char),
+                b'h' | b'H' => {
+                    self.message = Some(hint_menu(state));
+                    self.choosing_hint = !state.allowed_hints().is_empty();
+                }
+                // backspace and delete
+                0x08 | 0x7f => {
+                    self.input.pop();
+                }
+                b'\r' | b'\n' if !self.input.is_empty() => {
+                    let input = std::mem::take(&mut self.input);
+                    match input.parse() {
+                        Ok(guess) => {
+                            self.message = None;
+                            return Action::Guess(guess);
+                        }
+                        Err(_) => {
+                            self.message =
+                                Some(i18n::message("tui-too-large", &[("input", &input)]))
+                        }
+                    }
+                }
+                // q, Ctrl-C and Ctrl-D
+                b'q' | b'Q' | 0x03 | 0x04 => return Action::Quit,
+                b's' | b'S' => return Action::Save,
+                _ => {}
+            }
+        }
+    }
+
+    fn observe(&mut self, _guess: u32, verdict: Verdict, state: &GameState) {
+        if verdict.is_final() {
+            let message = match verdict {
+                Verdict::Correct => i18n::text("tui-won"),
+                _ => i18n::text("tui-over"),
+            };
+            self.message = Some(format!("{}{}{}", colour(verdict), message, RESET));
+            self.draw(state);
+            self.wait_for_key();
+        }
+    }
+
+    fn rejected(&mut self, error: &GuessError) {
+        self.message = Some(i18n::message("try-again", &[("error", &error.localised())]));
+    }
+
+    fn hinted(&mut self, kind: HintKind, hint: &Result<String, HintError>) {
+        self.message = Some(match hint {
+            Ok(text) => {
+                let text = format!("{BOLD}{}{RESET}", text);
+                i18n::message("hint-cost", &[("hint", &text), ("cost", &kind.cost())])
+            }
+            Err(error) => i18n::message("no-hint", &[("error", &error.localised())]),
+        });
+    }
+}
+
+fn hint_menu(state: &GameState) -> String {
+    if state.allowed_hints().is_empty() {
+        return i18n::text("no-hints");
+    }
+    let hints: Vec<String> = state
+        .allowed_hints()
+        .iter()
+        .enumerate()
+        .map(|(index, kind)| format!("{} {} (-{})", index + 1, kind, kind.cost()))
+        .collect();
+    i18n::message("tui-hint-menu", &[("hints", &hints.join(", "))])
+}
+
+// the terminal settings from before raw mode, also read by the panic hook
+static SAVED: Mutex<Option<sys::Termios>> = Mutex::new(None);
+static PANIC_HOOK: Once = Once::new();
+
+fn restore() {
+    let saved = SAVED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .take();
+    if let Some(saved) = saved {
+        sys::set(&saved);
+        // show the cursor, reset colours and leave the alternate screen
+        let _ = write!(io::stdout(), "\x1b[?25h\x1b[0m\x1b[?1049l");
+        let _ = io::stdout().flush();
+    }
+}
+
+// raw mode for as long as this lives
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> Option<RawMode> {
+        if !sys::is_terminal(0) || !sys::is_terminal(1) {
+            return None;
+        }
+        let saved = sys::get()?;
+        let mut raw = saved;
+        sys::make_raw(&mut raw);
+        *SAVED
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(saved);
+
+        PANIC_HOOK.call_once(|| {
+            let previous = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                restore();
+                previous(info);
+            }));
+        });
+
+        if !sys::set(&raw) {
+            restore();
+            return None;
+        }
+        Some(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod sys {
+    use std::io;
+    use std::os::raw::{c_int, c_void};
+
+    #[cfg(target_os = "linux")]
+    mod consts {
+        pub type Flag = std::os::raw::c_uint;
+        pub const NCCS: usize = 32;
+        pub const ICANON: Flag = 0o2;
+        pub const ECHO: Flag = 0o10;
+        pub const ISIG: Flag = 0o1;
+        pub const IEXTEN: Flag = 0o100000;
+        pub const ICRNL: Flag = 0o400;
+        pub const IXON: Flag = 0o2000;
+        pub const VTIME: usize = 5;
+        pub const VMIN: usize = 6;
+    }
+
+    #[cfg(target_os = "macos")]
+    mod consts {
+        pub type Flag = std::os::raw::c_ulong;
+        pub const NCCS: usize = 20;
+        pub const ICANON: Flag = 0x100;
+        pub const ECHO: Flag = 0x8;
+        pub const ISIG: Flag = 0x80;
+        pub const IEXTEN: Flag = 0x400;
+        pub const ICRNL: Flag = 0x100;
+        pub const IXON: Flag = 0x200;
+        pub const VTIME: usize = 17;
+        pub const VMIN: usize = 16;
+    }
+
+    use consts::*;
+
+    // struct termios as the C library lays it out
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct Termios {
+        c_iflag: Flag,
+        c_oflag: Flag,
+        c_cflag: Flag,
+        c_lflag: Flag,
+        #[cfg(target_os = "linux")]
+        c_line: u8,
+        c_cc: [u8; NCCS],
+        c_ispeed: Flag,
+        c_ospeed: Flag,
+    }
+
+    unsafe extern "C" {
+        fn tcgetattr(fd: c_int, termios: *mut Termios) -> c_int;
+        fn tcsetattr(fd: c_int, action: c_int, termios: *const Termios) -> c_int;
+        fn isatty(fd: c_int) -> c_int;
+        fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+    }
+
+    const TCSANOW: c_int = 0;
+
+    pub fn is_terminal(fd: c_int) -> bool {
+        // SAFETY: isatty only looks at the descriptor
+        unsafe { isatty(fd) == 1 }
+    }
+
+    pub fn get() -> Option<Termios> {
+        let mut termios = std::mem::MaybeUninit::<Termios>::uninit();
+        // SAFETY: tcgetattr fills the whole struct when it returns 0
+        unsafe {
+            if tcgetattr(0, termios.as_mut_ptr()) == 0 {
+                Some(termios.assume_init())
+            } else {
+                None
+            }
+        }
+    }
+
+    pub fn set(termios: &Termios) -> bool {
+        // SAFETY: the pointer is to a valid termios for the duration of the call
+        unsafe { tcsetattr(0, TCSANOW, termios) == 0 }
+    }
+
+    pub fn make_raw(termios: &mut Termios) {
+        termios.c_lflag &= !(ICANON | ECHO | ISIG | IEXTEN);
+        termios.c_iflag &= !(ICRNL | IXON);
+        // a read returns after one byte, or after 100ms with nothing
+        termios.c_cc[VMIN] = 0;
+        termios.c_cc[VTIME] = 1;
+    }
+
+    /// One key, or `None` if nothing was typed in the last 100ms. An error is a
+    /// terminal that can't be read any more, a signal only counts as nothing typed.
+    pub fn read_key() -> io::Result<Option<u8>> {
+        let mut byte = 0u8;
+        // SAFETY: reads at most one byte into a byte we own
+        let read = unsafe { read(0, (&mut byte as *mut u8).cast(), 1) };
+        match read {
+            1 => Ok(Some(byte)),
+            0 => Ok(None),
+            _ => {
+                let error = io::Error::last_os_error();
+                if error.kind() == io::ErrorKind::Interrupted {
+                    Ok(None)
+                } else {
+                    Err(error)
+                }
+            }
+        }
+    }
+}
+
+// no termios here, `RawMode::enable` always fails and the game uses line mode
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod sys {
+    #[derive(Clone, Copy)]
+    pub struct Termios;
+
+    pub fn is_terminal(_fd: i32) -> bool {
+        false
+    }
+
+    pub fn get() -> Option<Termios> {
+        None
+    }
+
+    pub fn set(_termios: &Termios) -> bool {
+        false
+    }
+
+    pub fn make_raw(_termios: &mut Termios) {}
+
+    pub fn read_key() -> std::io::Result<Option<u8>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,553 @@
+// word-guessing mode: the secret is a word out of a dictionary file, and every
+// guess gets a mark per letter (right place, somewhere else in the word, not in it)
+//
+// dictionary files have one word per line, blank lines and lines starting with `#`
+// are skipped. only words of the chosen length are used.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use crate::i18n;
+use crate::rng::Rng;
+
+/// Every pattern of marks gets a number below `3^length`, so longer words get slow.
+pub const MAX_WORD_LENGTH: usize = 10;
+
+#[derive(Debug)]
+pub enum DictionaryError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    // the file is fine, but it has nothing to play with
+    Unusable(String),
+}
+
+impl fmt::Display for DictionaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DictionaryError::Io(error) => write!(f, "{}", error),
+            DictionaryError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            DictionaryError::Unusable(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for DictionaryError {}
+
+impl From<io::Error> for DictionaryError {
+    fn from(error: io::Error) -> Self {
+        DictionaryError::Io(error)
+    }
+}
+
+/// The words a game may use, all of the same length, lowercase and without repeats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dictionary {
+    length: usize,
+    words: Vec<String>,
+}
+
+impl Dictionary {
+    pub fn load(path: &Path, length: usize) -> Result<Dictionary, DictionaryError> {
+        Dictionary::parse(&fs::read_to_string(path)?, length)
+    }
+
+    pub fn parse(text: &str, length: usize) -> Result<Dictionary, DictionaryError> {
+        if length == 0 || length > MAX_WORD_LENGTH {
+            return Err(DictionaryError::Unusable(format!(
+                "words must have 1 to {} letters",
+                MAX_WORD_LENGTH
+            )));
+        }
+
+        let mut words = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let word = line.trim();
+            if word.is_empty() || word.starts_with('#') {
+                continue;
+            }
+            if !word.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(DictionaryError::Parse {
+                    line: index + 1,
+                    message: format!(
+                        "'{}' is not a word, only the letters a to z are allowed",
+                        word
+                    ),
+                });
+            }
+            if word.len() == length {
+                words.push(word.to_ascii_lowercase());
+            }
+        }
+        words.sort();
+        words.dedup();
+
+        if words.is_empty() {
+            return Err(DictionaryError::Unusable(format!(
+                "there are no words with {} letters",
+                length
+            )));
+        }
+        Ok(Dictionary { length, words })
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words
+            .binary_search_by(|w| w.as_str().cmp(word))
+            .is_ok()
+    }
+
+    pub fn random(&self, rng: &mut Rng) -> &str {
+        &self.words[rng.below(self.words.len() as u64) as usize]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mark {
+    // right letter in the right place
+    Correct,
+    // in the word, but somewhere else
+    Present,
+    Absent,
+}
+
+impl Mark {
+    pub fn symbol(&self) -> char {
+        match self {
+            Mark::Correct => '=',
+            Mark::Present => '+',
+            Mark::Absent => '-',
+        }
+    }
+}
+
+/// The marks for one guess, one per letter.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pattern(pub Vec<Mark>);
+
+impl Pattern {
+    pub fn is_solved(&self) -> bool {
+        self.0.iter().all(|mark| *mark == Mark::Correct)
+    }
+
+    // a number below 3^length, cheaper to count by than the marks themselves
+    fn index(&self) -> usize {
+        self.0.iter().fold(0, |index, mark| {
+            index * 3
+                + match mark {
+                    Mark::Absent => 0,
+                    Mark::Present => 1,
+                    Mark::Correct => 2,
+                }
+        })
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for mark in &self.0 {
+            write!(f, "{}", mark.symbol())?;
+        }
+        Ok(())
+    }
+}
+
+/// Marks a guess against the secret.
+///
+/// Exact matches are marked first. A letter is only marked present as often as it is
+/// left over in the secret after that, so guessing `speed` for `abide` marks only the
+/// first `e`, and guessing `lolly` for `hello` marks the two `l`s in place but not the first.
+pub fn feedback(secret: &str, guess: &str) -> Pattern {
+    let secret = secret.as_bytes();
+    let guess = guess.as_bytes();
+    let mut marks = vec![Mark::Absent; guess.len()];
+    let mut left = [0u8; 26];
+
+    for (i, (s, g)) in secret.iter().zip(guess).enumerate() {
+        if s == g {
+            marks[i] = Mark::Correct;
+        } else {
+            left[(s - b'a') as usize] += 1;
+        }
+    }
+    for (i, g) in guess.iter().enumerate() {
+        let count = &mut left[(g - b'a') as usize];
+        if marks[i] == Mark::Absent && *count > 0 {
+            marks[i] = Mark::Present;
+            *count -= 1;
+        }
+    }
+    Pattern(marks)
+}
+
+/// Checks a guess against the hard-mode rule: every letter found so far has to be used
+/// again, the correct ones in the same place. Repeated letters count, so after `e` was
+/// marked twice the next guess needs two of them. Returns why the guess breaks the rule.
+pub fn hard_mode_violation(history: &[(String, Pattern)], guess: &str) -> Option<Violation> {
+    let guess = guess.as_bytes();
+    for (previous, pattern) in history {
+        let previous = previous.as_bytes();
+        let mut needed = [0usize; 26];
+        for (i, mark) in pattern.0.iter().enumerate() {
+            let letter = previous[i];
+            match mark {
+                Mark::Correct if guess.get(i) != Some(&letter) => {
+                    return Some(Violation::Misplaced {
+                        position: i + 1,
+                        letter: (letter as char).to_ascii_uppercase(),
+                    });
+                }
+                Mark::Correct | Mark::Present => needed[(letter - b'a') as usize] += 1,
+                Mark::Absent => {}
+            }
+        }
+        for (letter, count) in needed.iter().enumerate() {
+            let letter = b'a' + letter as u8;
+            let used = guess.iter().filter(|g| **g == letter).count();
+            if used < *count {
+                return Some(Violation::Missing {
+                    letter: (letter as char).to_ascii_uppercase(),
+                    times: *count,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// How a guess breaks the hard-mode rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    // a letter found in the right place is somewhere else now, position counts from 1
+    Misplaced { position: usize, letter: char },
+    // a found letter is missing, or is there fewer than `times` times
+    Missing { letter: char, times: usize },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Misplaced { position, letter } => {
+                write!(f, "letter {} must be {}", position, letter)
+            }
+            Violation::Missing { letter, times: 1 } => {
+                write!(f, "the guess must contain {}", letter)
+            }
+            Violation::Missing { letter, times } => {
+                write!(f, "the guess must contain {} {} times", letter, times)
+            }
+        }
+    }
+}
+
+impl Violation {
+    /// The rule in the player's language, `Display` stays English.
+    pub fn localised(&self) -> String {
+        match self {
+            Violation::Misplaced { position, letter } => i18n::message(
+                "wordle-misplaced",
+                &[("position", position), ("letter", letter)],
+            ),
+            Violation::Missing { letter, times: 1 } => {
+                i18n::message("wordle-missing", &[("letter", letter)])
+            }
+            Violation::Missing { letter, times } => i18n::message(
+                "wordle-missing-times",
+                &[("letter", letter), ("times", times)],
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordleError {
+    WrongLength { expected: usize },
+    UnknownWord(String),
+    HardMode(Violation),
+    GameOver,
+}
+
+impl fmt::Display for WordleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WordleError::WrongLength { expected } => {
+                write!(f, "a guess has {} letters", expected)
+            }
+            WordleError::UnknownWord(word) => write!(f, "'{}' is not in the dictionary", word),
+            WordleError::HardMode(reason) => write!(f, "hard mode: {}", reason),
+            WordleError::GameOver => write!(f, "the game is already over"),
+        }
+    }
+}
+
+impl std::error::Error for WordleError {}
+
+impl WordleError {
+    /// The error in the player's language, `Display` stays English for logs and the APIs.
+    pub fn localised(&self) -> String {
+        match self {
+            WordleError::WrongLength { expected } => {
+                i18n::message("wordle-length", &[("length", expected)])
+            }
+            WordleError::UnknownWord(word) => i18n::message("wordle-unknown", &[("word", word)]),
+            WordleError::HardMode(violation) => {
+                i18n::message("wordle-hard-mode", &[("rule", &violation.localised())])
+            }
+            WordleError::GameOver => i18n::text("game-over"),
+        }
+    }
+}
+
+/// One word-guessing game.
+#[derive(Debug, Clone)]
+pub struct WordleGame {
+    dictionary: Dictionary,
+    secret: String,
+    max_guesses: u32,
+    hard: bool,
+    history: Vec<(String, Pattern)>,
+}
+
+impl WordleGame {
+    /// The secret is expected to be in the dictionary.
+    pub fn new(dictionary: Dictionary, secret: &str, max_guesses: u32, hard: bool) -> Self {
+        WordleGame {
+            dictionary,
+            secret: secret.to_ascii_lowercase(),
+            max_guesses,
+            hard,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn seeded(dictionary: Dictionary, seed: u64, max_guesses: u32, hard: bool) -> Self {
+        let secret = dictionary.random(&mut Rng::new(seed)).to_string();
+        WordleGame::new(dictionary, &secret, max_guesses, hard)
+    }
+
+    pub fn dictionary(&self) -> &Dictionary {
+        &self.dictionary
+    }
+
+    pub fn max_guesses(&self) -> u32 {
+        self.max_guesses
+    }
+
+    pub fn is_hard(&self) -> bool {
+        self.hard
+    }
+
+    pub fn history(&self) -> &[(String, Pattern)] {
+        &self.history
+    }
+
+    /// The secret, only meant to be shown once the game is over.
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.history
+            .last()
+            .is_some_and(|(_, pattern)| pattern.is_solved())
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.history.len() as u32 >= self.max_guesses
+    }
+
+    pub fn guess(&mut self, guess: &str) -> Result<Pattern, WordleError> {
+        if self.is_over() {
+            return Err(WordleError::GameOver);
+        }
+        let guess = guess.trim().to_ascii_lowercase();
+        if guess.len() != self.dictionary.length() {
+            return Err(WordleError::WrongLength {
+                expected: self.dictionary.length(),
+            });
+        }
+        if !self.dictionary.contains(&guess) {
+            return Err(WordleError::UnknownWord(guess));
+        }
+        if self.hard
+            && let Some(reason) = hard_mode_violation(&self.history, &guess)
+        {
+            return Err(WordleError::HardMode(reason));
+        }
+        let pattern = feedback(&self.secret, &guess);
+        self.history.push((guess, pattern.clone()));
+        Ok(pattern)
+    }
+}
+
+/// The side that guesses the word, like `Player` for the number game.
+pub trait WordGuesser {
+    /// The next guess, or `None` to give up.
+    fn next_guess(&mut self, game: &WordleGame) -> Option<String>;
+
+    fn observe(&mut self, _guess: &str, _pattern: &Pattern) {}
+
+    fn rejected(&mut self, _error: &WordleError) {}
+}
+
+/// Runs a game until the word is found, the guesses run out or the guesser gives up.
+/// Returns whether the word was found.
+pub fn play(game: &mut WordleGame, guesser: &mut dyn WordGuesser) -> bool {
+    while !game.is_over() {
+        let Some(guess) = guesser.next_guess(game) else {
+            return false;
+        };
+        match game.guess(&guess) {
+            Ok(pattern) => guesser.observe(&guess, &pattern),
+            Err(error) => guesser.rejected(&error),
+        }
+    }
+    game.is_won()
+}
+
+/// Picks the guess with the highest expected information.
+///
+/// Every possible guess splits the words that are still possible by the pattern it
+/// would get. The guess whose split has the highest entropy, `-sum(p * log2(p))`, tells
+/// the most on average. Ties go to a word that could still be the secret, then to the
+/// first word in the dictionary. In hard mode only guesses that keep the rule are tried.
+#[derive(Debug, Clone)]
+pub struct EntropySolver {
+    words: Vec<String>,
+    candidates: Vec<usize>,
+    history: Vec<(String, Pattern)>,
+    hard: bool,
+}
+
+impl EntropySolver {
+    pub fn new(dictionary: &Dictionary, hard: bool) -> Self {
+        EntropySolver {
+            words: dictionary.words().to_vec(),
+            candidates: (0..dictionary.words().len()).collect(),
+            history: Vec::new(),
+            hard,
+        }
+    }
+
+    /// The words that still fit every pattern.
+    pub fn candidates(&self) -> impl Iterator<Item = &str> {
+        self.candidates
+            .iter()
+            .map(|index| self.words[*index].as_str())
+    }
+
+    /// How many bits `guess` is expected to give about the remaining words.
+    pub fn entropy(&self, guess: &str) -> f64 {
+        let mut counts = HashMap::new();
+        for candidate in &self.candidates {
+            *counts
+                .entry(feedback(&self.words[*candidate], guess).index())
+                .or_insert(0usize) += 1;
+        }
+        let total = self.candidates.len() as f64;
+        counts
+            .values()
+            .map(|count| {
+                let p = *count as f64 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    pub fn best_guess(&self) -> Option<String> {
+        match self.candidates.as_slice() {
+            [] => return None,
+            // with one or two left, guessing one of them is as good as it gets
+            [first, ..] if self.candidates.len() <= 2 => return Some(self.words[*first].clone()),
+            _ => {}
+        }
+
+        let mut best: Option<(f64, bool, usize)> = None;
+        for (index, word) in self.words.iter().enumerate() {
+            if self.hard && hard_mode_violation(&self.history, word).is_some() {
+                continue;
+            }
+            let entropy = self.entropy(word);
+            let possible = self.candidates.binary_search(&index).is_ok();
+            let better = match best {
+                None => true,
+                Some((best_entropy, best_possible, _)) => {
+                    entropy > best_entropy + 1e-9
+                        || ((entropy - best_entropy).abs() <= 1e-9 && possible && !best_possible)
+                }
+            };
+            if better {
+                best = Some((entropy, possible, index));
+            }
+        }
+        best.map(|(_, _, index)| self.words[index].clone())
+    }
+}
+
+impl WordGuesser for EntropySolver {
+    fn next_guess(&mut self, _game: &WordleGame) -> Option<String> {
+        self.best_guess()
+    }
+
+    fn observe(&mut self, guess: &str, pattern: &Pattern) {
+        let words = &self.words;
+        self.candidates
+            .retain(|candidate| feedback(&words[*candidate], guess) == *pattern);
+        self.history.push((guess.to_string(), pattern.clone()));
+    }
+}
+
+/// A person typing words.
+pub struct HumanWordGuesser<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> HumanWordGuesser<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        HumanWordGuesser { input, output }
+    }
+}
+
+impl<R: BufRead, W: Write> WordGuesser for HumanWordGuesser<R, W> {
+    fn next_guess(&mut self, game: &WordleGame) -> Option<String> {
+        let prompt = i18n::message(
+            "prompt-numbered",
+            &[
+                ("number", &(game.history().len() + 1)),
+                ("total", &game.max_guesses()),
+            ],
+        );
+        let _ = write!(self.output, "{} ", prompt);
+        let _ = self.output.flush();
+
+        let mut line = String::new();
+        match self.input.read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) if line.trim().eq_ignore_ascii_case("quit") => None,
+            Ok(_) => Some(line.trim().to_string()),
+        }
+    }
+
+    fn observe(&mut self, guess: &str, pattern: &Pattern) {
+        let _ = writeln!(self.output, "{} {}", guess.to_ascii_uppercase(), pattern);
+    }
+
+    fn rejected(&mut self, error: &WordleError) {
+        let _ = writeln!(
+            self.output,
+            "{}",
+            i18n::message("try-again", &[("error", &error.localised())])
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::cmp::Ordering;
+
+use guessing_game::bench::bisection_bound;
+use guessing_game::{
+    Action, AdversaryHost, GameState, Host, Outcome, Player, Rng, Rules, Strategy, play,
+};
+
+// guesses anything in the range, possible or not, to poke at the adversary's answers
+struct Wild(Rng);
+
+impl Player for Wild {
+    fn next_action(&mut self, state: &GameState) -> Action {
+        let rules = state.rules();
+        Action::Guess(self.0.range(rules.low, rules.high))
+    }
+}
+
+fn rules(low: u32, high: u32, max_attempts: u32) -> Rules {
+    Rules {
+        low,
+        high,
+        max_attempts,
+        ..Rules::default()
+    }
+}
+
+// every answer the host gave must be the true answer for the number it reveals
+fn assert_consistent(host: &AdversaryHost, rules: &Rules) {
+    let secret = host
+        .reveal()
+        .expect("the adversary always has a number left");
+    assert!(rules.contains(secret));
+    for (guess, answer) in host.answers() {
+        assert_eq!(guess.cmp(&secret), *answer, "guess {} vs {}", guess, secret);
+    }
+}
+
+#[test]
+fn every_answer_fits_the_revealed_number() {
+    for seed in 0..200 {
+        for rules in [rules(1, 100, 7), rules(1, 1000, 30), rules(0, u32::MAX, 40)] {
+            let mut host = AdversaryHost::new(&rules);
+            let mut player: Box<dyn Player> = match seed % 3 {
+                0 => Box::new(Wild(Rng::new(seed))),
+                1 => Strategy::Random.player(seed),
+                _ => Strategy::NoisyBisection.player(seed),
+            };
+            let mut state = GameState::new(rules);
+            play(&mut state, &mut host, player.as_mut());
+
+            assert_consistent(&host, &rules);
+            assert_eq!(host.answers().len() as u32, state.attempts());
+            // a win is only possible once a single number is left
+            if state.is_won() {
+                assert_eq!(host.candidates().len(), 1);
+                assert_eq!(host.answers().last().unwrap().1, Ordering::Equal);
+            }
+        }
+    }
+}
+
+#[test]
+fn binary_search_still_wins_within_the_bound() {
+    for (low, high) in [
+        (1, 1),
+        (1, 2),
+        (1, 100),
+        (1, 1024),
+        (7, 10_006),
+        (0, u32::MAX),
+    ] {
+        let bound = bisection_bound(rules(low, high, 1).size());
+        let rules = rules(low, high, bound);
+        let mut host = AdversaryHost::new(&rules);
+        let mut state = GameState::new(rules);
+        let outcome = play(
+            &mut state,
+            &mut host,
+            Strategy::BinarySearch.player(0).as_mut(),
+        );
+
+        assert_eq!(
+            outcome,
+            Outcome::Won { attempts: bound },
+            "{}-{}",
+            low,
+            high
+        );
+        assert_consistent(&host, &rules);
+    }
+}
+
+#[test]
+fn every_strategy_leaves_the_adversary_a_witness() {
+    // the same setup as bench::against_adversary: as many attempts as there are numbers
+    for (low, high) in [(1, 1), (1, 100), (5, 1_005)] {
+        let size = rules(low, high, 1).size() as u32;
+        let rules = rules(low, high, size);
+        for strategy in Strategy::ALL {
+            let mut host = AdversaryHost::new(&rules);
+            let mut state = GameState::new(rules);
+            play(&mut state, &mut host, strategy.player(3).as_mut());
+
+            assert!(state.is_won(), "{} on {}-{}", strategy.name(), low, high);
+            assert!(host.witness().is_some());
+            assert_consistent(&host, &rules);
+        }
+    }
+}
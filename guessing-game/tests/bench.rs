@@ -0,0 +1,73 @@
+use guessing_game::bench::{self, against_adversary, bisection_bound};
+use guessing_game::{GameState, Outcome, Rules, SecretHost, Strategy, play};
+
+// low..=high with exactly as many attempts as a bisection may need
+fn tight(low: u32, high: u32) -> Rules {
+    let rules = Rules {
+        low,
+        high,
+        ..Rules::default()
+    };
+    Rules {
+        max_attempts: bisection_bound(rules.size()),
+        ..rules
+    }
+}
+
+const RANGES: [(u32, u32); 7] = [
+    (5, 5),
+    (5, 6),
+    (1, 3),
+    (1, 100),
+    (0, 1023),
+    (1, 1000),
+    (u32::MAX - 999, u32::MAX),
+];
+
+#[test]
+fn the_bound_is_floor_log2_plus_one() {
+    // plain ceil(log2(size)) would be one short at 1, 2, 4, 8 and every other power of two
+    let bounds: Vec<u32> = [1, 2, 3, 4, 7, 8, 100, 1024, 1 << 32]
+        .into_iter()
+        .map(bisection_bound)
+        .collect();
+    assert_eq!(bounds, [1, 2, 2, 3, 3, 4, 7, 11, 33]);
+}
+
+#[test]
+fn binary_search_finds_every_secret_within_the_bound() {
+    for (low, high) in RANGES {
+        let rules = tight(low, high);
+        let mut worst = 0;
+        for secret in low..=high {
+            let mut state = GameState::new(rules);
+            let mut player = Strategy::BinarySearch.player(0);
+            let outcome = play(&mut state, &mut SecretHost::new(secret), player.as_mut());
+            match outcome {
+                Outcome::Won { attempts } => worst = worst.max(attempts),
+                other => panic!("{:?} for secret {} in {}-{}", other, secret, low, high),
+            }
+        }
+        // some secret needs every one of the attempts, so the bound can't be lowered
+        assert_eq!(worst, rules.max_attempts, "{}-{}", low, high);
+    }
+}
+
+#[test]
+fn binary_search_meets_the_adversary_at_the_bound() {
+    for (low, high) in RANGES.into_iter().chain([(0, u32::MAX)]) {
+        let rules = tight(low, high);
+        assert_eq!(
+            against_adversary(Strategy::BinarySearch, &rules, 0),
+            rules.max_attempts,
+            "{}-{}",
+            low,
+            high
+        );
+    }
+
+    let report = bench::run(Strategy::BinarySearch, &tight(1, 100), 200, 7);
+    assert!(report.worst <= 7);
+    assert_eq!(report.within_limit, 200);
+    assert_eq!(report.against_adversary, 7);
+}
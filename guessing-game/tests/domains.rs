@@ -0,0 +1,124 @@
+use guessing_game::domains::{self, Dates, Floats, Integers, Letters, ValueError};
+use guessing_game::{
+    Bisection, Compass, Date, Domain, DomainGame, Grid, GridSolver, Order, Ordered, Point,
+};
+
+// every secret of the domain, or a spread of seeds where there are too many
+fn solves_every_seed<D: Ordered + Clone>(domain: D, max_guesses: u32) {
+    for seed in 0..200 {
+        let mut game = DomainGame::seeded(domain.clone(), seed, max_guesses);
+        let mut solver = Bisection::new(&domain);
+        assert!(
+            domains::play(&mut game, &mut solver),
+            "seed {}: {} not found in {} guesses",
+            seed,
+            game.secret(),
+            max_guesses
+        );
+    }
+}
+
+#[test]
+fn bisection_solves_every_ordered_domain() {
+    solves_every_seed(Integers::new(1, 1000).unwrap(), 10);
+    // 1000 steps of 0.01 wide, and the tolerance covers two of them
+    solves_every_seed(Floats::new(0.0, 10.0, 0.01).unwrap(), 10);
+    solves_every_seed(Dates::default(), 9);
+    solves_every_seed(Letters::default(), 5);
+}
+
+#[test]
+fn the_grid_solver_follows_the_compass() {
+    let grid = Grid::new(Point { x: -50, y: 0 }, Point { x: 49, y: 99 }).unwrap();
+    for seed in 0..200 {
+        let mut game = DomainGame::seeded(grid, seed, 7);
+        assert!(domains::play(&mut game, &mut GridSolver::new(&grid)));
+    }
+
+    let here = Point { x: 3, y: 3 };
+    assert_eq!(
+        Compass::between(here, Point { x: 5, y: 9 }),
+        Compass::NorthEast
+    );
+    assert_eq!(Compass::between(here, Point { x: 3, y: 0 }), Compass::South);
+    assert_eq!(Compass::NorthWest.axes(), (Order::TooHigh, Order::TooLow));
+    assert_eq!(grid.parse(" 4, -2 "), Ok(Point { x: 4, y: -2 }));
+}
+
+#[test]
+fn the_widest_ranges_do_not_overflow() {
+    let widest = Grid::new(
+        Point {
+            x: i64::MIN,
+            y: i64::MIN,
+        },
+        Point {
+            x: i64::MAX,
+            y: i64::MAX,
+        },
+    )
+    .unwrap();
+    for seed in 0..20 {
+        // 64 halvings per axis, plus one each for the last step
+        let mut game = DomainGame::seeded(widest, seed, 130);
+        assert!(domains::play(&mut game, &mut GridSolver::new(&widest)));
+    }
+    let line = Integers::new(i64::MIN, i64::MAX).unwrap();
+    assert_eq!(line.middle(&i64::MIN, &i64::MAX), -1);
+    solves_every_seed(line, 65);
+
+    // every day a date can be, the first and the last one included
+    let every_day = Dates::new(Date::MIN, Date::MAX).unwrap();
+    solves_every_seed(every_day, 22);
+    assert_eq!(every_day.below(&Date::from_days(i64::MIN)), None);
+    assert_eq!(every_day.above(&Date::from_days(i64::MAX)), None);
+}
+
+#[test]
+fn floats_are_right_within_the_tolerance() {
+    let floats = Floats::new(0.0, 1.0, 0.05).unwrap();
+    assert_eq!(floats.feedback(&0.5, &0.54), Order::Correct);
+    assert_eq!(floats.feedback(&0.5, &0.44), Order::TooLow);
+    assert_eq!(floats.feedback(&0.5, &0.56), Order::TooHigh);
+    assert!(Floats::new(0.0, 1.0, 0.0).is_err());
+
+    let mut game = DomainGame::new(floats, 0.5, 3);
+    assert!(game.guess(1.5).is_err());
+    assert_eq!(game.guess(0.52), Ok(Order::Correct));
+    assert!(game.is_won());
+}
+
+#[test]
+fn dates_read_and_print_the_same() {
+    let date: Date = "2024-02-29".parse().unwrap();
+    assert_eq!(date.ymd(), (2024, 2, 29));
+    assert_eq!(date.to_string(), "2024-02-29");
+    assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+    assert_eq!(Date::from_ymd(2000, 3, 1).unwrap().days(), 11_017);
+    assert!("2023-02-29".parse::<Date>().is_err());
+    assert!("2024-13-01".parse::<Date>().is_err());
+    assert!("yesterday".parse::<Date>().is_err());
+
+    // years far enough out would overflow the calendar arithmetic
+    assert_eq!(Date::from_ymd(0, 1, 1), Some(Date::MIN));
+    assert_eq!(Date::from_ymd(9999, 12, 31), Some(Date::MAX));
+    assert_eq!(Date::MIN.to_string(), "0000-01-01");
+    assert_eq!(Date::from_ymd(10_000, 1, 1), None);
+    let dates = Dates::default();
+    let error = dates.parse("9223372036854775807-03-01").unwrap_err();
+    assert_eq!(error, ValueError::Year(i64::MAX));
+    assert_eq!(
+        error.to_string(),
+        "9223372036854775807 is not a year from 0 to 9999"
+    );
+    assert!(dates.parse("10000-01-01").is_err());
+    assert_eq!(
+        Dates::new(Date::from_days(i64::MIN), Date::from_days(0)),
+        Err(ValueError::DayOutOfRange(i64::MIN))
+    );
+    assert!(Dates::new(Date::MIN, Date::from_days(i64::MAX)).is_err());
+
+    let letters = Letters::default();
+    assert_eq!(letters.parse("Q"), Ok('q'));
+    assert!(letters.parse("qu").is_err());
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use guessing_game::scores::score;
+use guessing_game::{
+    GameState, HintError, HintKind, HostKind, Rules, SaveKey, SavedGame, ScoreEntry, SecretHost,
+};
+
+fn game(secret: u32) -> (GameState, SecretHost) {
+    let state = GameState::new(Rules::default()).with_hints(&HintKind::ALL);
+    (state, SecretHost::new(secret))
+}
+
+#[test]
+fn every_hint_tells_the_truth() {
+    let (mut state, mut host) = game(37);
+    assert_eq!(
+        state.hint(&host, HintKind::Parity).unwrap(),
+        "The number is odd."
+    );
+    // these need a guess to talk about, and cost nothing when refused
+    assert!(matches!(
+        state.hint(&host, HintKind::Within),
+        Err(HintError::NotYet(_))
+    ));
+    assert_eq!(state.hints(), [HintKind::Parity]);
+
+    state.guess(&mut host, 30).unwrap();
+    assert_eq!(
+        state.hint(&host, HintKind::Within).unwrap(),
+        "30 is within 10 of the number."
+    );
+    state.guess(&mut host, 60).unwrap();
+    assert_eq!(
+        state.hint(&host, HintKind::HotCold).unwrap(),
+        "60 is cool, colder than 30."
+    );
+
+    assert_eq!(
+        state.hint(&host, HintKind::Digit).unwrap(),
+        "The ones digit is 7."
+    );
+    assert_eq!(
+        state.hint(&host, HintKind::Digit).unwrap(),
+        "The tens digit is 3."
+    );
+    assert_eq!(
+        state.hint(&host, HintKind::Digit).unwrap(),
+        "The hundreds digit is 0."
+    );
+    assert!(state.hint(&host, HintKind::Digit).is_err());
+}
+
+#[test]
+fn hints_must_be_allowed_and_need_a_secret() {
+    let rules = Rules::default();
+    let host = SecretHost::new(5);
+    let mut state = GameState::new(rules).with_hints(&[HintKind::Parity]);
+    assert_eq!(
+        state.hint(&host, HintKind::Digit),
+        Err(HintError::NotAllowed(HintKind::Digit))
+    );
+
+    let evil = HostKind::Evil.build(&rules, 1);
+    assert_eq!(
+        state.hint(evil.as_ref(), HintKind::Parity),
+        Err(HintError::NoSecret)
+    );
+    assert!(state.hints().is_empty());
+}
+
+#[test]
+fn hints_attempts_and_time_lower_the_score() {
+    assert_eq!(score(true, 1, &[], Duration::ZERO), 1000);
+    assert_eq!(score(true, 3, &[], Duration::ZERO), 900);
+    assert_eq!(score(true, 1, &[HintKind::Digit], Duration::ZERO), 800);
+    assert_eq!(score(true, 1, &[], Duration::from_secs(30)), 970);
+    assert_eq!(score(false, 1, &[], Duration::ZERO), 0);
+    // never below nothing
+    assert_eq!(
+        score(true, 100, &HintKind::ALL, Duration::from_secs(600)),
+        0
+    );
+
+    let entry = ScoreEntry::now("ann", "normal", true, 2, Duration::ZERO)
+        .with_hints(&[HintKind::Parity, HintKind::HotCold]);
+    assert_eq!((entry.hints, entry.score), (2, 800));
+}
+
+#[test]
+fn hints_survive_a_save() {
+    let (mut state, mut host) = game(37);
+    state.guess(&mut host, 50).unwrap();
+    state.hint(&host, HintKind::Parity).unwrap();
+
+    let saved = SavedGame::from_game(
+        0,
+        HostKind::Fair,
+        &state,
+        Some(37),
+        Duration::ZERO,
+        "normal",
+    );
+    let key = SaveKey::random();
+    let saved = SavedGame::parse(&saved.to_text(&key), &key).unwrap();
+    assert_eq!(saved.allowed_hints, HintKind::ALL);
+    assert_eq!(saved.transcript.hints, [HintKind::Parity]);
+}
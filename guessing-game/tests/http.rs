@@ -0,0 +1,239 @@
+use std::io::{Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use guessing_game::http::Games;
+use guessing_game::{HttpServer, Json, Rules};
+
+fn start() -> SocketAddr {
+    let server =
+        HttpServer::bind("127.0.0.1:0", Rules::default(), Duration::from_secs(60)).unwrap();
+    let addr = server.local_addr().unwrap();
+    // the thread ends with the test process
+    thread::spawn(move || server.run());
+    addr
+}
+
+// sends one request and returns the status and the parsed body
+fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Json) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, Json::parse(body).unwrap())
+}
+
+// sends whatever bytes it is given and returns the status of the answer
+fn raw_status(addr: SocketAddr, request: &str) -> u16 {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+    stream.shutdown(Shutdown::Write).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response.split_whitespace().nth(1).unwrap().parse().unwrap()
+}
+
+fn field<'a>(json: &'a Json, name: &str) -> &'a Json {
+    json.get(name)
+        .unwrap_or_else(|| panic!("no field {} in {}", name, json))
+}
+
+#[test]
+fn a_game_can_be_played_over_http() {
+    let addr = start();
+    let (status, game) = request(
+        addr,
+        "POST",
+        "/games",
+        r#"{"min": 1, "max": 1000, "attempts": 10}"#,
+    );
+    assert_eq!(status, 201);
+    let id = field(&game, "id").as_str().unwrap().to_string();
+    assert_eq!(field(&game, "status").as_str(), Some("playing"));
+    assert_eq!(field(&game, "secret"), &Json::Null);
+
+    let (mut low, mut high) = (1, 1000);
+    let state = loop {
+        let guess = low + (high - low) / 2;
+        let path = format!("/games/{}/guesses", id);
+        let (status, state) = request(addr, "POST", &path, &format!("{{\"guess\": {}}}", guess));
+        assert_eq!(status, 200);
+        match field(&state, "last_verdict").as_str().unwrap() {
+            "too-low" => low = guess + 1,
+            "too-high" => high = guess - 1,
+            "correct" => break state,
+            other => panic!("unexpected verdict {}", other),
+        }
+    };
+    assert_eq!(field(&state, "status").as_str(), Some("won"));
+    assert!(field(&state, "secret").as_u32().is_some());
+
+    let (status, fetched) = request(addr, "GET", &format!("/games/{}", id), "");
+    assert_eq!(status, 200);
+    assert_eq!(fetched, state);
+
+    // a finished game takes no more guesses
+    let (status, body) = request(
+        addr,
+        "POST",
+        &format!("/games/{}/guesses", id),
+        r#"{"guess": 1}"#,
+    );
+    assert_eq!(status, 409);
+    assert!(field(&body, "error").as_str().is_some());
+}
+
+#[test]
+fn errors_have_proper_status_codes() {
+    let addr = start();
+    let (_, game) = request(addr, "POST", "/games", "");
+    let guesses = format!("/games/{}/guesses", field(&game, "id").as_str().unwrap());
+
+    assert_eq!(request(addr, "GET", "/games/nope", "").0, 404);
+    assert_eq!(
+        request(addr, "POST", "/games/nope/guesses", r#"{"guess": 5}"#).0,
+        404
+    );
+    assert_eq!(request(addr, "GET", "/elsewhere", "").0, 404);
+    assert_eq!(request(addr, "DELETE", "/games", "").0, 405);
+
+    for malformed in [
+        "",
+        "{",
+        "[]",
+        r#"{"guess": "five"}"#,
+        r#"{"guess": 4.5}"#,
+        r#"{"guess": -3}"#,
+    ] {
+        assert_eq!(
+            request(addr, "POST", &guesses, malformed).0,
+            400,
+            "body {}",
+            malformed
+        );
+    }
+    assert_eq!(request(addr, "POST", &guesses, r#"{"guess": 500}"#).0, 422);
+    assert_eq!(
+        request(addr, "POST", "/games", r#"{"min": 10, "max": 1}"#).0,
+        422
+    );
+    assert_eq!(
+        request(addr, "POST", "/games", r#"{"attempts": "many"}"#).0,
+        400
+    );
+
+    // none of that used up an attempt
+    let (_, state) = request(addr, "GET", &guesses.replace("/guesses", ""), "");
+    assert_eq!(field(&state, "attempts").as_u32(), Some(0));
+}
+
+#[test]
+fn endless_lines_are_refused() {
+    let addr = start();
+    let path = format!("/{}", "a".repeat(20_000));
+    assert_eq!(
+        raw_status(addr, &format!("GET {} HTTP/1.1\r\n\r\n", path)),
+        400
+    );
+
+    let header = format!("X-Padding: {}\r\n", "a".repeat(20_000));
+    let request = format!("GET /games/nope HTTP/1.1\r\n{}\r\n", header);
+    assert_eq!(raw_status(addr, &request), 431);
+
+    let headers = "X-Padding: a\r\n".repeat(1000);
+    let request = format!("GET /games/nope HTTP/1.1\r\n{}\r\n", headers);
+    assert_eq!(raw_status(addr, &request), 431);
+
+    // a long line that is still within the limit is fine
+    let header = format!("X-Padding: {}\r\n", "a".repeat(4000));
+    let request = format!("GET /games/nope HTTP/1.1\r\n{}\r\n", header);
+    assert_eq!(raw_status(addr, &request), 404);
+}
+
+#[test]
+fn game_ids_do_not_follow_from_each_other() {
+    let now = Instant::now();
+    let ids = |games: &mut Games| -> Vec<String> {
+        (0..50)
+            .map(|_| {
+                let created = games.handle("POST", "/games", "", now);
+                field(&created.body, "id").as_str().unwrap().to_string()
+            })
+            .collect()
+    };
+    let first = ids(&mut Games::new(Rules::default(), Duration::from_secs(60)));
+    let second = ids(&mut Games::new(Rules::default(), Duration::from_secs(60)));
+
+    for id in first.iter().chain(&second) {
+        assert_eq!(id.len(), 32);
+        assert!(id.bytes().all(|b| b.is_ascii_hexdigit()));
+    }
+    let mut all: Vec<&String> = first.iter().chain(&second).collect();
+    all.sort();
+    all.dedup();
+    // no repeats, not even between two servers started the same way
+    assert_eq!(all.len(), 100);
+}
+
+#[test]
+fn idle_games_expire() {
+    let start = Instant::now();
+    let mut games = Games::new(Rules::default(), Duration::from_secs(60));
+
+    let created = games.handle("POST", "/games", "", start);
+    let id = field(&created.body, "id").as_str().unwrap().to_string();
+    let other = games.handle("POST", "/games", "", start);
+    let other = field(&other.body, "id").as_str().unwrap().to_string();
+
+    // using a game keeps it alive
+    let later = start + Duration::from_secs(45);
+    let guess = games.handle(
+        "POST",
+        &format!("/games/{}/guesses", id),
+        r#"{"guess": 50}"#,
+        later,
+    );
+    assert_eq!(guess.status, 200);
+
+    let expired = start + Duration::from_secs(90);
+    assert_eq!(
+        games
+            .handle("GET", &format!("/games/{}", id), "", expired)
+            .status,
+        200
+    );
+    assert_eq!(
+        games
+            .handle("GET", &format!("/games/{}", other), "", expired)
+            .status,
+        404
+    );
+    assert_eq!(games.len(), 1);
+}
+
+#[test]
+fn json_round_trips() {
+    let text = r#"{"name":"a \"quoted\"\nline é 😀","list":[1,2.5,-3,true,null],"empty":{}}"#;
+    let json = Json::parse(text).unwrap();
+    assert_eq!(
+        field(&json, "name").as_str(),
+        Some("a \"quoted\"\nline é 😀")
+    );
+    assert_eq!(Json::parse(&json.to_string()).unwrap(), json);
+
+    assert!(Json::parse("{\"a\": 1,}").is_err());
+    assert!(Json::parse("[1] 2").is_err());
+    assert!(Json::parse(&"[".repeat(1000)).is_err());
+}
@@ -0,0 +1,173 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use guessing_game::i18n::{self, LOCALES, fill, locale_from_env, placeholders};
+use guessing_game::wordle::{feedback, hard_mode_violation};
+use guessing_game::{Catalog, Dictionary, WordleGame};
+
+#[test]
+fn every_locale_defines_every_message() {
+    let english = Catalog::english();
+    let ids: Vec<&str> = english.ids().collect();
+    assert!(!ids.is_empty());
+
+    for locale in LOCALES {
+        let catalog = Catalog::builtin(locale).unwrap();
+        let missing: Vec<&&str> = ids.iter().filter(|id| catalog.get(id).is_none()).collect();
+        assert!(missing.is_empty(), "{} is missing {:?}", locale, missing);
+        let unknown: Vec<&str> = catalog
+            .ids()
+            .filter(|id| english.get(id).is_none())
+            .collect();
+        assert!(
+            unknown.is_empty(),
+            "{} has unknown ids {:?}",
+            locale,
+            unknown
+        );
+
+        // a translation that drops or renames a `{name}` would print it literally
+        for id in &ids {
+            assert_eq!(
+                placeholders(catalog.get(id).unwrap()),
+                placeholders(english.get(id).unwrap()),
+                "{} changes the placeholders of {}",
+                locale,
+                id
+            );
+        }
+    }
+}
+
+#[test]
+fn missing_messages_fall_back_to_english() {
+    let partial = Catalog::parse("xx", "# only one\ntoo-low = Trop bas !\n").unwrap();
+    assert_eq!(partial.get("too-low"), Some("Trop bas !"));
+    assert_eq!(partial.get("too-high"), None);
+
+    // nothing installed in the tests, so this is English
+    assert_eq!(i18n::text("too-high"), "Too high!");
+    assert_eq!(i18n::text("no-such-id"), "no-such-id");
+
+    assert!(Catalog::parse("xx", "too-low Trop bas").is_err());
+    let twice = Catalog::parse("xx", "a = 1\nb = 2\na = 3\n").unwrap_err();
+    assert_eq!(twice.line, 3);
+}
+
+#[test]
+fn values_are_filled_in_by_name() {
+    assert_eq!(
+        fill(
+            "{guess} is {temperature}, {not-given}",
+            &[("temperature", &"cool"), ("guess", &60)]
+        ),
+        "60 is cool, {not-given}"
+    );
+    assert_eq!(
+        i18n::message("attempts-left", &[("left", &3)]),
+        "Attempts left: 3"
+    );
+}
+
+#[test]
+fn the_language_comes_from_lang() {
+    assert_eq!(locale_from_env("de_DE.UTF-8"), Some("de"));
+    assert_eq!(locale_from_env("es"), Some("es"));
+    assert_eq!(locale_from_env("hi_IN"), Some("hi"));
+    assert_eq!(locale_from_env("C.UTF-8"), None);
+    assert_eq!(locale_from_env("fr_FR.UTF-8"), None);
+}
+
+// plays a game in `lang` with `input` typed in, and returns what it printed
+fn play_in(lang: &str, args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guessing-game"))
+        .args(["--lang", lang])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn mistakes_in_the_other_games_are_translated() {
+    let out = play_in("es", &["--mode", "mastermind", "--seed", "1"], "12\n1239\n");
+    assert!(
+        out.contains("un código tiene 4 cifras, '12' tiene 2"),
+        "{}",
+        out
+    );
+    assert!(
+        out.contains("'9' no es un color, usa las cifras de 1 a 6"),
+        "{}",
+        out
+    );
+
+    let out = play_in(
+        "hi",
+        &[
+            "--mode",
+            "date",
+            "--from",
+            "2024-01-01",
+            "--to",
+            "2024-12-31",
+        ],
+        "yesterday\n99999-01-01\n2023-02-29\n1999-01-01\n",
+    );
+    assert!(
+        out.contains("'yesterday' 2024-05-01 जैसी कोई तारीख नहीं है"),
+        "{}",
+        out
+    );
+    assert!(
+        out.contains("99999 0 से 9999 तक का कोई साल नहीं है"),
+        "{}",
+        out
+    );
+    assert!(out.contains("2023-02-29 कोई दिन नहीं है"), "{}", out);
+    assert!(out.contains("1999-01-01 2024-01-01 से 2024-12-31 तक का एक दिन नहीं है"));
+
+    let out = play_in("de", &["--mode", "grid"], "3;4\n");
+    assert!(out.contains("'3;4' ist kein Punkt wie 3,4"), "{}", out);
+}
+
+#[test]
+fn hard_mode_rules_are_translated() {
+    let words = concat!(env!("CARGO_MANIFEST_DIR"), "/words.txt");
+    let dictionary = Dictionary::load(Path::new(words), 5).unwrap();
+    let secret = WordleGame::seeded(dictionary.clone(), 7, 6, true)
+        .secret()
+        .to_string();
+    // a first guess that finds a letter, then one that drops it again
+    let (first, pattern) = dictionary
+        .words()
+        .iter()
+        .filter(|word| **word != secret)
+        .map(|word| (word.clone(), feedback(&secret, word)))
+        .find(|(_, pattern)| pattern.to_string() != "-----")
+        .unwrap();
+    let history = vec![(first.clone(), pattern)];
+    let second = dictionary
+        .words()
+        .iter()
+        .find(|word| hard_mode_violation(&history, word).is_some())
+        .unwrap();
+
+    let args = ["--mode", "wordle", "--dict", words, "--hard", "--seed", "7"];
+    let out = play_in("de", &args, &format!("zzzzz\nab\n{}\n{}\n", first, second));
+    assert!(out.contains("'zzzzz' steht nicht im Wörterbuch"), "{}", out);
+    assert!(out.contains("ein Tipp hat 5 Buchstaben"), "{}", out);
+    assert!(out.contains("schwerer Modus: "), "{}", out);
+    assert!(!out.contains("hard mode"), "{}", out);
+}
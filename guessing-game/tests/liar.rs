@@ -0,0 +1,147 @@
+use std::cmp::Ordering;
+use std::process::Command;
+
+use guessing_game::liar::{SLACK, attempts_needed, find_lies};
+use guessing_game::{GameState, Host, LiarHost, Outcome, Rules, UlamSolver, Verdict, play};
+
+fn liar_rules(low: u32, high: u32, lies: u32) -> Rules {
+    let rules = Rules {
+        low,
+        high,
+        ..Rules::default()
+    };
+    Rules {
+        // the attempts liar mode gives
+        max_attempts: attempts_needed(rules.size(), lies) + SLACK,
+        ..rules
+    }
+}
+
+// lies on exactly the given turns, whatever the guess, so a test can spend every lie
+struct ScriptedLiar {
+    secret: u32,
+    lie_on: Vec<usize>,
+    turn: usize,
+}
+
+impl Host for ScriptedLiar {
+    fn answer(&mut self, guess: u32) -> Ordering {
+        self.turn += 1;
+        let truth = guess.cmp(&self.secret);
+        if truth != Ordering::Equal && self.lie_on.contains(&self.turn) {
+            truth.reverse()
+        } else {
+            truth
+        }
+    }
+
+    fn reveal(&self) -> Option<u32> {
+        Some(self.secret)
+    }
+}
+
+#[test]
+fn the_lies_found_are_the_lies_told() {
+    let rules = liar_rules(1, 100, 3);
+    let mut lied = 0;
+    for seed in 0..300 {
+        let mut host = LiarHost::seeded(&rules, seed, 3);
+        let mut state = GameState::new(rules);
+        play(&mut state, &mut host, &mut UlamSolver::new(3));
+        if !state.is_won() {
+            continue;
+        }
+        assert_eq!(
+            find_lies(host.secret(), state.history()),
+            host.lies(),
+            "seed {}",
+            seed
+        );
+        lied += host.lies().len();
+    }
+    assert!(lied > 100, "only {} lies in 300 games", lied);
+
+    // worked out by hand: the secret is 40
+    let history = [
+        (50, Verdict::TooHigh),
+        (25, Verdict::TooHigh),
+        (30, Verdict::TooLow),
+        (45, Verdict::TooLow),
+        (38, Verdict::TooSlow),
+        (40, Verdict::Correct),
+    ];
+    assert_eq!(find_lies(40, &history), [2, 4]);
+}
+
+#[test]
+fn the_solver_wins_within_the_attempts_liar_mode_gives() {
+    for lies in 0..=3 {
+        for (low, high) in [(1, 1), (1, 2), (1, 20), (1, 100), (500, 1523)] {
+            let rules = liar_rules(low, high, lies);
+            for seed in 0..40 {
+                let mut host = LiarHost::seeded(&rules, seed, lies);
+                let mut state = GameState::new(rules);
+                let outcome = play(&mut state, &mut host, &mut UlamSolver::new(lies));
+                assert!(
+                    matches!(outcome, Outcome::Won { .. }),
+                    "{:?} with {} lies on {}-{}, seed {}: {:?}",
+                    outcome,
+                    lies,
+                    low,
+                    high,
+                    seed,
+                    state.history()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn the_solver_survives_every_lie_being_told() {
+    for lies in 1..=3 {
+        let rules = liar_rules(1, 100, lies);
+        for secret in 1..=100 {
+            // every lie at the start, when the solver knows the least
+            let mut host = ScriptedLiar {
+                secret,
+                lie_on: (1..=lies as usize).collect(),
+                turn: 0,
+            };
+            let mut state = GameState::new(rules);
+            let outcome = play(&mut state, &mut host, &mut UlamSolver::new(lies));
+            assert!(
+                matches!(outcome, Outcome::Won { .. }),
+                "{:?} with {} lies, secret {}",
+                outcome,
+                lies,
+                secret
+            );
+        }
+    }
+}
+
+#[test]
+fn a_backwards_range_with_lies_is_an_error_not_a_crash() {
+    let output = Command::new(env!("CARGO_BIN_EXE_guessing-game"))
+        .args([
+            "--min", "50", "--max", "10", "--lies", "1", "--player", "bot",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("the lowest number (50) must not be larger than the highest (10)"),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("panicked"));
+
+    let backwards = Rules {
+        low: 50,
+        high: 10,
+        ..Rules::default()
+    };
+    assert_eq!(backwards.size(), 0);
+}
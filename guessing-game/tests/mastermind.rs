@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use guessing_game::mastermind::{Config, score};
+use guessing_game::{Breaker, Code, Feedback, KnuthSolver, MastermindGame};
+
+fn code(text: &str) -> Code {
+    Code::parse(text, &Config::default()).unwrap()
+}
+
+#[test]
+fn repeated_colours_are_only_counted_once() {
+    let cases = [
+        ("1122", "1233", 1, 1),
+        ("1234", "4321", 0, 4),
+        ("1111", "1122", 2, 0),
+        ("1122", "2211", 0, 4),
+        ("6543", "3456", 0, 4),
+        ("1234", "1234", 4, 0),
+        ("1123", "1312", 1, 3),
+    ];
+    for (secret, guess, exact, partial) in cases {
+        assert_eq!(
+            score(&code(secret), &code(guess)),
+            Feedback { exact, partial },
+            "{} against {}",
+            guess,
+            secret
+        );
+    }
+}
+
+// follows the solver down every feedback it can get, so every secret is played once
+// without recomputing the shared first guesses for each of them
+fn worst_case(solver: &KnuthSolver, secrets: Vec<Code>, depth: u32, solved: &mut usize) -> u32 {
+    let guess = solver.best_guess().expect("a secret is left");
+    let mut groups: HashMap<Feedback, Vec<Code>> = HashMap::new();
+    for secret in secrets {
+        groups
+            .entry(score(&secret, &guess))
+            .or_default()
+            .push(secret);
+    }
+
+    let mut worst = depth;
+    for (feedback, group) in groups {
+        if feedback.exact == 4 {
+            *solved += 1;
+            continue;
+        }
+        let mut next = solver.clone();
+        next.observe(&guess, feedback);
+        worst = worst.max(worst_case(&next, group, depth + 1, solved));
+    }
+    worst
+}
+
+#[test]
+fn knuth_solves_every_code_in_five_guesses() {
+    let config = Config::default();
+    let solver = KnuthSolver::new(&config).unwrap();
+    assert_eq!(solver.best_guess(), Some(code("1122")));
+
+    let mut solved = 0;
+    let worst = worst_case(&solver, Code::all(&config), 1, &mut solved);
+    assert_eq!(solved, 1296);
+    assert!(worst <= 5, "needed {} guesses", worst);
+}
+
+#[test]
+fn knuth_wins_real_games() {
+    let config = Config::default();
+    for seed in 0..5 {
+        let mut game = MastermindGame::seeded(config, seed);
+        let mut solver = KnuthSolver::new(&config).unwrap();
+        assert!(guessing_game::mastermind::play(&mut game, &mut solver));
+        assert!(game.history().len() <= 5);
+    }
+}
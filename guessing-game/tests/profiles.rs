@@ -0,0 +1,97 @@
+use std::fs;
+use std::time::Duration;
+
+use guessing_game::{HintKind, Profile, ProfileError, Rules};
+
+const LUNCH: &str = "\
+# lunch break rules
+base = easy            # comments can follow a setting
+name = lunch
+range = 1..=500
+
+attempts = 9
+time_limit = 2m
+guess_limit = 15
+hints = parity, within
+";
+
+// the line of the error, or a panic if the text was accepted
+fn error_line(text: &str) -> usize {
+    match Profile::parse(text, "custom") {
+        Ok(profile) => panic!("{:?} was accepted", profile),
+        Err(ProfileError { line, .. }) => line,
+    }
+}
+
+#[test]
+fn a_rules_file_sets_every_field() {
+    let path = std::env::temp_dir().join(format!("guessing-game-{}.rules", std::process::id()));
+    fs::write(&path, LUNCH.replace("name = lunch\n", "")).unwrap();
+    let profile = Profile::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    // without a name line the file name is the name
+    assert!(profile.name.starts_with("guessing-game-"));
+    assert_eq!(
+        profile.rules,
+        Rules {
+            low: 1,
+            high: 500,
+            max_attempts: 9,
+            time_limit: Some(Duration::from_secs(120)),
+            guess_limit: Some(Duration::from_secs(15)),
+        }
+    );
+    assert_eq!(profile.hints, [HintKind::Parity, HintKind::Within]);
+    assert_eq!(Profile::parse(LUNCH, "custom").unwrap().name, "lunch");
+
+    // a file that only picks a base is that built-in profile under another name
+    let hard = Profile::parse("base = hard", "mine").unwrap();
+    assert_eq!(hard.rules, Profile::builtin("hard").unwrap().rules);
+}
+
+#[test]
+fn a_bad_field_is_reported_on_its_line() {
+    let bad = [
+        ("attempts = many", 6),
+        ("time_limit = 2h", 7),
+        ("guess_limit = soon", 8),
+        ("hints = parity, cheat", 9),
+        ("range = 1 to 500", 4),
+    ];
+    for (line, number) in bad {
+        let key = line.split(' ').next().unwrap();
+        let text: String = LUNCH
+            .lines()
+            .map(|old| if old.starts_with(key) { line } else { old })
+            .map(|line| format!("{}\n", line))
+            .collect();
+        assert_eq!(error_line(&text), number, "{}", line);
+    }
+
+    assert_eq!(error_line("name = x\nspeed = 3\n"), 2);
+    assert_eq!(error_line("name = x\n\njust some words\n"), 3);
+    // base has to come first, anything set before it would be thrown away
+    assert_eq!(error_line("attempts = 3\nbase = hard\n"), 2);
+}
+
+#[test]
+fn a_duplicate_key_points_at_the_second_one() {
+    let text = format!("{}attempts = 10\n", LUNCH);
+    let error = Profile::parse(&text, "custom").unwrap_err();
+    assert_eq!(error.line, 10);
+    assert_eq!(error.message, "attempts is already set on line 6");
+    assert_eq!(
+        error.to_string(),
+        "line 10: attempts is already set on line 6"
+    );
+}
+
+#[test]
+fn rules_that_do_not_fit_together_point_at_the_setting_to_fix() {
+    // low > high can only be found once the whole file is read
+    assert_eq!(error_line("name = x\nattempts = 3\nrange = 50..10\n"), 3);
+    assert_eq!(error_line("range = 1..10\n\nattempts = 0\n"), 3);
+    assert_eq!(error_line("# nothing\ntime_limit = 0s\n"), 2);
+    assert_eq!(error_line("guess_limit = 0\n"), 1);
+}
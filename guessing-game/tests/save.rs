@@ -0,0 +1,227 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::time::Duration;
+
+use guessing_game::save::SaveError;
+use guessing_game::{
+    Action, GameState, HostKind, Outcome, Player, Rules, SaveKey, SavedGame, SecretHost, Verdict,
+    play_from,
+};
+
+const GUESSES: [u32; 8] = [50, 25, 75, 12, 88, 37, 63, 6];
+
+fn liar_rules() -> Rules {
+    Rules {
+        max_attempts: 20,
+        ..Rules::default()
+    }
+}
+
+#[test]
+fn a_resumed_liar_tells_the_same_lies() {
+    let rules = liar_rules();
+    let host_kind = HostKind::Liar(3);
+
+    // the whole game in one go
+    let mut host = host_kind.build(&rules, 11);
+    let mut straight = GameState::new(rules);
+    for guess in GUESSES {
+        straight.guess(host.as_mut(), guess).unwrap();
+    }
+
+    // the same game saved halfway through the text format and picked up again
+    let mut host = host_kind.build(&rules, 11);
+    let mut state = GameState::new(rules);
+    for guess in &GUESSES[..4] {
+        state.guess(host.as_mut(), *guess).unwrap();
+    }
+    let saved = SavedGame::from_game(
+        11,
+        host_kind,
+        &state,
+        host.reveal(),
+        Duration::from_millis(1500),
+        "normal liar 3",
+    );
+    let key = SaveKey::random();
+    let loaded = SavedGame::parse(&saved.to_text(&key), &key).unwrap();
+    assert_eq!(loaded, saved);
+
+    let (mut state, mut host) = loaded.resume().unwrap();
+    for guess in &GUESSES[4..] {
+        state.guess(host.as_mut(), *guess).unwrap();
+    }
+    assert_eq!(state.history(), straight.history());
+}
+
+#[test]
+fn edited_files_are_rejected() {
+    let rules = Rules::default();
+    let mut host = HostKind::Fair.build(&rules, 4);
+    let mut state = GameState::new(rules);
+    state.guess(host.as_mut(), 50).unwrap();
+    let secret = host.reveal().unwrap();
+    let key = SaveKey::random();
+    let text = SavedGame::from_game(
+        4,
+        HostKind::Fair,
+        &state,
+        Some(secret),
+        Duration::ZERO,
+        "normal",
+    )
+    .to_text(&key);
+
+    // neither the seed nor the secret can be read from the file
+    assert!(!text.contains("seed"));
+    assert!(!text.contains("secret"));
+    assert!(text.lines().nth(1).unwrap().starts_with("sealed "));
+
+    let edits = [
+        text.replace("guess 50", "guess 51"),
+        text.replace("attempts 7", "attempts 70"),
+        text.replace("elapsed-ms 0", "elapsed-ms 1"),
+        text.replace("sealed ", "sealed 0"),
+    ];
+    for edited in edits {
+        assert_ne!(edited, text);
+        assert!(
+            matches!(SavedGame::parse(&edited, &key), Err(SaveError::Tampered)),
+            "{}",
+            edited
+        );
+    }
+
+    // another install can't read it either
+    assert!(matches!(
+        SavedGame::parse(&text, &SaveKey::random()),
+        Err(SaveError::Tampered)
+    ));
+
+    let unsigned: String = text
+        .lines()
+        .filter(|line| !line.starts_with("signature"))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    assert!(matches!(
+        SavedGame::parse(&unsigned, &key),
+        Err(SaveError::Parse { .. })
+    ));
+}
+
+struct Guesses(Vec<u32>);
+
+impl Player for Guesses {
+    fn next_action(&mut self, _state: &GameState) -> Action {
+        self.0.pop().map_or(Action::Save, Action::Guess)
+    }
+}
+
+#[test]
+fn time_played_before_the_save_still_counts() {
+    let rules = Rules {
+        time_limit: Some(Duration::from_secs(60)),
+        ..Rules::default()
+    };
+    let mut host = HostKind::Fair.build(&rules, 9);
+    let mut state = GameState::new(rules);
+
+    let outcome = play_from(
+        &mut state,
+        host.as_mut(),
+        &mut Guesses(vec![]),
+        Duration::ZERO,
+    );
+    assert_eq!(outcome, Outcome::Saved { attempts: 0 });
+
+    let outcome = play_from(
+        &mut state,
+        host.as_mut(),
+        &mut Guesses(vec![50]),
+        Duration::from_secs(61),
+    );
+    assert_eq!(outcome, Outcome::Lost { attempts: 1 });
+    assert_eq!(state.last_verdict(), Some(Verdict::OutOfTime));
+}
+
+// runs the game as a person would, with its data kept in `data`
+fn play(data: &Path, args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guessing-game"))
+        .args(args)
+        .env("XDG_DATA_HOME", data)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn a_save_that_was_quit_can_not_be_resumed_again() {
+    let data = std::env::temp_dir().join(format!("guessing-game-resume-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&data);
+    fs::create_dir_all(&data).unwrap();
+    let save = data.join("game.txt");
+    let copy = data.join("copy.txt");
+    let save_arg = save.to_str().unwrap();
+
+    // a first guess that can't be right, so the game is still going when it is saved
+    let secret = SecretHost::seeded(&Rules::default(), 5).secret();
+    let first = if secret == 50 { 51 } else { 50 };
+    let output = play(
+        &data,
+        &["--seed", "5", "--save", save_arg],
+        &format!("{}\nsave\n", first),
+    );
+    assert!(output.status.success(), "{:?}", output);
+    fs::copy(&save, &copy).unwrap();
+
+    // quitting shows the answer, so the save is gone
+    let output = play(&data, &["--resume", save_arg], "quit\n");
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains(&secret.to_string()));
+    assert!(!save.exists());
+
+    let output = play(&data, &["--resume", save_arg], "");
+    assert_eq!(output.status.code(), Some(2));
+
+    // and a copy made before is refused too
+    let output = play(&data, &["--resume", copy.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already played"));
+    fs::remove_dir_all(data).unwrap();
+}
+
+#[test]
+fn the_seal_matches_the_published_test_vectors() {
+    use guessing_game::seal::{hmac_sha256, sha256, to_hex};
+
+    assert_eq!(
+        to_hex(&sha256(b"abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    // longer than one block, so the padding spills into a second one
+    assert_eq!(
+        to_hex(&sha256(
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+        )),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+    // RFC 4231, test case 2
+    assert_eq!(
+        to_hex(&hmac_sha256(
+            b"Jefe",
+            &[b"what do ya want ", b"for nothing?"]
+        )),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+}
@@ -0,0 +1,181 @@
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use guessing_game::scores::{high_scores, player_stats};
+use guessing_game::{ScoreBoard, ScoreEntry};
+
+// a fresh directory per test, the tests run in parallel
+fn board(name: &str) -> (ScoreBoard, PathBuf) {
+    let dir = std::env::temp_dir().join(format!(
+        "guessing-game-scores-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    (ScoreBoard::new(dir.join("scores.tsv")), dir)
+}
+
+fn entry(player: &str, difficulty: &str, won: bool, attempts: u32) -> ScoreEntry {
+    ScoreEntry::now(player, difficulty, won, attempts, Duration::from_secs(3))
+}
+
+#[test]
+fn games_finishing_at_once_all_get_written() {
+    let (board, dir) = board("threads");
+    thread::scope(|scope| {
+        for writer in 0..8 {
+            let board = &board;
+            scope.spawn(move || {
+                for game in 1..=10 {
+                    let player = format!("player{}", writer);
+                    board.record(&entry(&player, "normal", true, game)).unwrap();
+                }
+            });
+        }
+    });
+
+    let entries = board.load().unwrap();
+    assert_eq!(entries.len(), 80);
+    for writer in 0..8 {
+        let player = format!("player{}", writer);
+        let attempts: Vec<u32> = entries
+            .iter()
+            .filter(|entry| entry.player == player)
+            .map(|entry| entry.attempts)
+            .collect();
+        // every writer's games are there, in the order it played them
+        assert_eq!(attempts, (1..=10).collect::<Vec<_>>());
+    }
+
+    // nothing is left behind: no lock, no temp file
+    let mut files: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|file| file.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["scores.tsv"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_lock_left_by_a_crash_is_broken() {
+    let (board, dir) = board("stale");
+    let lock = fs::File::create(dir.join("scores.lock")).unwrap();
+    lock.set_modified(SystemTime::now() - Duration::from_secs(120))
+        .unwrap();
+    drop(lock);
+
+    board.record(&entry("ann", "normal", true, 3)).unwrap();
+    assert_eq!(board.load().unwrap().len(), 1);
+    assert!(!dir.join("scores.lock").exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn old_rows_without_hints_still_load() {
+    let (board, dir) = board("old");
+    fs::write(
+        board.path(),
+        "# player\tdifficulty\twon\tattempts\telapsed_ms\tdate\n\
+         ann\tnormal\twon\t3\t4000\t2024-05-01T12:30:00Z\n\
+         bob\thard\tlost\t10\t9000\t2024-05-02T08:00:00Z\n",
+    )
+    .unwrap();
+    board.record(&entry("ann", "normal", true, 2)).unwrap();
+
+    let entries = board.load().unwrap();
+    assert_eq!(entries.len(), 3);
+    // an old win is scored as one without hints, an old loss as nothing
+    assert_eq!((entries[0].hints, entries[0].score), (0, 896));
+    assert_eq!(entries[0].date, "2024-05-01T12:30:00Z");
+    assert_eq!((entries[1].won, entries[1].score), (false, 0));
+    assert_eq!(entries[2].player, "ann");
+
+    // the old lines are kept as they were
+    let text = fs::read_to_string(board.path()).unwrap();
+    assert!(text.contains("ann\tnormal\twon\t3\t4000\t2024-05-01T12:30:00Z\n"));
+
+    fs::write(board.path(), "ann\tnormal\twon\n").unwrap();
+    let error = board.load().unwrap_err();
+    assert!(error.to_string().contains("line 1: expected 6 or 8 fields"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn stats_are_kept_per_player_and_difficulty() {
+    let entries = [
+        entry("ann", "normal", true, 3),
+        entry("ann", "normal", true, 4),
+        entry("ann", "mastermind 4x6/10", true, 5),
+        entry("ann", "normal", false, 7),
+        entry("ann", "normal", true, 3),
+        entry("ann", "normal", true, 3),
+        entry("ann", "normal", true, 2),
+        entry("bob", "normal", false, 7),
+    ];
+    let stats = player_stats(&entries);
+    let keys: Vec<(&str, &str)> = stats
+        .iter()
+        .map(|stats| (stats.player.as_str(), stats.difficulty.as_str()))
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            ("ann", "mastermind 4x6/10"),
+            ("ann", "normal"),
+            ("bob", "normal")
+        ]
+    );
+
+    let ann = &stats[1];
+    assert_eq!((ann.games, ann.wins), (6, 5));
+    // the mastermind win in between does not break the normal streak, the loss does
+    assert_eq!(ann.best_streak, 3);
+    assert_eq!(
+        ann.histogram
+            .iter()
+            .map(|(a, c)| (*a, *c))
+            .collect::<Vec<_>>(),
+        [(2, 1), (3, 3), (4, 1)]
+    );
+    assert_eq!(stats[0].histogram.len(), 1);
+
+    let bob = &stats[2];
+    assert_eq!((bob.games, bob.wins, bob.best_streak), (1, 0, 0));
+    assert!(bob.histogram.is_empty());
+    assert_eq!(bob.win_rate(), 0.0);
+}
+
+#[test]
+fn high_scores_put_the_best_wins_first() {
+    let quick = |attempts, seconds| {
+        ScoreEntry::now(
+            "ann",
+            "normal",
+            true,
+            attempts,
+            Duration::from_secs(seconds),
+        )
+    };
+    let entries = [
+        quick(4, 10),
+        entry("bob", "normal", false, 1),
+        quick(2, 10),
+        // the same score as the one above it, but found with fewer attempts
+        quick(1, 60),
+        quick(2, 5),
+    ];
+
+    let best = high_scores(&entries, 3);
+    let order: Vec<(u32, u64)> = best
+        .iter()
+        .map(|entry| (entry.attempts, entry.elapsed.as_secs()))
+        .collect();
+    assert_eq!(order, [(2, 5), (1, 60), (2, 10)]);
+    assert!(best.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    // lost games never make it on the list
+    assert_eq!(high_scores(&entries, 10).len(), 4);
+}
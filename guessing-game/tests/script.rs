@@ -0,0 +1,106 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use guessing_game::{Json, Rules, SecretHost};
+
+// runs the game with the script on stdin, never with a terminal attached
+fn run(args: &[&str], script: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guessing-game"))
+        .args(args)
+        .args(["--script", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(script.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn events(output: &Output) -> Vec<Json> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| Json::parse(line).unwrap_or_else(|error| panic!("{}: {}", line, error)))
+        .collect()
+}
+
+fn secret(seed: u64) -> u32 {
+    SecretHost::seeded(&Rules::default(), seed).secret()
+}
+
+#[test]
+fn a_winning_script_exits_with_0() {
+    let secret = secret(42);
+    let script = format!("# warm up\n1\n\n{}\n", secret);
+    let output = run(&["--seed", "42", "--format", "json"], &script);
+    assert_eq!(output.status.code(), Some(0));
+
+    let events = events(&output);
+    let names: Vec<&str> = events
+        .iter()
+        .map(|event| event.get("event").unwrap().as_str().unwrap())
+        .collect();
+    let turns = if secret == 1 { 1 } else { 2 };
+    assert_eq!(names.len(), turns + 2);
+    assert_eq!(names[0], "start");
+    assert_eq!(names[turns + 1], "end");
+
+    let last = &events[turns];
+    assert_eq!(last.get("verdict").unwrap().as_str(), Some("correct"));
+    let end = &events[turns + 1];
+    assert_eq!(end.get("result").unwrap().as_str(), Some("won"));
+    assert_eq!(end.get("secret").unwrap().as_u32(), Some(secret));
+}
+
+#[test]
+fn a_losing_script_exits_with_1() {
+    let secret = secret(7);
+    let wrong = if secret == 50 { 51 } else { 50 };
+    let script = format!("{}\n", wrong).repeat(3);
+    let output = run(
+        &["--seed", "7", "--attempts", "3", "--format", "json"],
+        &script,
+    );
+    assert_eq!(output.status.code(), Some(1));
+
+    let events = events(&output);
+    let end = events.last().unwrap();
+    assert_eq!(end.get("result").unwrap().as_str(), Some("lost"));
+    assert_eq!(
+        events[3].get("verdict").unwrap().as_str(),
+        Some("out-of-attempts")
+    );
+}
+
+#[test]
+fn bad_input_exits_with_2() {
+    let output = run(&["--seed", "1"], "50\nfifty\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line 2: 'fifty'"));
+
+    // a script that ends before the game does is broken too
+    let secret = secret(1);
+    let wrong = if secret == 50 { 51 } else { 50 };
+    let output = run(&["--seed", "1"], &format!("{}\n", wrong));
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn rejected_guesses_are_reported_but_not_counted() {
+    let secret = secret(3);
+    let output = run(
+        &["--seed", "3", "--format", "json"],
+        &format!("500\n{}\n", secret),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let events = events(&output);
+    assert_eq!(events[1].get("event").unwrap().as_str(), Some("rejected"));
+    assert_eq!(events[1].get("guess").unwrap().as_u32(), Some(500));
+    assert_eq!(events[2].get("turn").unwrap().as_u32(), Some(1));
+}
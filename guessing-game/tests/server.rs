@@ -0,0 +1,207 @@
+use std::net::SocketAddr;
+use std::thread;
+
+use guessing_game::server::{Reply, Request, Room};
+use guessing_game::{Client, Rules, SecretHost, Server, Verdict};
+
+fn start(rules: Rules, seed: u64) -> SocketAddr {
+    let server = Server::bind("127.0.0.1:0", rules, seed).unwrap();
+    let addr = server.local_addr().unwrap();
+    // the thread ends with the test process
+    thread::spawn(move || server.run());
+    addr
+}
+
+fn receive(client: &mut Client) -> Reply {
+    client.receive().unwrap().expect("the server hung up")
+}
+
+fn join(addr: SocketAddr, name: &str, room: Room) -> Client {
+    let mut client = Client::connect(addr).unwrap();
+    client
+        .send(&Request::Join {
+            name: name.to_string(),
+            room,
+        })
+        .unwrap();
+    match receive(&mut client) {
+        Reply::Welcome { room: joined, .. } => assert_eq!(joined, room),
+        other => panic!("expected WELCOME, got {:?}", other),
+    }
+    client
+}
+
+// bisects over the wire until the server says correct, returns the secret
+fn bisect(client: &mut Client, rules: &Rules) -> u32 {
+    let (mut low, mut high) = (rules.low, rules.high);
+    loop {
+        let guess = low + (high - low) / 2;
+        client.send(&Request::Guess(guess)).unwrap();
+        match receive(client) {
+            Reply::Verdict { verdict, .. } => match verdict {
+                Verdict::TooLow => low = guess + 1,
+                Verdict::TooHigh => high = guess - 1,
+                Verdict::Correct => return guess,
+                other => panic!("unexpected verdict {:?}", other),
+            },
+            other => panic!("expected VERDICT, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn solo_games_run_side_by_side() {
+    let rules = Rules::default();
+    let addr = start(rules, 11);
+
+    let players: Vec<_> = (0..8)
+        .map(|player| {
+            thread::spawn(move || {
+                let mut client = join(addr, &format!("player{}", player), Room::Solo);
+                let secret = bisect(&mut client, &rules);
+                client.send(&Request::Quit).unwrap();
+                assert_eq!(receive(&mut client), Reply::Bye);
+                secret
+            })
+        })
+        .collect();
+    for player in players {
+        assert!(rules.contains(player.join().unwrap()));
+    }
+}
+
+#[test]
+fn a_lost_solo_game_shows_the_secret() {
+    let rules = Rules {
+        max_attempts: 1,
+        ..Rules::default()
+    };
+    let addr = start(rules, 3);
+    let mut client = join(addr, "unlucky", Room::Solo);
+
+    // with one attempt at least one of these two misses
+    for guess in [1, 2] {
+        client.send(&Request::Guess(guess)).unwrap();
+        match receive(&mut client) {
+            Reply::Verdict {
+                verdict: Verdict::Correct,
+                ..
+            } => continue,
+            Reply::Verdict {
+                verdict: Verdict::OutOfAttempts,
+                attempts_left: 0,
+                ..
+            } => {
+                let Reply::Secret(secret) = receive(&mut client) else {
+                    panic!("expected SECRET");
+                };
+                assert_ne!(secret, guess);
+                return;
+            }
+            other => panic!("unexpected reply {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn the_first_racer_to_find_the_secret_wins_for_everyone() {
+    let rules = Rules::default();
+    let addr = start(rules, 5);
+    let mut fast = join(addr, "fast", Room::Race);
+    let mut slow = join(addr, "slow", Room::Race);
+
+    let secret = bisect(&mut fast, &rules);
+    let winner = Reply::Winner {
+        name: "fast".to_string(),
+        secret,
+    };
+    for client in [&mut fast, &mut slow] {
+        assert_eq!(receive(client), winner);
+        assert_eq!(receive(client), Reply::Round(2));
+    }
+
+    // the next round has its own secret, and the slow racer can win it
+    let next = bisect(&mut slow, &rules);
+    assert_eq!(
+        receive(&mut fast),
+        Reply::Winner {
+            name: "slow".to_string(),
+            secret: next,
+        }
+    );
+}
+
+#[test]
+fn a_round_nobody_wins_ends_when_every_racer_is_out() {
+    let rules = Rules {
+        max_attempts: 1,
+        ..Rules::default()
+    };
+    let addr = start(rules, 8);
+    // the first round's secret comes straight from the seed
+    let secret = SecretHost::seeded(&rules, 8).secret();
+    let wrong = secret % rules.high + 1;
+
+    let mut first = join(addr, "first", Room::Race);
+    let mut second = join(addr, "second", Room::Race);
+    let lost = Reply::Verdict {
+        guess: wrong,
+        verdict: Verdict::OutOfAttempts,
+        attempts_left: 0,
+    };
+
+    first.send(&Request::Guess(wrong)).unwrap();
+    assert_eq!(receive(&mut first), lost);
+    // the round goes on for the second racer, the first one has to wait
+    first.send(&Request::Guess(secret)).unwrap();
+    assert_eq!(
+        receive(&mut first),
+        Reply::Error("you are out of this round, wait for the next".to_string())
+    );
+
+    second.send(&Request::Guess(wrong)).unwrap();
+    assert_eq!(receive(&mut second), lost);
+    for client in [&mut first, &mut second] {
+        assert_eq!(receive(client), Reply::Secret(secret));
+        assert_eq!(receive(client), Reply::Round(2));
+    }
+
+    // the room is not stuck, both can play the next round
+    for client in [&mut first, &mut second] {
+        client.send(&Request::Guess(50)).unwrap();
+        assert!(matches!(
+            receive(client),
+            Reply::Verdict {
+                guess: 50,
+                attempts_left: 0,
+                ..
+            }
+        ));
+    }
+}
+
+#[test]
+fn bad_lines_get_errors_and_the_session_goes_on() {
+    let addr = start(Rules::default(), 1);
+    let mut client = Client::connect(addr).unwrap();
+
+    client.send(&Request::Guess(50)).unwrap();
+    assert!(matches!(receive(&mut client), Reply::Error(_)));
+
+    let mut client = join(addr, "tester", Room::Solo);
+    client.send(&Request::Guess(1000)).unwrap();
+    assert_eq!(
+        receive(&mut client),
+        Reply::Error("1000 is outside the range 1-100".to_string())
+    );
+    client
+        .send(&Request::Join {
+            name: "again".to_string(),
+            room: Room::Race,
+        })
+        .unwrap();
+    assert_eq!(
+        receive(&mut client),
+        Reply::Error("you are already in a game".to_string())
+    );
+}
@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use guessing_game::{
+    Action, BinarySearch, Clock, GameState, GuessError, HostKind, ManualClock, Outcome, Player,
+    Rules, SecretHost, TimeAttack, Transcript, Verdict, play_timed,
+};
+
+// takes the given time to think before each guess, then lets another player pick it
+struct Thinking<P> {
+    clock: ManualClock,
+    delays: Vec<u64>,
+    inner: P,
+}
+
+impl<P: Player> Player for Thinking<P> {
+    fn next_action(&mut self, state: &GameState) -> Action {
+        let turn = (state.attempts() as usize).min(self.delays.len() - 1);
+        self.clock.advance(Duration::from_secs(self.delays[turn]));
+        self.inner.next_action(state)
+    }
+
+    fn observe(&mut self, guess: u32, verdict: Verdict, state: &GameState) {
+        self.inner.observe(guess, verdict, state);
+    }
+}
+
+fn countdown(seconds: u64, attempts: u32) -> Rules {
+    Rules {
+        max_attempts: attempts,
+        guess_limit: Some(Duration::from_secs(seconds)),
+        ..Rules::default()
+    }
+}
+
+#[test]
+fn a_slow_guess_counts_as_a_wrong_one() {
+    let rules = countdown(10, 7);
+    let clock = ManualClock::new();
+    let mut host = SecretHost::seeded(&rules, 5);
+    let mut player = Thinking {
+        clock: clock.clone(),
+        delays: vec![3, 11, 10, 1],
+        inner: BinarySearch::new(),
+    };
+    let mut state = GameState::new(rules);
+    let outcome = play_timed(&mut state, &mut host, &mut player, Duration::ZERO, &clock);
+
+    assert!(matches!(outcome, Outcome::Won { .. }));
+    // the countdown starts again after every answer, so only the 11 seconds were too slow
+    let verdicts: Vec<Verdict> = state.history().iter().map(|(_, v)| *v).collect();
+    assert_eq!(verdicts[1], Verdict::TooSlow);
+    assert_eq!(
+        verdicts.iter().filter(|v| **v == Verdict::TooSlow).count(),
+        1
+    );
+
+    // the slow guess is written down as such and replays the same way
+    let transcript = Transcript::from_game(5, HostKind::Fair, &state);
+    assert!(transcript.to_text().contains("guess-limit-ms 10000\n"));
+    let parsed = Transcript::parse(&transcript.to_text()).unwrap();
+    assert_eq!(parsed.replay().unwrap(), state);
+}
+
+#[test]
+fn a_slow_last_guess_ends_the_game() {
+    let rules = countdown(5, 2);
+    let clock = ManualClock::new();
+    let mut host = SecretHost::seeded(&rules, 1);
+    let mut player = Thinking {
+        clock: clock.clone(),
+        delays: vec![6],
+        inner: BinarySearch::new(),
+    };
+    let mut state = GameState::new(rules);
+    let outcome = play_timed(&mut state, &mut host, &mut player, Duration::ZERO, &clock);
+
+    assert_eq!(outcome, Outcome::Lost { attempts: 2 });
+    assert_eq!(
+        state.history().iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+        [Verdict::TooSlow, Verdict::OutOfTime]
+    );
+    assert!(Rules::validate(&countdown(0, 2)).is_err());
+}
+
+#[test]
+fn a_late_guess_outside_the_range_is_rejected_not_counted() {
+    let mut state = GameState::new(countdown(5, 2));
+    let outside = GuessError::OutOfRange {
+        guess: 101,
+        low: 1,
+        high: 100,
+    };
+    assert_eq!(state.too_slow(101), Err(outside.clone()));
+    assert_eq!(state.time_up(101), Err(outside));
+    assert_eq!(state.attempts(), 0);
+
+    // a typo on the last attempt doesn't end the game either
+    state.too_slow(50).unwrap();
+    assert!(state.too_slow(0).is_err());
+    assert!(!state.is_over());
+    assert_eq!(state.too_slow(50), Ok(Verdict::OutOfTime));
+    assert_eq!(state.time_up(50), Err(GuessError::GameOver));
+}
+
+#[test]
+fn a_time_attack_stops_when_the_time_is_up() {
+    // one number only, so every secret takes exactly one guess
+    let rules = Rules {
+        low: 7,
+        high: 7,
+        ..Rules::default()
+    };
+    let clock = ManualClock::new();
+    let attack = TimeAttack::new(rules, Duration::from_secs(60), 3);
+    let score = attack.run(&clock, &mut |_, _| {
+        Box::new(Thinking {
+            clock: clock.clone(),
+            delays: vec![7],
+            inner: BinarySearch::new(),
+        })
+    });
+
+    // guesses at 7, 14, ... 56 seconds count, the one at 63 is too late
+    assert_eq!(score.solved, 8);
+    assert_eq!(score.missed, 0);
+    assert_eq!(score.attempts, 9);
+    assert_eq!(score.elapsed, Duration::from_secs(60));
+    assert!(!score.quit);
+}
+
+#[test]
+fn a_time_attack_counts_missed_secrets_and_quits() {
+    struct Stubborn(ManualClock);
+
+    impl Player for Stubborn {
+        fn next_action(&mut self, state: &GameState) -> Action {
+            self.0.advance(Duration::from_secs(4));
+            if self.0.now() >= Duration::from_secs(30) {
+                Action::Quit
+            } else {
+                Action::Guess(state.rules().low)
+            }
+        }
+    }
+
+    let rules = Rules {
+        low: 1,
+        high: 2,
+        max_attempts: 1,
+        ..Rules::default()
+    };
+    let clock = ManualClock::new();
+    let attack = TimeAttack::new(rules, Duration::from_secs(60), 9);
+    let score = attack.run(&clock, &mut |_, _| Box::new(Stubborn(clock.clone())));
+
+    // seven guesses at 4 to 28 seconds, then the player stops
+    assert!(score.quit);
+    assert_eq!(score.solved + score.missed, 7);
+    assert!(score.missed > 0);
+    assert_eq!(score.elapsed, Duration::from_secs(32));
+}
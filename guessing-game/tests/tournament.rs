@@ -0,0 +1,79 @@
+use guessing_game::solvers::BinarySearch;
+use guessing_game::{Action, Entrant, GameState, Player, Rules, Tournament};
+
+// guesses upwards from the lowest number, one at a time
+struct Counter {
+    next: u32,
+}
+
+impl Player for Counter {
+    fn next_action(&mut self, state: &GameState) -> Action {
+        self.next = self.next.max(state.rules().low);
+        self.next += 1;
+        Action::Guess(self.next - 1)
+    }
+}
+
+struct Counting;
+
+impl Entrant for Counting {
+    fn name(&self) -> String {
+        "counting, slowly".to_string()
+    }
+
+    fn player(&self, _seed: u64) -> Box<dyn Player> {
+        Box::new(Counter { next: 0 })
+    }
+}
+
+struct Bisecting;
+
+impl Entrant for Bisecting {
+    fn name(&self) -> String {
+        "bisecting".to_string()
+    }
+
+    fn player(&self, _seed: u64) -> Box<dyn Player> {
+        Box::new(BinarySearch::new())
+    }
+}
+
+#[test]
+fn standings_do_not_depend_on_the_thread_count() {
+    let tournament = Tournament::with_strategies(Rules::default(), 300, 17);
+    let one = tournament.run(1);
+    for threads in [2, 3, 8, 64] {
+        assert_eq!(tournament.run(threads), one, "{} threads", threads);
+    }
+    assert_eq!(one.rows.len(), 4);
+    // every entrant meets the three others on every secret
+    assert!(one.rows.iter().all(|row| row.played == 900));
+}
+
+#[test]
+fn registered_entrants_are_ranked() {
+    let mut tournament = Tournament::new(Rules::default(), 50, 3);
+    tournament.register(Counting);
+    tournament.register(Bisecting);
+    let standings = tournament.run(4);
+
+    let names: Vec<&str> = standings.rows.iter().map(|row| row.name.as_str()).collect();
+    assert_eq!(names, ["bisecting", "counting, slowly"]);
+    let best = &standings.rows[0];
+    assert_eq!(best.solved, 50);
+    assert_eq!(best.wins + best.draws + best.losses, 50);
+    assert_eq!(best.points, best.wins * 2 + best.draws);
+
+    let csv = standings.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("rank,entrant,played,won,drawn,lost,points,solved,mean_attempts")
+    );
+    assert!(
+        lines
+            .nth(1)
+            .unwrap()
+            .starts_with("2,\"counting, slowly\",50,")
+    );
+}
@@ -0,0 +1,93 @@
+use guessing_game::{
+    BinarySearch, GameState, HostKind, ReplayError, Rng, Rules, Transcript, Verdict, play,
+};
+
+fn recorded_game(seed: u64, host: HostKind) -> (Transcript, GameState) {
+    let rules = Rules {
+        max_attempts: 12,
+        ..Rules::default()
+    };
+    let mut state = GameState::new(rules);
+    play(
+        &mut state,
+        host.build(&rules, seed).as_mut(),
+        &mut BinarySearch::new(),
+    );
+    (Transcript::from_game(seed, host, &state), state)
+}
+
+#[test]
+fn the_same_seed_gives_the_same_numbers() {
+    let mut rng = Rng::new(42);
+    let numbers: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+    // written down once, a change here breaks every transcript that was ever saved
+    assert_eq!(
+        numbers,
+        [
+            3_580_622_183_945_639_842,
+            10_378_725_325_292_465_923,
+            8_967_075_514_996_744_559,
+            5_001_014_893_397_904_463,
+        ]
+    );
+
+    let mut again = Rng::new(42);
+    assert!(numbers.iter().all(|n| *n == again.next_u64()));
+    assert_ne!(Rng::new(43).next_u64(), numbers[0]);
+
+    let mut rng = Rng::new(7);
+    assert!((0..1000).all(|_| (3..=9).contains(&rng.range(3, 9))));
+}
+
+#[test]
+fn a_transcript_reads_back_as_it_was_written() {
+    for host in [HostKind::Fair, HostKind::Evil, HostKind::Liar(2)] {
+        let (transcript, _) = recorded_game(42, host);
+        let text = transcript.to_text();
+        assert!(text.starts_with("# guessing-game transcript\nseed 42\n"));
+        assert_eq!(Transcript::parse(&text).unwrap(), transcript);
+    }
+}
+
+#[test]
+fn a_replay_ends_where_the_recording_did() {
+    for seed in 0..20 {
+        for host in [HostKind::Fair, HostKind::Evil, HostKind::Liar(2)] {
+            let (transcript, state) = recorded_game(seed, host);
+            assert!(state.attempts() > 0);
+            let parsed = Transcript::parse(&transcript.to_text()).unwrap();
+            assert_eq!(parsed.replay().unwrap(), state, "seed {} {:?}", seed, host);
+        }
+    }
+}
+
+#[test]
+fn a_changed_verdict_is_found() {
+    let (mut transcript, _) = recorded_game(42, HostKind::Fair);
+    let (guess, recorded) = transcript.turns[0];
+    let tampered = if recorded == Verdict::TooLow {
+        Verdict::TooHigh
+    } else {
+        Verdict::TooLow
+    };
+    transcript.turns[0].1 = tampered;
+
+    let parsed = Transcript::parse(&transcript.to_text()).unwrap();
+    assert_eq!(
+        parsed.replay(),
+        Err(ReplayError::Mismatch {
+            turn: 1,
+            guess,
+            recorded: tampered,
+            replayed: recorded,
+        })
+    );
+
+    // a different seed is a different secret, so the recording stops fitting somewhere
+    let (mut transcript, _) = recorded_game(42, HostKind::Fair);
+    transcript.seed = 43;
+    assert!(matches!(
+        transcript.replay(),
+        Err(ReplayError::Mismatch { .. })
+    ));
+}
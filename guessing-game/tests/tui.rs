@@ -0,0 +1,45 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use guessing_game::tui::{open_range, range_bar};
+use guessing_game::{GameState, Rules, SecretHost};
+
+#[test]
+fn the_range_bar_narrows_with_each_guess() {
+    let rules = Rules::default();
+    let mut host = SecretHost::new(30);
+    let mut state = GameState::new(rules);
+    assert_eq!(range_bar(&state, 10), "==========");
+
+    state.guess(&mut host, 50).unwrap();
+    assert_eq!(open_range(&state), (1, 49));
+    assert_eq!(range_bar(&state, 10), "=====.....");
+
+    state.guess(&mut host, 21).unwrap();
+    assert_eq!(open_range(&state), (22, 49));
+    assert_eq!(range_bar(&state, 10), "..===.....");
+
+    state.guess(&mut host, 30).unwrap();
+    assert_eq!(open_range(&state), (30, 30));
+    assert_eq!(range_bar(&state, 10), "..=.......");
+}
+
+#[test]
+fn without_a_terminal_the_game_falls_back_to_line_mode() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guessing-game"))
+        .args(["--tui", "--seed", "1", "--scores"])
+        .arg(std::env::temp_dir().join("guessing-game-tui-test-scores.txt"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"quit\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line mode"));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Please input your guess"));
+    // not a single escape code
+    assert!(!stdout.contains('\x1b'));
+}
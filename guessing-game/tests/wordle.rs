@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use guessing_game::wordle::{WordGuesser, WordleError, feedback, hard_mode_violation};
+use guessing_game::{Dictionary, EntropySolver, Pattern, WordleGame};
+
+fn marks(guess: &str, secret: &str) -> String {
+    feedback(secret, guess).to_string()
+}
+
+fn dictionary() -> Dictionary {
+    Dictionary::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("words.txt"), 5).unwrap()
+}
+
+#[test]
+fn repeated_letters_are_only_marked_as_often_as_they_occur() {
+    // abide has one e, so only the first e of speed finds it
+    assert_eq!(marks("speed", "abide"), "--+-+");
+    assert_eq!(marks("speed", "erase"), "+-++-");
+    assert_eq!(marks("geese", "those"), "---==");
+    // exact matches are claimed first, even when they come after a present one
+    assert_eq!(marks("lolly", "hello"), "-+==-");
+    assert_eq!(marks("eerie", "there"), "+-+-=");
+    assert_eq!(marks("crane", "crane"), "=====");
+}
+
+#[test]
+fn hard_mode_needs_every_found_letter() {
+    let history = vec![("crane".to_string(), feedback("trace", "crane"))];
+    assert_eq!(history[0].1.to_string(), "+==-=");
+
+    assert_eq!(hard_mode_violation(&history, "trace"), None);
+    assert_eq!(
+        hard_mode_violation(&history, "brake").map(|rule| rule.to_string()),
+        Some("the guess must contain C".to_string())
+    );
+    assert_eq!(
+        hard_mode_violation(&history, "react").map(|rule| rule.to_string()),
+        Some("letter 2 must be R".to_string())
+    );
+
+    // two e's found means two e's needed
+    let history = vec![("geese".to_string(), feedback("sheep", "geese"))];
+    assert_eq!(
+        hard_mode_violation(&history, "bless").map(|rule| rule.to_string()),
+        Some("the guess must contain E 2 times".to_string())
+    );
+    assert_eq!(hard_mode_violation(&history, "sheep"), None);
+}
+
+#[test]
+fn the_game_rejects_words_that_break_the_rules() {
+    let mut game = WordleGame::new(dictionary(), "trace", 6, true);
+    assert_eq!(game.guess("crane").unwrap().to_string(), "+==-=");
+    assert_eq!(
+        game.guess("zzzzz"),
+        Err(WordleError::UnknownWord("zzzzz".to_string()))
+    );
+    assert_eq!(
+        game.guess("toolong"),
+        Err(WordleError::WrongLength { expected: 5 })
+    );
+    assert!(matches!(game.guess("grade"), Err(WordleError::HardMode(_))));
+    assert!(game.guess("TRACE").unwrap().is_solved());
+    assert_eq!(game.guess("trace"), Err(WordleError::GameOver));
+}
+
+#[test]
+fn dictionary_errors_have_line_numbers() {
+    let error = Dictionary::parse("# words\nhello\nit's\n", 5).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 3: 'it's' is not a word, only the letters a to z are allowed"
+    );
+    assert!(Dictionary::parse("cat\ndog\n", 5).is_err());
+}
+
+// follows the solver down every pattern it can get, so every word is played once
+// without recomputing the shared first guesses for each of them
+fn worst_case(solver: &EntropySolver, secrets: Vec<String>, depth: u32, solved: &mut usize) -> u32 {
+    let guess = solver.best_guess().expect("a word is left");
+    let mut groups: HashMap<Pattern, Vec<String>> = HashMap::new();
+    for secret in secrets {
+        groups
+            .entry(feedback(&secret, &guess))
+            .or_default()
+            .push(secret);
+    }
+
+    let mut worst = depth;
+    for (pattern, group) in groups {
+        if pattern.is_solved() {
+            *solved += 1;
+            continue;
+        }
+        let mut next = solver.clone();
+        next.observe(&guess, &pattern);
+        worst = worst.max(worst_case(&next, group, depth + 1, solved));
+    }
+    worst
+}
+
+#[test]
+fn entropy_solver_finds_every_word_in_six_guesses() {
+    let dictionary = dictionary();
+    let solver = EntropySolver::new(&dictionary, false);
+    let mut solved = 0;
+    let worst = worst_case(&solver, dictionary.words().to_vec(), 1, &mut solved);
+    assert_eq!(solved, dictionary.words().len());
+    assert!(worst <= 6, "needed {} guesses", worst);
+}
+
+#[test]
+fn entropy_solver_keeps_to_hard_mode() {
+    let dictionary = dictionary();
+    for seed in 0..10 {
+        let mut game = WordleGame::seeded(dictionary.clone(), seed, 10, true);
+        let mut solver = EntropySolver::new(&dictionary, true);
+        while !game.is_over() {
+            let guess = solver.best_guess().unwrap();
+            let pattern = game
+                .guess(&guess)
+                .expect("the solver only makes valid guesses");
+            solver.observe(&guess, &pattern);
+        }
+        assert!(game.is_won(), "seed {} lost", seed);
+    }
+}
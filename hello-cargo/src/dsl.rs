@@ -0,0 +1,545 @@
+// a tiny text language for move scripts, so a sequence of Moves can be written down
+//
+//     walk north 3 4; jump 5; peek left
+//     run 8 6 x3      # the same run three times
+//
+// moves are split by `;` or a new line, `x3` after a move repeats it, `#` starts a
+// comment. the lexer and the parser are written by hand and every error says the
+// line and column it was found at. print_script writes moves back in the same
+// language, so parsing what it prints gives the same moves again.
+
+use crate::enums::{Directions, Moves, Side};
+use std::fmt;
+
+// the repeats are written out, so these keep a few bytes of script from asking
+// for billions of moves: one `xN` can't go past MAX_REPEAT, a whole script past MAX_MOVES
+pub(crate) const MAX_REPEAT: u32 = 1_000;
+pub(crate) const MAX_MOVES: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Word(String),
+    Number(u32),
+    // `x3`, how many times to do the move before it
+    Repeat(u32),
+    // `;` or a new line
+    Separator(char),
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Word(word) => write!(f, "'{}'", word),
+            TokenKind::Number(n) => write!(f, "the number {}", n),
+            TokenKind::Repeat(n) => write!(f, "'x{}'", n),
+            TokenKind::Separator('\n') => write!(f, "the end of the line"),
+            TokenKind::Separator(c) => write!(f, "'{}'", c),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    line: usize,
+    col: usize,
+}
+
+// lines and columns count from 1, columns in characters
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ParseError {
+    pub(crate) line: usize,
+    pub(crate) col: usize,
+    pub(crate) message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.col, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn lex(source: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let (mut line, mut col) = (1, 1);
+
+    while let Some(&c) = chars.peek() {
+        let (start_line, start_col) = (line, col);
+        let error = |message: String| ParseError {
+            line: start_line,
+            col: start_col,
+            message,
+        };
+
+        if c == '\n' || c == ';' {
+            chars.next();
+            tokens.push(Token {
+                kind: TokenKind::Separator(c),
+                line,
+                col,
+            });
+            if c == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+            continue;
+        }
+        if c.is_whitespace() {
+            chars.next();
+            col += 1;
+            continue;
+        }
+        if c == '#' {
+            // the comment runs to the end of the line, the new line still separates
+            while chars.peek().is_some_and(|c| *c != '\n') {
+                chars.next();
+                col += 1;
+            }
+            continue;
+        }
+
+        if c.is_ascii_alphanumeric() {
+            let mut text = String::new();
+            while let Some(&c) = chars.peek() {
+                if !c.is_ascii_alphanumeric() {
+                    break;
+                }
+                text.push(c);
+                chars.next();
+                col += 1;
+            }
+            let kind = if text.bytes().all(|b| b.is_ascii_digit()) {
+                TokenKind::Number(parse_u32(&text).map_err(error)?)
+            } else if let Some(count) = text
+                .strip_prefix(['x', 'X'])
+                .filter(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            {
+                TokenKind::Repeat(parse_u32(count).map_err(error)?)
+            } else if text.as_bytes()[0].is_ascii_digit() {
+                return Err(error(format!("'{}' is not a number", text)));
+            } else {
+                TokenKind::Word(text.to_ascii_lowercase())
+            };
+            tokens.push(Token {
+                kind,
+                line: start_line,
+                col: start_col,
+            });
+            continue;
+        }
+
+        return Err(error(format!("unexpected character '{}'", c)));
+    }
+    Ok(tokens)
+}
+
+fn parse_u32(digits: &str) -> Result<u32, String> {
+    digits
+        .parse()
+        .map_err(|_| format!("{} is too large, the most is {}", digits, u32::MAX))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    // where the input ends, for errors about something missing at the very end
+    end: (usize, usize),
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    // an error pointing at the next token, or at the end if there is none
+    fn error_here(&self, message: String) -> ParseError {
+        let (line, col) = self.peek().map_or(self.end, |t| (t.line, t.col));
+        ParseError { line, col, message }
+    }
+
+    fn found(&self) -> String {
+        self.peek()
+            .map_or("the end of the script".to_string(), |t| t.kind.to_string())
+    }
+
+    fn number(&mut self, what: &str) -> Result<u32, ParseError> {
+        match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Number(n)) => {
+                let n = *n;
+                self.pos += 1;
+                Ok(n)
+            }
+            _ => Err(self.error_here(format!("expected {}, found {}", what, self.found()))),
+        }
+    }
+
+    fn word(&mut self, what: &str) -> Result<(String, Token), ParseError> {
+        match self.peek() {
+            Some(Token {
+                kind: TokenKind::Word(word),
+                ..
+            }) => {
+                let word = word.clone();
+                let token = self.next().expect("just peeked at it");
+                Ok((word, token))
+            }
+            _ => Err(self.error_here(format!("expected {}, found {}", what, self.found()))),
+        }
+    }
+
+    fn direction(&mut self) -> Result<Directions, ParseError> {
+        let (word, token) = self.word("a direction")?;
+        parse_direction(&word).ok_or_else(|| ParseError {
+            line: token.line,
+            col: token.col,
+            message: format!(
+                "unknown direction '{}', expected one like n, north, ne or northeast",
+                word
+            ),
+        })
+    }
+
+    fn side(&mut self) -> Result<Side, ParseError> {
+        let (word, token) = self.word("a side")?;
+        parse_side(&word).ok_or_else(|| ParseError {
+            line: token.line,
+            col: token.col,
+            message: format!("unknown side '{}', expected left or right", word),
+        })
+    }
+
+    fn one_move(&mut self) -> Result<Moves, ParseError> {
+        let (word, token) = self.word("a move")?;
+        let m = match word.as_str() {
+            "walk" => Moves::Walk {
+                d: self.direction()?,
+                s: self.number("the speed of the walk")?,
+                v: self.number("the vigor of the walk")?,
+            },
+            "run" => Moves::Run {
+                s: self.number("the speed of the run")?,
+                v: self.number("the vigor of the run")?,
+            },
+            "jump" => Moves::Jump {
+                h: self.number("the height of the jump")?,
+            },
+            "peek" => Moves::Peek { k: self.side()? },
+            "duck" => Moves::Duck,
+            _ => {
+                return Err(ParseError {
+                    line: token.line,
+                    col: token.col,
+                    message: format!(
+                        "unknown move '{}', expected walk, run, jump, peek or duck",
+                        word
+                    ),
+                });
+            }
+        };
+        Ok(m)
+    }
+}
+
+// parses a whole script, the moves come out in order with repeats written out
+pub(crate) fn parse_script(source: &str) -> Result<Vec<Moves>, ParseError> {
+    let tokens = lex(source)?;
+    let last_line = source.split('\n').next_back().unwrap_or("");
+    let end = (
+        source.matches('\n').count() + 1,
+        last_line.chars().count() + 1,
+    );
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end,
+    };
+    let mut moves = Vec::new();
+
+    loop {
+        // empty statements are fine, `;;` or blank lines
+        while matches!(
+            parser.peek().map(|t| &t.kind),
+            Some(TokenKind::Separator(_))
+        ) {
+            parser.pos += 1;
+        }
+        if parser.peek().is_none() {
+            break;
+        }
+        if moves.len() >= MAX_MOVES {
+            return Err(parser.error_here(format!("a script can have at most {} moves", MAX_MOVES)));
+        }
+
+        let m = parser.one_move()?;
+        let times = match parser.peek().map(|t| &t.kind) {
+            Some(TokenKind::Repeat(0)) => {
+                return Err(parser.error_here("a move can't be repeated 0 times".to_string()));
+            }
+            Some(TokenKind::Repeat(n)) if *n > MAX_REPEAT => {
+                return Err(parser.error_here(format!(
+                    "a move can be repeated at most {} times, not {}",
+                    MAX_REPEAT, n
+                )));
+            }
+            Some(TokenKind::Repeat(n)) if moves.len() + *n as usize > MAX_MOVES => {
+                return Err(parser.error_here(format!(
+                    "a script can have at most {} moves, this repeat makes it {}",
+                    MAX_MOVES,
+                    moves.len() + *n as usize
+                )));
+            }
+            Some(TokenKind::Repeat(n)) => {
+                let n = *n;
+                parser.pos += 1;
+                n
+            }
+            _ => 1,
+        };
+        for _ in 0..times {
+            moves.push(m.clone());
+        }
+
+        match parser.peek().map(|t| &t.kind) {
+            None | Some(TokenKind::Separator(_)) => {}
+            Some(_) => {
+                return Err(parser.error_here(format!(
+                    "expected ';' or a new line after the move, found {}",
+                    parser.found()
+                )));
+            }
+        }
+    }
+    Ok(moves)
+}
+
+// anything Directions::from_str takes that is one word, like n, ne or northeast
+pub(crate) fn parse_direction(word: &str) -> Option<Directions> {
+    word.parse().ok()
+}
+
+pub(crate) fn parse_side(word: &str) -> Option<Side> {
+    match word.to_ascii_lowercase().as_str() {
+        "left" => Some(Side::Left),
+        "right" => Some(Side::Right),
+        _ => None,
+    }
+}
+
+pub(crate) fn print_direction(d: Directions) -> &'static str {
+    d.name()
+}
+
+pub(crate) fn print_side(k: Side) -> &'static str {
+    match k {
+        Side::Left => "left",
+        Side::Right => "right",
+    }
+}
+
+pub(crate) fn print_move(m: &Moves) -> String {
+    match m {
+        Moves::Walk { d, s, v } => format!("walk {} {} {}", print_direction(*d), s, v),
+        Moves::Run { s, v } => format!("run {} {}", s, v),
+        Moves::Jump { h } => format!("jump {}", h),
+        Moves::Peek { k } => format!("peek {}", print_side(*k)),
+        Moves::Duck => "duck".to_string(),
+    }
+}
+
+// the same move several times in a row is written once with a repeat count,
+// a longer run than one `xN` may ask for is split over several of them
+pub(crate) fn print_script(moves: &[Moves]) -> String {
+    let mut parts = Vec::new();
+    let mut i = 0;
+    while i < moves.len() {
+        let run = moves[i..]
+            .iter()
+            .take_while(|m| **m == moves[i])
+            .take(MAX_REPEAT as usize)
+            .count();
+        if run > 1 {
+            parts.push(format!("{} x{}", print_move(&moves[i]), run));
+        } else {
+            parts.push(print_move(&moves[i]));
+        }
+        i += run;
+    }
+    parts.join("; ")
+}
+
+pub fn demonstrate_dsl() {
+    println!("=== Move Script Examples ===\n");
+
+    let script = "walk north 3 4; jump 5; peek left\nrun 8 6 x3  # sprint\nduck";
+    match parse_script(script) {
+        Ok(moves) => {
+            println!("{:?} parses into {} moves:", script, moves.len());
+            for m in &moves {
+                println!("  {}", m.describe());
+            }
+            println!("and prints back as: {}", print_script(&moves));
+        }
+        Err(error) => println!("{:?} does not parse: {}", script, error),
+    }
+
+    for broken in [
+        "walk north 3; jump 5",
+        "jump 2\npeek up",
+        "run 8 6 x0",
+        "hop 3",
+    ] {
+        if let Err(error) = parse_script(broken) {
+            println!("{:?}: {}", broken, error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIDES: [Side; 2] = [Side::Left, Side::Right];
+
+    // one of every variant, with the smallest and largest numbers each can hold
+    fn every_move() -> Vec<Moves> {
+        let mut moves = vec![Moves::Duck];
+        for n in [0, 1, 42, u32::MAX] {
+            moves.push(Moves::Jump { h: n });
+            moves.push(Moves::Run {
+                s: n,
+                v: u32::MAX - n,
+            });
+            for d in Directions::ALL {
+                moves.push(Moves::Walk { d, s: n, v: n / 2 });
+            }
+        }
+        for k in SIDES {
+            moves.push(Moves::Peek { k });
+        }
+        moves
+    }
+
+    #[test]
+    fn parses_the_example_script() {
+        let moves = parse_script("walk north 3 4; jump 5; peek left; run 8 6 x3").unwrap();
+        assert_eq!(
+            moves,
+            vec![
+                Moves::Walk {
+                    d: Directions::North,
+                    s: 3,
+                    v: 4
+                },
+                Moves::Jump { h: 5 },
+                Moves::Peek { k: Side::Left },
+                Moves::Run { s: 8, v: 6 },
+                Moves::Run { s: 8, v: 6 },
+                Moves::Run { s: 8, v: 6 },
+            ]
+        );
+    }
+
+    #[test]
+    fn new_lines_comments_and_empty_statements() {
+        let moves = parse_script("\n# warm up\nDUCK;;\n  Peek Right # look first\n\n").unwrap();
+        assert_eq!(moves, vec![Moves::Duck, Moves::Peek { k: Side::Right }]);
+        assert_eq!(parse_script("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn errors_point_at_the_line_and_column() {
+        let error = parse_script("jump 2\npeek up").unwrap_err();
+        assert_eq!((error.line, error.col), (2, 6));
+        assert!(error.message.contains("unknown side 'up'"));
+
+        let error = parse_script("walk north 3; jump 5").unwrap_err();
+        assert_eq!((error.line, error.col), (1, 13));
+        assert!(error.message.contains("vigor"));
+
+        // missing at the very end points just past the last character
+        let error = parse_script("duck\njump").unwrap_err();
+        assert_eq!((error.line, error.col), (2, 5));
+
+        let error = parse_script("duck duck").unwrap_err();
+        assert_eq!((error.line, error.col), (1, 6));
+
+        let error = parse_script("run 1 2 x0").unwrap_err();
+        assert_eq!((error.line, error.col), (1, 9));
+
+        let error = parse_script("jump 4294967296").unwrap_err();
+        assert_eq!((error.line, error.col), (1, 6));
+        assert!(error.message.contains("too large"));
+
+        let error = parse_script("jump -1").unwrap_err();
+        assert_eq!((error.line, error.col), (1, 6));
+
+        let error = parse_script("jump 3a").unwrap_err();
+        assert!(error.message.contains("not a number"));
+    }
+
+    #[test]
+    fn repeats_are_capped() {
+        let most = format!("duck x{}", MAX_REPEAT);
+        assert_eq!(parse_script(&most).unwrap().len(), MAX_REPEAT as usize);
+
+        let error = parse_script(
+            "duck
+run 1 2 x4294967295",
+        )
+        .unwrap_err();
+        assert_eq!((error.line, error.col), (2, 9));
+        assert!(error.message.contains("at most 1000 times"));
+
+        // a lot of repeats that are fine one by one still add up
+        let lines = MAX_MOVES / MAX_REPEAT as usize;
+        let full = "duck x1000\n".repeat(lines);
+        assert_eq!(parse_script(&full).unwrap().len(), MAX_MOVES);
+        let one_short = format!("{}duck x999\n", "duck x1000\n".repeat(lines - 1));
+        let error = parse_script(&format!("{}jump 1 x2", one_short)).unwrap_err();
+        assert_eq!((error.line, error.col), (lines + 1, 8));
+        assert!(error.message.contains("makes it 10001"));
+        let error = parse_script(&format!("{}peek left", full)).unwrap_err();
+        assert_eq!((error.line, error.col), (lines + 1, 1));
+    }
+
+    #[test]
+    fn every_value_survives_print_and_parse() {
+        for d in Directions::ALL {
+            assert_eq!(parse_direction(print_direction(d)), Some(d));
+        }
+        for k in SIDES {
+            assert_eq!(parse_side(print_side(k)), Some(k));
+        }
+        for m in every_move() {
+            assert_eq!(parse_script(&print_move(&m)).unwrap(), vec![m]);
+        }
+
+        // repeats are folded when printing and unfolded again when parsing
+        let mut moves = every_move();
+        moves.extend([Moves::Duck, Moves::Duck, Moves::Duck]);
+        let printed = print_script(&moves);
+        assert!(printed.ends_with("duck x3"));
+        assert_eq!(parse_script(&printed).unwrap(), moves);
+
+        // a run longer than MAX_REPEAT still prints as something the parser takes
+        let long = vec![Moves::Duck; MAX_REPEAT as usize + 500];
+        let printed = print_script(&long);
+        assert_eq!(printed, "duck x1000; duck x500");
+        assert_eq!(parse_script(&printed).unwrap(), long);
+    }
+}
@@ -3,12 +3,181 @@
 // Enums allow you to define types by enumerating its possible variants
 // to use enums and match code blocks to the specific case of an enum, rust provides pattern matching in the same form of a switch case as in javascript
 // while using pattern matching we need to match code blocks for every case if any is left out code panics
-#[derive(Debug)]
-enum Directions {
+use crate::stamina::CostTable;
+use std::f64::consts::FRAC_1_SQRT_2;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Directions {
     North,
     South,
     East,
     West,
+    // the diagonals, halfway between two of the four above
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+// all eight directions go round like a compass: clockwise in steps of 45 degrees,
+// 0 is north and 90 is east
+impl Directions {
+    pub(crate) const ALL: [Directions; 8] = [
+        Directions::North,
+        Directions::NorthEast,
+        Directions::East,
+        Directions::SouthEast,
+        Directions::South,
+        Directions::SouthWest,
+        Directions::West,
+        Directions::NorthWest,
+    ];
+
+    // how many eighths of a turn clockwise from north, 0 to 7
+    fn eighths(self) -> i32 {
+        Directions::ALL
+            .iter()
+            .position(|d| *d == self)
+            .expect("ALL has every direction") as i32
+    }
+
+    // turns clockwise by `eighths` steps of 45 degrees, negative turns counterclockwise
+    pub(crate) fn rotate(self, eighths: i32) -> Directions {
+        Directions::ALL[(self.eighths() + eighths).rem_euclid(8) as usize]
+    }
+
+    pub(crate) fn turn_right(self) -> Directions {
+        self.rotate(1)
+    }
+
+    pub(crate) fn turn_left(self) -> Directions {
+        self.rotate(-1)
+    }
+
+    pub(crate) fn reverse(self) -> Directions {
+        self.rotate(4)
+    }
+
+    // the direction on the left or right hand of someone facing this way,
+    // a quarter turn to that side
+    pub(crate) fn to_side(self, k: Side) -> Directions {
+        match k {
+            Side::Left => self.rotate(-2),
+            Side::Right => self.rotate(2),
+        }
+    }
+
+    pub(crate) fn degrees(self) -> u32 {
+        self.eighths() as u32 * 45
+    }
+
+    // any whole number of degrees, 450 is the same as 90 and -45 the same as 315
+    // None when it is not one of the eight directions
+    pub(crate) fn from_degrees(degrees: i32) -> Option<Directions> {
+        let degrees = degrees.rem_euclid(360);
+        if degrees % 45 == 0 {
+            Some(Directions::ALL[(degrees / 45) as usize])
+        } else {
+            None
+        }
+    }
+
+    // a vector of length 1 pointing this way, x to the east and y to the north
+    pub(crate) fn unit_vector(self) -> (f64, f64) {
+        let (x, y) = self.offset();
+        if x != 0 && y != 0 {
+            (x as f64 * FRAC_1_SQRT_2, -y as f64 * FRAC_1_SQRT_2)
+        } else {
+            (x as f64, -y as f64)
+        }
+    }
+
+    // the direction closest to the vector (x east, y north), None for a vector with
+    // no direction: zero, infinite or NaN
+    pub(crate) fn from_vector(x: f64, y: f64) -> Option<Directions> {
+        if !(x.is_finite() && y.is_finite()) || (x == 0.0 && y == 0.0) {
+            return None;
+        }
+        // atan2(x, y) is the compass bearing, 0 at north and growing clockwise
+        let eighths = (x.atan2(y).to_degrees() / 45.0).round() as i32;
+        Some(Directions::North.rotate(eighths))
+    }
+
+    // one step on a grid where x grows to the east and y grows to the south
+    pub(crate) fn offset(self) -> (i32, i32) {
+        match self {
+            Directions::North => (0, -1),
+            Directions::NorthEast => (1, -1),
+            Directions::East => (1, 0),
+            Directions::SouthEast => (1, 1),
+            Directions::South => (0, 1),
+            Directions::SouthWest => (-1, 1),
+            Directions::West => (-1, 0),
+            Directions::NorthWest => (-1, -1),
+        }
+    }
+
+    // the full lowercase name, what the parser and the move scripts use
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Directions::North => "north",
+            Directions::NorthEast => "northeast",
+            Directions::East => "east",
+            Directions::SouthEast => "southeast",
+            Directions::South => "south",
+            Directions::SouthWest => "southwest",
+            Directions::West => "west",
+            Directions::NorthWest => "northwest",
+        }
+    }
+
+    pub(crate) fn abbreviation(self) -> &'static str {
+        match self {
+            Directions::North => "N",
+            Directions::NorthEast => "NE",
+            Directions::East => "E",
+            Directions::SouthEast => "SE",
+            Directions::South => "S",
+            Directions::SouthWest => "SW",
+            Directions::West => "W",
+            Directions::NorthWest => "NW",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ParseDirectionError(String);
+
+impl fmt::Display for ParseDirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not a direction, try N, NE, north or north-east",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseDirectionError {}
+
+// "N", "ne", "North", "northeast", "north-east", "North_East" and "north east" all work
+impl FromStr for Directions {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let word: String = s
+            .trim()
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .collect::<String>()
+            .to_ascii_lowercase();
+        Directions::ALL
+            .into_iter()
+            .find(|d| word == d.name() || word == d.abbreviation().to_ascii_lowercase())
+            .ok_or_else(|| ParseDirectionError(s.to_string()))
+    }
 }
 
 pub fn demonstrate_enums() {
@@ -20,6 +189,54 @@ pub fn demonstrate_enums() {
     turn_to_direction(direction3);
     let direction4 = Directions::West;
     turn_to_direction(direction4);
+    let direction5 = Directions::NorthEast;
+    turn_to_direction(direction5);
+
+    // turning, in eighths of a circle
+    let facing = Directions::North;
+    println!(
+        "Facing {:?}: a right turn faces {:?}, a left turn {:?}, turning around {:?}",
+        facing,
+        facing.turn_right(),
+        facing.turn_left(),
+        facing.reverse()
+    );
+    for k in [Side::Left, Side::Right] {
+        println!(
+            "Facing {:?}, the {:?} side is {:?} and the other side is {:?}",
+            facing,
+            k,
+            facing.to_side(k),
+            facing.to_side(k.opposite())
+        );
+    }
+
+    // degrees and vectors
+    for d in [Directions::East, Directions::SouthWest] {
+        let (x, y) = d.unit_vector();
+        println!(
+            "{:?} ({}) is {} degrees, the vector ({:.3}, {:.3})",
+            d,
+            d.abbreviation(),
+            d.degrees(),
+            x,
+            y
+        );
+    }
+    println!("-45 degrees is {:?}", Directions::from_degrees(-45));
+    println!("100 degrees is {:?}", Directions::from_degrees(100));
+    println!(
+        "The vector (3, 1) points closest to {:?}",
+        Directions::from_vector(3.0, 1.0)
+    );
+
+    // parsing
+    for text in ["N", "ne", "South-West", "up"] {
+        match text.parse::<Directions>() {
+            Ok(d) => println!("{:?} parses as {:?}", text, d),
+            Err(error) => println!("{:?} doesn't parse: {}", text, error),
+        }
+    }
 }
 
 fn turn_to_direction(direction: Directions) {
@@ -28,20 +245,24 @@ fn turn_to_direction(direction: Directions) {
         Directions::South => println!("Moving South"),
         Directions::East => println!("Moving East"),
         Directions::West => println!("Moving West"),
+        Directions::NorthEast => println!("Moving North East"),
+        Directions::NorthWest => println!("Moving North West"),
+        Directions::SouthEast => println!("Moving South East"),
+        Directions::SouthWest => println!("Moving South West"),
     }
 }
 
 // there is on esimilarity in structs and enums
 // just as we could define methods in structs by using implementation (impl)
 // we are also able to define methods in enums using impl
-#[derive(Debug)]
-enum Side {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Side {
     Left,
     Right,
 }
 
-#[derive(Debug)]
-enum Moves {
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Moves {
     Jump { h: u32 },
     Duck,
     Peek { k: Side },
@@ -49,7 +270,84 @@ enum Moves {
     Run { s: u32, v: u32 },
 }
 
+// the bounds the constructors below check, anything outside them is not a sensible move
+pub(crate) const MAX_HEIGHT: u32 = 100;
+pub(crate) const MAX_SPEED: u32 = 50;
+pub(crate) const MAX_VIGOR: u32 = 50;
+
+// what can go wrong building a move or working out what it costs
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum MoveError {
+    OutOfRange {
+        what: &'static str,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+    // the cost doesn't fit in a u32, only possible for moves built without the constructors
+    // or with a very expensive CostTable
+    CostOverflow(Moves),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::OutOfRange {
+                what,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "{} must be between {} and {}, got {}",
+                what, min, max, value
+            ),
+            MoveError::CostOverflow(m) => {
+                write!(f, "the cost of '{}' is too large to count", m.describe())
+            }
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+fn check(what: &'static str, value: u32, min: u32, max: u32) -> Result<u32, MoveError> {
+    if (min..=max).contains(&value) {
+        Ok(value)
+    } else {
+        Err(MoveError::OutOfRange {
+            what,
+            value,
+            min,
+            max,
+        })
+    }
+}
+
 impl Moves {
+    // constructors that only build sensible moves, the variants can still be written
+    // out by hand, which is why the cost functions check their arithmetic anyway
+    pub(crate) fn jump(h: u32) -> Result<Moves, MoveError> {
+        Ok(Moves::Jump {
+            h: check("height", h, 1, MAX_HEIGHT)?,
+        })
+    }
+
+    pub(crate) fn walk(d: Directions, s: u32, v: u32) -> Result<Moves, MoveError> {
+        Ok(Moves::Walk {
+            d,
+            s: check("speed", s, 1, MAX_SPEED)?,
+            v: check("vigor", v, 0, MAX_VIGOR)?,
+        })
+    }
+
+    pub(crate) fn run(s: u32, v: u32) -> Result<Moves, MoveError> {
+        Ok(Moves::Run {
+            s: check("speed", s, 1, MAX_SPEED)?,
+            v: check("vigor", v, 0, MAX_VIGOR)?,
+        })
+    }
+
     fn charge(&self) {
         match self {
             Moves::Jump { h } => {
@@ -71,17 +369,12 @@ impl Moves {
         }
     }
     // Method that calculates energy cost
-    fn energy_cost(&self) -> u32 {
-        match self {
-            Moves::Jump { h } => h * 2,         // Higher jumps cost more energy
-            Moves::Duck => 1,                   // Low energy cost
-            Moves::Peek { .. } => 1,            // Very low cost (.. ignores the data)
-            Moves::Walk { s, v, .. } => s + v,  // Walking cost depends on speed and vigor
-            Moves::Run { s, v } => (s + v) * 2, // Running costs the most
-        }
+    // the prices come from the default CostTable in stamina.rs, a game can bring its own
+    pub(crate) fn energy_cost(&self) -> Result<u32, MoveError> {
+        CostTable::default().cost(self)
     }
     // Method that returns a description
-    fn describe(&self) -> String {
+    pub(crate) fn describe(&self) -> String {
         match self {
             Moves::Jump { h } => format!("A {} meter high jump", h),
             Moves::Duck => "Ducking down low".to_string(),
@@ -108,6 +401,13 @@ impl Side {
     }
 }
 
+fn show_cost(m: &Moves) -> String {
+    match m.energy_cost() {
+        Ok(cost) => cost.to_string(),
+        Err(error) => format!("error: {}", error),
+    }
+}
+
 pub fn demonstrate_enum_game() {
     // Create some move instances
     let jump = Moves::Jump { h: 3 };
@@ -135,11 +435,26 @@ pub fn demonstrate_enum_game() {
     walk_north.charge();
 
     //energy cost for moves
-    println!("Energy cost for jump: {}", jump.energy_cost());
-    println!("Energy cost for duck: {}", duck.energy_cost());
-    println!("Energy cost for peek: {}", peek_left.energy_cost());
-    println!("Energy cost for run: {}", run_fast.energy_cost());
-    println!("Energy cost for walk: {}", walk_north.energy_cost());
+    println!("Energy cost for jump: {}", show_cost(&jump));
+    println!("Energy cost for duck: {}", show_cost(&duck));
+    println!("Energy cost for peek: {}", show_cost(&peek_left));
+    println!("Energy cost for run: {}", show_cost(&run_fast));
+    println!("Energy cost for walk: {}", show_cost(&walk_north));
+
+    //validated constructors and checked costs
+    match Moves::jump(500) {
+        Ok(m) => println!("Built {}", m.describe()),
+        Err(error) => println!("Can't build a 500 meter jump: {}", error),
+    }
+    match Moves::walk(Directions::West, 2, 3) {
+        Ok(m) => println!("Built '{}', it costs {}", m.describe(), show_cost(&m)),
+        Err(error) => println!("Can't build the walk: {}", error),
+    }
+    if let Err(error) = Moves::run(0, 3) {
+        println!("Can't build a run standing still: {}", error);
+    }
+    let huge = Moves::Run { s: u32::MAX, v: 1 };
+    println!("Energy cost for a run built by hand: {}", show_cost(&huge));
 
     //describe moves
     println!("Description for jump: {}", jump.describe());
@@ -158,3 +473,246 @@ pub fn demonstrate_enum_game() {
     let leftside = Side::Left;
     println!("Opposite of {:?} is {:?}", leftside, leftside.opposite());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the values around every bound, plus the ends of u32
+    fn edges(min: u32, max: u32) -> Vec<u32> {
+        let mut values = vec![
+            0,
+            1,
+            min,
+            max,
+            max + 1,
+            u32::MAX / 2,
+            u32::MAX - 1,
+            u32::MAX,
+        ];
+        values.extend(min.checked_sub(1));
+        values.extend(max.checked_sub(1));
+        values.extend(min.checked_add(1));
+        values
+    }
+
+    // a small xorshift so the random cases are the same on every run
+    fn numbers(seed: u64, count: usize) -> Vec<u32> {
+        let mut state = seed;
+        (0..count)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                // mostly small numbers near the bounds, now and then a huge one
+                if state.is_multiple_of(4) {
+                    (state >> 32) as u32
+                } else {
+                    (state % 128) as u32
+                }
+            })
+            .collect()
+    }
+
+    // what a cost should be, worked out in u128 where nothing can overflow
+    fn wide_cost(m: &Moves, costs: &CostTable) -> u128 {
+        match m {
+            Moves::Jump { h } => *h as u128 * costs.jump_per_height as u128,
+            Moves::Duck => costs.duck as u128,
+            Moves::Peek { .. } => costs.peek as u128,
+            Moves::Walk { s, v, .. } => (*s as u128 + *v as u128) * costs.walk_per_point as u128,
+            Moves::Run { s, v } => (*s as u128 + *v as u128) * costs.run_per_point as u128,
+        }
+    }
+
+    // the checked cost is the wide cost whenever that fits, an overflow error otherwise
+    fn assert_cost(m: Moves, costs: &CostTable) {
+        let wide = wide_cost(&m, costs);
+        match costs.cost(&m) {
+            Ok(cost) => assert_eq!(cost as u128, wide, "{:?}", m),
+            Err(error) => {
+                assert!(wide > u32::MAX as u128, "{:?} should cost {}", m, wide);
+                assert_eq!(error, MoveError::CostOverflow(m));
+            }
+        }
+    }
+
+    #[test]
+    fn constructors_accept_exactly_the_bounds() {
+        for h in edges(1, MAX_HEIGHT) {
+            assert_eq!(
+                Moves::jump(h).is_ok(),
+                (1..=MAX_HEIGHT).contains(&h),
+                "h {}",
+                h
+            );
+        }
+        for s in edges(1, MAX_SPEED) {
+            for v in edges(0, MAX_VIGOR) {
+                let fits = (1..=MAX_SPEED).contains(&s) && v <= MAX_VIGOR;
+                assert_eq!(Moves::run(s, v).is_ok(), fits, "s {} v {}", s, v);
+                assert_eq!(
+                    Moves::walk(Directions::North, s, v).is_ok(),
+                    fits,
+                    "s {} v {}",
+                    s,
+                    v
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn errors_say_what_is_wrong() {
+        assert_eq!(
+            Moves::jump(MAX_HEIGHT + 1),
+            Err(MoveError::OutOfRange {
+                what: "height",
+                value: MAX_HEIGHT + 1,
+                min: 1,
+                max: MAX_HEIGHT,
+            })
+        );
+        // speed is checked before vigor
+        let error = Moves::run(0, MAX_VIGOR + 1).unwrap_err();
+        assert_eq!(error.to_string(), "speed must be between 1 and 50, got 0");
+        let error = Moves::Run { s: u32::MAX, v: 1 }.energy_cost().unwrap_err();
+        assert!(error.to_string().contains("too large"));
+    }
+
+    #[test]
+    fn validated_moves_always_have_a_cost() {
+        let biggest = [
+            Moves::jump(MAX_HEIGHT).unwrap(),
+            Moves::run(MAX_SPEED, MAX_VIGOR).unwrap(),
+            Moves::walk(Directions::South, MAX_SPEED, MAX_VIGOR).unwrap(),
+        ];
+        for m in biggest {
+            assert!(m.energy_cost().is_ok(), "{:?}", m);
+        }
+        assert_eq!(Moves::jump(MAX_HEIGHT).unwrap().energy_cost(), Ok(200));
+        assert_eq!(
+            Moves::run(MAX_SPEED, MAX_VIGOR).unwrap().energy_cost(),
+            Ok(200)
+        );
+        assert_eq!(Moves::run(1, 0).unwrap().energy_cost(), Ok(2));
+    }
+
+    #[test]
+    fn checked_costs_match_wide_arithmetic_at_the_edges() {
+        let tables = [
+            CostTable::default(),
+            CostTable {
+                jump_per_height: u32::MAX,
+                duck: u32::MAX,
+                peek: 0,
+                walk_per_point: 2,
+                run_per_point: u32::MAX / 2,
+            },
+        ];
+        let values = edges(1, MAX_SPEED);
+        for costs in &tables {
+            for &a in &values {
+                assert_cost(Moves::Jump { h: a }, costs);
+                for &b in &values {
+                    assert_cost(Moves::Run { s: a, v: b }, costs);
+                    assert_cost(
+                        Moves::Walk {
+                            d: Directions::East,
+                            s: a,
+                            v: b,
+                        },
+                        costs,
+                    );
+                }
+            }
+            assert_cost(Moves::Duck, costs);
+            assert_cost(Moves::Peek { k: Side::Left }, costs);
+        }
+    }
+
+    #[test]
+    fn checked_costs_match_wide_arithmetic_for_random_moves() {
+        let values = numbers(0x5eed, 3000);
+        let costs = CostTable::default();
+        for pair in values.chunks(3) {
+            let [a, b, c] = [pair[0], pair[1], pair[2]];
+            assert_cost(Moves::Jump { h: a }, &costs);
+            assert_cost(Moves::Run { s: b, v: c }, &costs);
+            let table = CostTable {
+                walk_per_point: c,
+                ..costs
+            };
+            assert_cost(
+                Moves::Walk {
+                    d: Directions::West,
+                    s: a,
+                    v: b,
+                },
+                &table,
+            );
+        }
+    }
+
+    #[test]
+    fn turns_go_all_the_way_round() {
+        for d in Directions::ALL {
+            let mut turned = d;
+            for step in 1..=8 {
+                turned = turned.turn_right();
+                // nowhere back at the start before the full circle
+                assert_eq!(turned == d, step == 8, "{:?} after {} turns", d, step);
+            }
+            assert_eq!(d.turn_right().turn_left(), d);
+            assert_eq!(d.reverse().reverse(), d);
+            assert_ne!(d.reverse(), d);
+            assert_eq!(d.to_side(Side::Left), d.to_side(Side::Right).reverse());
+        }
+        assert_eq!(Directions::North.turn_left(), Directions::NorthWest);
+        assert_eq!(Directions::East.reverse(), Directions::West);
+        assert_eq!(Directions::East.to_side(Side::Right), Directions::South);
+    }
+
+    #[test]
+    fn degrees_and_vectors_lead_back_to_the_direction() {
+        for d in Directions::ALL {
+            assert_eq!(Directions::from_degrees(d.degrees() as i32), Some(d));
+            assert_eq!(Directions::from_degrees(d.degrees() as i32 - 360), Some(d));
+            let (x, y) = d.unit_vector();
+            assert_eq!(Directions::from_vector(x, y), Some(d));
+            assert!((x.hypot(y) - 1.0).abs() < 1e-12);
+        }
+        assert_eq!(Directions::from_degrees(450), Some(Directions::East));
+        assert_eq!(Directions::from_degrees(10), None);
+        assert_eq!(Directions::from_vector(0.0, 0.0), None);
+        assert_eq!(Directions::from_vector(f64::NAN, 1.0), None);
+        // close enough to north-east counts
+        assert_eq!(
+            Directions::from_vector(1.0, 0.9),
+            Some(Directions::NorthEast)
+        );
+    }
+
+    #[test]
+    fn names_and_abbreviations_parse_in_any_case() {
+        for d in Directions::ALL {
+            for word in [d.name(), d.abbreviation()] {
+                assert_eq!(word.parse(), Ok(d));
+                assert_eq!(word.to_ascii_uppercase().parse(), Ok(d));
+                assert_eq!(word.to_ascii_lowercase().parse(), Ok(d));
+            }
+        }
+        assert_eq!("North-East".parse(), Ok(Directions::NorthEast));
+        assert_eq!(" south_west ".parse(), Ok(Directions::SouthWest));
+        assert_eq!("north west".parse(), Ok(Directions::NorthWest));
+
+        for bad in ["up", "", "nn", "north-north"] {
+            assert_eq!(
+                bad.parse::<Directions>(),
+                Err(ParseDirectionError(bad.to_string()))
+            );
+        }
+        let error = "up".parse::<Directions>().unwrap_err();
+        assert!(error.to_string().contains("'up' is not a direction"));
+    }
+}
@@ -1,4 +1,5 @@
 mod borrow;
+mod dsl;
 mod enums;
 mod hashmaps;
 mod heap_stack;
@@ -6,8 +7,10 @@ mod mem_mgmt;
 mod mutability;
 mod option_enum;
 mod ownership;
+mod stamina;
 mod structs;
 mod vectors;
+mod world;
 
 fn main() {
     println!("Hello, cargoo!!!");
@@ -103,6 +106,21 @@ fn main() {
     enums::demonstrate_enums();
     enums::demonstrate_enum_game();
 
+    println!("\n{}", "=".repeat(50));
+    println!("GRID WORLD EXAMPLES");
+    println!("{}", "=".repeat(50));
+    world::demonstrate_world();
+
+    println!("\n{}", "=".repeat(50));
+    println!("STAMINA EXAMPLES");
+    println!("{}", "=".repeat(50));
+    stamina::demonstrate_stamina();
+
+    println!("\n{}", "=".repeat(50));
+    println!("MOVE SCRIPT EXAMPLES");
+    println!("{}", "=".repeat(50));
+    dsl::demonstrate_dsl();
+
     println!("\n{}", "=".repeat(50));
     println!("VECTORS EXAMPLES");
     println!("{}", "=".repeat(50));
@@ -0,0 +1,366 @@
+// stamina as a real budget: moves cost energy, energy comes back a little every tick
+// the price of each kind of move lives in a CostTable, so a game can make running
+// cheaper or jumping dearer without touching the match in enums.rs
+
+use crate::enums::{MoveError, Moves};
+use std::fmt;
+
+// what each kind of move costs
+// walk and run are paid per point of speed plus vigor, jump per unit of height
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CostTable {
+    pub(crate) jump_per_height: u32,
+    pub(crate) duck: u32,
+    pub(crate) peek: u32,
+    pub(crate) walk_per_point: u32,
+    pub(crate) run_per_point: u32,
+}
+
+// the prices Moves::energy_cost has always used
+impl Default for CostTable {
+    fn default() -> Self {
+        CostTable {
+            jump_per_height: 2,
+            duck: 1,
+            peek: 1,
+            walk_per_point: 1,
+            run_per_point: 2,
+        }
+    }
+}
+
+impl CostTable {
+    // checked all the way, a huge move is an error rather than a panic or a wrapped number
+    pub(crate) fn cost(&self, m: &Moves) -> Result<u32, MoveError> {
+        let cost = match m {
+            Moves::Jump { h } => h.checked_mul(self.jump_per_height),
+            Moves::Duck => Some(self.duck),
+            Moves::Peek { .. } => Some(self.peek),
+            Moves::Walk { s, v, .. } => s
+                .checked_add(*v)
+                .and_then(|points| points.checked_mul(self.walk_per_point)),
+            Moves::Run { s, v } => s
+                .checked_add(*v)
+                .and_then(|points| points.checked_mul(self.run_per_point)),
+        };
+        cost.ok_or_else(|| MoveError::CostOverflow(m.clone()))
+    }
+}
+
+// why a move was refused
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StaminaError {
+    // resting a few ticks would be enough
+    NotEnough { needed: u32, available: u32 },
+    // no amount of resting helps, the move costs more than the whole pool
+    OverMax { needed: u32, max: u32 },
+    // the move has no sensible cost at all
+    BadMove(MoveError),
+}
+
+impl fmt::Display for StaminaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StaminaError::NotEnough { needed, available } => write!(
+                f,
+                "the move needs {} stamina but only {} is left",
+                needed, available
+            ),
+            StaminaError::OverMax { needed, max } => write!(
+                f,
+                "the move needs {} stamina, more than the {} the actor can ever have",
+                needed, max
+            ),
+            StaminaError::BadMove(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for StaminaError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Stamina {
+    pub(crate) max: u32,
+    pub(crate) current: u32,
+    // how much comes back every tick
+    pub(crate) regen: u32,
+}
+
+impl Stamina {
+    // starts with a full pool
+    pub(crate) fn new(max: u32, regen: u32) -> Stamina {
+        Stamina {
+            max,
+            current: max,
+            regen,
+        }
+    }
+
+    // takes `needed` out of the pool, or leaves it alone and says why not
+    pub(crate) fn spend(&mut self, needed: u32) -> Result<u32, StaminaError> {
+        if needed > self.max {
+            return Err(StaminaError::OverMax {
+                needed,
+                max: self.max,
+            });
+        }
+        if needed > self.current {
+            return Err(StaminaError::NotEnough {
+                needed,
+                available: self.current,
+            });
+        }
+        self.current -= needed;
+        Ok(self.current)
+    }
+
+    // looks the move up in the table and pays for it, the one way every mover pays
+    pub(crate) fn pay(&mut self, costs: &CostTable, m: &Moves) -> Result<u32, StaminaError> {
+        let needed = costs.cost(m).map_err(StaminaError::BadMove)?;
+        self.spend(needed)
+    }
+
+    // one tick of rest, never past the max
+    pub(crate) fn tick(&mut self) {
+        self.current = self.current.saturating_add(self.regen).min(self.max);
+    }
+}
+
+// someone who moves around on a stamina budget
+pub(crate) struct Actor {
+    pub(crate) name: String,
+    pub(crate) stamina: Stamina,
+    pub(crate) costs: CostTable,
+}
+
+impl Actor {
+    pub(crate) fn new(name: &str, stamina: Stamina, costs: CostTable) -> Actor {
+        Actor {
+            name: name.to_string(),
+            stamina,
+            costs,
+        }
+    }
+
+    // pays for the move, Ok holds the stamina that is left
+    pub(crate) fn perform(&mut self, m: &Moves) -> Result<u32, StaminaError> {
+        self.stamina.pay(&self.costs, m)
+    }
+}
+
+// how long a pace can be kept up
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Sustain {
+    // regen covers the cost, the actor never has to stop
+    Forever,
+    // this many ticks in a row, then the next one can't be paid
+    Ticks(u32),
+}
+
+// runs `Run { s, v }` once per tick from a full pool (pay, then regenerate)
+// until the actor can't afford it any more
+pub(crate) fn sustain_run(
+    stamina: Stamina,
+    costs: &CostTable,
+    s: u32,
+    v: u32,
+) -> Result<Sustain, MoveError> {
+    let cost = costs.cost(&Moves::Run { s, v })?;
+    if cost > stamina.max {
+        return Ok(Sustain::Ticks(0));
+    }
+    // every tick that can be paid at all leaves the pool at least as full as before
+    if cost <= stamina.regen {
+        return Ok(Sustain::Forever);
+    }
+    // otherwise the pool never reaches the max again and shrinks by cost - regen every
+    // tick, so after k ticks it holds max - k * (cost - regen) and the tick after that
+    // can be paid as long as that is still at least the cost
+    Ok(Sustain::Ticks(
+        (stamina.max - cost) / (cost - stamina.regen) + 1,
+    ))
+}
+
+pub fn demonstrate_stamina() {
+    println!("=== Stamina Examples ===\n");
+
+    let mut runner = Actor::new("runner", Stamina::new(40, 3), CostTable::default());
+    let plan = [
+        Moves::Run { s: 6, v: 4 },
+        Moves::Jump { h: 5 },
+        Moves::Run { s: 6, v: 4 },
+        Moves::Duck,
+        Moves::Run { s: 30, v: 1 },
+    ];
+    for m in &plan {
+        match runner.perform(m) {
+            Ok(left) => println!(
+                "{} did '{}', {} stamina left",
+                runner.name,
+                m.describe(),
+                left
+            ),
+            Err(error) => println!("{} can't do '{}': {}", runner.name, m.describe(), error),
+        }
+        runner.stamina.tick();
+    }
+
+    // a sprinter's table: running is cheap, everything else is the usual price
+    let sprinter = CostTable {
+        run_per_point: 1,
+        ..CostTable::default()
+    };
+    let pool = Stamina::new(100, 6);
+    for (s, v) in [(3, 2), (5, 3), (8, 6)] {
+        for (name, costs) in [("default", CostTable::default()), ("sprinter", sprinter)] {
+            let how_long = match sustain_run(pool, &costs, s, v) {
+                Ok(Sustain::Forever) => "forever".to_string(),
+                Ok(Sustain::Ticks(ticks)) => format!("{} ticks", ticks),
+                Err(error) => format!("error: {}", error),
+            };
+            println!(
+                "Run {{ s: {}, v: {} }} with the {} costs ({} a tick, {} back): {}",
+                s,
+                v,
+                name,
+                costs.cost(&Moves::Run { s, v }).unwrap_or(u32::MAX),
+                pool.regen,
+                how_long
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{Directions, Side};
+
+    #[test]
+    fn spending_takes_from_the_pool_or_says_why_not() {
+        let mut pool = Stamina::new(10, 3);
+        assert_eq!(pool.spend(4), Ok(6));
+        assert_eq!(
+            pool.spend(7),
+            Err(StaminaError::NotEnough {
+                needed: 7,
+                available: 6
+            })
+        );
+        // more than the max is OverMax even when the pool is empty too
+        assert_eq!(
+            pool.spend(11),
+            Err(StaminaError::OverMax {
+                needed: 11,
+                max: 10
+            })
+        );
+        assert_eq!(pool.current, 6);
+
+        assert_eq!(pool.spend(6), Ok(0));
+        pool.tick();
+        pool.tick();
+        pool.tick();
+        pool.tick();
+        assert_eq!(pool.current, 10);
+    }
+
+    #[test]
+    fn the_cost_table_prices_every_move() {
+        let costs = CostTable {
+            jump_per_height: 5,
+            duck: 2,
+            peek: 0,
+            walk_per_point: 3,
+            run_per_point: 4,
+        };
+        let walk = Moves::Walk {
+            d: Directions::North,
+            s: 2,
+            v: 1,
+        };
+        assert_eq!(costs.cost(&Moves::Jump { h: 3 }), Ok(15));
+        assert_eq!(costs.cost(&Moves::Duck), Ok(2));
+        assert_eq!(costs.cost(&Moves::Peek { k: Side::Left }), Ok(0));
+        assert_eq!(costs.cost(&walk), Ok(9));
+        assert_eq!(costs.cost(&Moves::Run { s: 2, v: 1 }), Ok(12));
+
+        // the default table is what energy_cost charges
+        assert_eq!(CostTable::default().cost(&walk), walk.energy_cost());
+
+        let huge = Moves::Run { s: u32::MAX, v: 1 };
+        assert_eq!(costs.cost(&huge), Err(MoveError::CostOverflow(huge)));
+    }
+
+    #[test]
+    fn an_actor_reports_every_refusal_as_a_stamina_error() {
+        let mut actor = Actor::new("a", Stamina::new(10, 0), CostTable::default());
+        assert_eq!(actor.perform(&Moves::Jump { h: 3 }), Ok(4));
+        assert!(matches!(
+            actor.perform(&Moves::Jump { h: 3 }),
+            Err(StaminaError::NotEnough { .. })
+        ));
+        assert!(matches!(
+            actor.perform(&Moves::Jump { h: u32::MAX }),
+            Err(StaminaError::BadMove(MoveError::CostOverflow(_)))
+        ));
+    }
+
+    // the same thing the long way, one tick at a time
+    fn simulate(stamina: Stamina, cost: u32) -> u32 {
+        let mut pool = stamina;
+        let mut ticks = 0;
+        while pool.spend(cost).is_ok() {
+            pool.tick();
+            ticks += 1;
+        }
+        ticks
+    }
+
+    #[test]
+    fn sustain_run_counts_the_ticks_a_pace_lasts() {
+        let costs = CostTable::default();
+        // a run of 3 + 2 costs 10 a tick
+        assert_eq!(
+            sustain_run(Stamina::new(100, 10), &costs, 3, 2),
+            Ok(Sustain::Forever)
+        );
+        assert_eq!(
+            sustain_run(Stamina::new(9, 100), &costs, 3, 2),
+            Ok(Sustain::Ticks(0))
+        );
+        assert_eq!(
+            sustain_run(Stamina::new(10, 0), &costs, 3, 2),
+            Ok(Sustain::Ticks(1))
+        );
+        // 100, 94, 88, ... the 16th tick starts with 10 left
+        assert_eq!(
+            sustain_run(Stamina::new(100, 4), &costs, 3, 2),
+            Ok(Sustain::Ticks(16))
+        );
+        assert!(sustain_run(Stamina::new(100, 4), &costs, u32::MAX, 0).is_err());
+
+        let unit = CostTable {
+            run_per_point: 1,
+            ..costs
+        };
+        for max in 0..40 {
+            for regen in 0..12 {
+                for cost in 1..=max.min(30) {
+                    if cost <= regen {
+                        continue;
+                    }
+                    let stamina = Stamina::new(max, regen);
+                    assert_eq!(
+                        sustain_run(stamina, &unit, cost, 0),
+                        Ok(Sustain::Ticks(simulate(stamina, cost))),
+                        "max {} regen {} cost {}",
+                        max,
+                        regen,
+                        cost
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,548 @@
+// a small grid world where the Moves from enums.rs actually do something
+// a character stands on a tile, faces one of the Directions and has a pool of stamina
+// that pays for every move (see stamina.rs) and comes back a little every tick
+// applying a move changes that state and hands back a MoveResult instead of printing
+
+use crate::enums::{Directions, Moves, Side};
+use crate::stamina::{CostTable, Stamina, StaminaError};
+use std::fmt;
+
+// how many tiles a peek can see before it gives up
+const PEEK_RANGE: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Tile {
+    Open,
+    Obstacle { h: u32 },
+}
+
+// x grows to the east, y grows to the south, (0, 0) is the top left corner
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Position {
+    pub(crate) x: usize,
+    pub(crate) y: usize,
+}
+
+impl Position {
+    // one tile towards d, None when that would go past the top or the left edge
+    fn step(&self, d: Directions) -> Option<Position> {
+        let (dx, dy) = d.offset();
+        Some(Position {
+            x: self.x.checked_add_signed(dx as isize)?,
+            y: self.y.checked_add_signed(dy as isize)?,
+        })
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+pub(crate) struct Character {
+    pub(crate) position: Position,
+    pub(crate) facing: Directions,
+    pub(crate) stamina: Stamina,
+}
+
+// why a move stopped before it was done
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Blocker {
+    Edge,
+    Obstacle { h: u32 },
+}
+
+// what a move did, the caller decides whether and how to show it
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum MoveResult {
+    Moved {
+        from: Position,
+        to: Position,
+    },
+    // got as far as `to` and then something was in the way
+    Blocked {
+        from: Position,
+        to: Position,
+        by: Blocker,
+    },
+    // cleared is the height of the obstacle jumped over, None for a jump over nothing
+    Jumped {
+        to: Position,
+        cleared: Option<u32>,
+    },
+    JumpTooLow {
+        h: u32,
+        needed: u32,
+    },
+    Ducked,
+    Revealed {
+        side: Side,
+        tiles: Vec<(Position, Tile)>,
+    },
+    // nothing happened, the character can't pay for the move or it has no sensible cost
+    Refused(StaminaError),
+}
+
+impl fmt::Display for MoveResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveResult::Moved { from, to } => write!(f, "moved from {} to {}", from, to),
+            MoveResult::Blocked { from, to, by } => match by {
+                Blocker::Edge => write!(f, "moved from {} to {}, then hit the edge", from, to),
+                Blocker::Obstacle { h } => write!(
+                    f,
+                    "moved from {} to {}, then hit an obstacle {} high",
+                    from, to, h
+                ),
+            },
+            MoveResult::Jumped { to, cleared } => match cleared {
+                Some(h) => write!(f, "jumped over an obstacle {} high to {}", h, to),
+                None => write!(f, "jumped to {}", to),
+            },
+            MoveResult::JumpTooLow { h, needed } => {
+                write!(f, "a jump of {} is too low, it needs {}", h, needed)
+            }
+            MoveResult::Ducked => write!(f, "ducked"),
+            MoveResult::Revealed { side, tiles } => {
+                write!(f, "peeked {:?} and saw {} tiles:", side, tiles.len())?;
+                for (at, tile) in tiles {
+                    match tile {
+                        Tile::Open => write!(f, " {} open", at)?,
+                        Tile::Obstacle { h } => write!(f, " {} obstacle {}", at, h)?,
+                    }
+                }
+                Ok(())
+            }
+            MoveResult::Refused(error) => write!(f, "refused, {}", error),
+        }
+    }
+}
+
+pub(crate) struct World {
+    width: usize,
+    height: usize,
+    // row by row, tiles[y * width + x]
+    tiles: Vec<Tile>,
+    revealed: Vec<bool>,
+    costs: CostTable,
+    pub(crate) character: Character,
+}
+
+impl World {
+    // an empty world, the character starts in the top left corner facing east
+    pub(crate) fn new(width: usize, height: usize, stamina: Stamina) -> World {
+        let mut world = World {
+            width,
+            height,
+            tiles: vec![Tile::Open; width * height],
+            revealed: vec![false; width * height],
+            costs: CostTable::default(),
+            character: Character {
+                position: Position { x: 0, y: 0 },
+                facing: Directions::East,
+                stamina,
+            },
+        };
+        world.reveal(Position { x: 0, y: 0 });
+        world
+    }
+
+    pub(crate) fn with_costs(mut self, costs: CostTable) -> World {
+        self.costs = costs;
+        self
+    }
+
+    // time passes, the character gets some stamina back
+    pub(crate) fn tick(&mut self) {
+        self.character.stamina.tick();
+    }
+
+    pub(crate) fn place_obstacle(&mut self, at: Position, h: u32) {
+        if let Some(i) = self.index(at) {
+            self.tiles[i] = Tile::Obstacle { h };
+        }
+    }
+
+    // None for a position outside the world
+    pub(crate) fn tile(&self, at: Position) -> Option<Tile> {
+        self.index(at).map(|i| self.tiles[i])
+    }
+
+    fn index(&self, at: Position) -> Option<usize> {
+        if at.x < self.width && at.y < self.height {
+            Some(at.y * self.width + at.x)
+        } else {
+            None
+        }
+    }
+
+    fn reveal(&mut self, at: Position) {
+        if let Some(i) = self.index(at) {
+            self.revealed[i] = true;
+        }
+    }
+
+    // the tile next to `at` towards d, if it is inside the world
+    fn neighbour(&self, at: Position, d: Directions) -> Option<(Position, Tile)> {
+        let next = at.step(d)?;
+        self.tile(next).map(|tile| (next, tile))
+    }
+
+    // pays for the move first, a move the character can't afford changes nothing
+    pub(crate) fn apply(&mut self, m: &Moves) -> MoveResult {
+        if let Err(error) = self.character.stamina.pay(&self.costs, m) {
+            return MoveResult::Refused(error);
+        }
+
+        match m {
+            Moves::Walk { d, s, .. } => {
+                self.character.facing = *d;
+                self.travel(*s)
+            }
+            // a run has no direction of its own, it goes the way the character faces
+            Moves::Run { s, .. } => self.travel(*s),
+            Moves::Jump { h } => self.jump(*h),
+            Moves::Duck => MoveResult::Ducked,
+            Moves::Peek { k } => self.peek(*k),
+        }
+    }
+
+    // s tiles ahead, stopping in front of the first thing in the way
+    fn travel(&mut self, s: u32) -> MoveResult {
+        let from = self.character.position;
+        for _ in 0..s {
+            let here = self.character.position;
+            let by = match self.neighbour(here, self.character.facing) {
+                Some((next, Tile::Open)) => {
+                    self.character.position = next;
+                    self.reveal(next);
+                    continue;
+                }
+                Some((_, Tile::Obstacle { h })) => Blocker::Obstacle { h },
+                None => Blocker::Edge,
+            };
+            return MoveResult::Blocked { from, to: here, by };
+        }
+        MoveResult::Moved {
+            from,
+            to: self.character.position,
+        }
+    }
+
+    // one tile ahead, or over an obstacle up to h high onto the tile behind it
+    fn jump(&mut self, h: u32) -> MoveResult {
+        let here = self.character.position;
+        let facing = self.character.facing;
+        let (to, cleared) = match self.neighbour(here, facing) {
+            None => {
+                return MoveResult::Blocked {
+                    from: here,
+                    to: here,
+                    by: Blocker::Edge,
+                };
+            }
+            Some((next, Tile::Open)) => (next, None),
+            Some((_, Tile::Obstacle { h: needed })) if needed > h => {
+                return MoveResult::JumpTooLow { h, needed };
+            }
+            Some((over, Tile::Obstacle { h: needed })) => match self.neighbour(over, facing) {
+                Some((landing, Tile::Open)) => (landing, Some(needed)),
+                // nowhere to land, so the character doesn't take off
+                Some((_, Tile::Obstacle { h })) => {
+                    return MoveResult::Blocked {
+                        from: here,
+                        to: here,
+                        by: Blocker::Obstacle { h },
+                    };
+                }
+                None => {
+                    return MoveResult::Blocked {
+                        from: here,
+                        to: here,
+                        by: Blocker::Edge,
+                    };
+                }
+            },
+        };
+        self.character.position = to;
+        self.reveal(to);
+        MoveResult::Jumped { to, cleared }
+    }
+
+    // looks along the row or column on that side, an obstacle hides what is behind it
+    fn peek(&mut self, k: Side) -> MoveResult {
+        let d = self.character.facing.to_side(k);
+        let mut at = self.character.position;
+        let mut tiles = Vec::new();
+        while tiles.len() < PEEK_RANGE {
+            let Some((next, tile)) = self.neighbour(at, d) else {
+                break;
+            };
+            self.reveal(next);
+            tiles.push((next, tile));
+            if tile != Tile::Open {
+                break;
+            }
+            at = next;
+        }
+        MoveResult::Revealed { side: k, tiles }
+    }
+
+    // the map as far as the character knows it: @ is the character, # an obstacle,
+    // . open ground and ? a tile nobody has seen yet
+    pub(crate) fn render(&self) -> String {
+        let mut map = String::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let at = Position { x, y };
+                let i = y * self.width + x;
+                let c = if at == self.character.position {
+                    '@'
+                } else if !self.revealed[i] {
+                    '?'
+                } else {
+                    match self.tiles[i] {
+                        Tile::Open => '.',
+                        Tile::Obstacle { .. } => '#',
+                    }
+                };
+                map.push(c);
+            }
+            map.push('\n');
+        }
+        map
+    }
+}
+
+pub fn demonstrate_world() {
+    println!("=== Grid World Examples ===\n");
+
+    // rough ground, jumping costs half as much again as usual
+    let costs = CostTable {
+        jump_per_height: 3,
+        ..CostTable::default()
+    };
+    let mut world = World::new(7, 4, Stamina::new(50, 1)).with_costs(costs);
+    world.place_obstacle(Position { x: 3, y: 0 }, 2);
+    world.place_obstacle(Position { x: 5, y: 2 }, 6);
+    world.place_obstacle(Position { x: 6, y: 3 }, 1);
+
+    let moves = [
+        Moves::Walk {
+            d: Directions::East,
+            s: 5,
+            v: 1,
+        },
+        Moves::Jump { h: 1 },
+        // built by hand past every sensible bound, its cost doesn't even fit in a u32
+        Moves::Jump { h: u32::MAX },
+        Moves::Jump { h: 3 },
+        Moves::Peek { k: Side::Right },
+        Moves::Run { s: 4, v: 1 },
+        Moves::Walk {
+            d: Directions::South,
+            s: 3,
+            v: 2,
+        },
+        Moves::Duck,
+        Moves::Peek { k: Side::Right },
+        Moves::Run { s: 8, v: 6 },
+    ];
+
+    for m in &moves {
+        let result = world.apply(m);
+        println!("{:<45} -> {}", m.describe(), result);
+        world.tick();
+    }
+
+    let c = &world.character;
+    println!(
+        "\nThe character ends up at {} facing {:?} with {} stamina left",
+        c.position, c.facing, c.stamina.current
+    );
+    if let Some(tile) = world.tile(c.position) {
+        println!("It stands on {:?} ground", tile);
+    }
+    print!("{}", world.render());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::MoveError;
+
+    fn at(x: usize, y: usize) -> Position {
+        Position { x, y }
+    }
+
+    fn walk(d: Directions, s: u32) -> Moves {
+        Moves::Walk { d, s, v: 0 }
+    }
+
+    #[test]
+    fn walking_moves_the_character_and_turns_it() {
+        let mut world = World::new(5, 3, Stamina::new(100, 0));
+        assert_eq!(
+            world.apply(&walk(Directions::East, 3)),
+            MoveResult::Moved {
+                from: at(0, 0),
+                to: at(3, 0)
+            }
+        );
+        assert_eq!(
+            world.apply(&walk(Directions::South, 2)),
+            MoveResult::Moved {
+                from: at(3, 0),
+                to: at(3, 2)
+            }
+        );
+        let c = &world.character;
+        assert_eq!((c.position, c.facing), (at(3, 2), Directions::South));
+        assert_eq!(c.stamina.current, 95);
+
+        // a run keeps the way the character faces
+        world.apply(&walk(Directions::West, 0));
+        assert_eq!(
+            world.apply(&Moves::Run { s: 2, v: 0 }),
+            MoveResult::Moved {
+                from: at(3, 2),
+                to: at(1, 2)
+            }
+        );
+    }
+
+    #[test]
+    fn edges_and_obstacles_stop_a_move_halfway() {
+        let mut world = World::new(4, 4, Stamina::new(100, 0));
+        world.place_obstacle(at(2, 1), 3);
+
+        // the top and left edges are where a step would go below zero
+        assert_eq!(
+            world.apply(&walk(Directions::North, 2)),
+            MoveResult::Blocked {
+                from: at(0, 0),
+                to: at(0, 0),
+                by: Blocker::Edge
+            }
+        );
+        assert_eq!(
+            world.apply(&walk(Directions::East, 9)),
+            MoveResult::Blocked {
+                from: at(0, 0),
+                to: at(3, 0),
+                by: Blocker::Edge
+            }
+        );
+        world.apply(&walk(Directions::South, 1));
+        assert_eq!(
+            world.apply(&walk(Directions::West, 3)),
+            MoveResult::Blocked {
+                from: at(3, 1),
+                to: at(3, 1),
+                by: Blocker::Obstacle { h: 3 }
+            }
+        );
+        // a blocked move is still paid for in full
+        assert_eq!(world.character.stamina.current, 100 - 2 - 9 - 1 - 3);
+    }
+
+    #[test]
+    fn a_jump_clears_what_it_is_high_enough_for() {
+        let mut world = World::new(6, 1, Stamina::new(100, 0));
+        world.place_obstacle(at(1, 0), 2);
+        world.place_obstacle(at(4, 0), 1);
+        world.place_obstacle(at(5, 0), 1);
+
+        assert_eq!(
+            world.apply(&Moves::Jump { h: 1 }),
+            MoveResult::JumpTooLow { h: 1, needed: 2 }
+        );
+        assert_eq!(world.character.position, at(0, 0));
+        assert_eq!(
+            world.apply(&Moves::Jump { h: 2 }),
+            MoveResult::Jumped {
+                to: at(2, 0),
+                cleared: Some(2)
+            }
+        );
+        assert_eq!(
+            world.apply(&Moves::Jump { h: 1 }),
+            MoveResult::Jumped {
+                to: at(3, 0),
+                cleared: None
+            }
+        );
+        // high enough, but there is nowhere to land behind the obstacle
+        assert_eq!(
+            world.apply(&Moves::Jump { h: 1 }),
+            MoveResult::Blocked {
+                from: at(3, 0),
+                to: at(3, 0),
+                by: Blocker::Obstacle { h: 1 }
+            }
+        );
+    }
+
+    #[test]
+    fn a_peek_reveals_tiles_up_to_the_first_obstacle() {
+        let mut world = World::new(3, 4, Stamina::new(10, 0));
+        world.place_obstacle(at(0, 2), 1);
+        assert_eq!(world.render(), "@??\n???\n???\n???\n");
+
+        // facing east, right is south
+        assert_eq!(
+            world.apply(&Moves::Peek { k: Side::Right }),
+            MoveResult::Revealed {
+                side: Side::Right,
+                tiles: vec![(at(0, 1), Tile::Open), (at(0, 2), Tile::Obstacle { h: 1 })],
+            }
+        );
+        assert_eq!(world.render(), "@??\n.??\n#??\n???\n");
+
+        // and left is north, past the edge there is nothing to see
+        assert_eq!(
+            world.apply(&Moves::Peek { k: Side::Left }),
+            MoveResult::Revealed {
+                side: Side::Left,
+                tiles: vec![],
+            }
+        );
+        assert_eq!(world.character.position, at(0, 0));
+    }
+
+    #[test]
+    fn a_move_that_cant_be_paid_changes_nothing() {
+        let mut world = World::new(5, 5, Stamina::new(5, 1));
+        world.apply(&walk(Directions::East, 3));
+        let before = (world.character.position, world.character.facing);
+        assert_eq!(world.character.stamina.current, 2);
+
+        assert_eq!(
+            world.apply(&walk(Directions::South, 3)),
+            MoveResult::Refused(StaminaError::NotEnough {
+                needed: 3,
+                available: 2
+            })
+        );
+        assert_eq!(
+            world.apply(&Moves::Jump { h: 10 }),
+            MoveResult::Refused(StaminaError::OverMax { needed: 20, max: 5 })
+        );
+        assert_eq!(
+            world.apply(&Moves::Jump { h: u32::MAX }),
+            MoveResult::Refused(StaminaError::BadMove(MoveError::CostOverflow(
+                Moves::Jump { h: u32::MAX }
+            )))
+        );
+        assert_eq!((world.character.position, world.character.facing), before);
+        assert_eq!(world.character.stamina.current, 2);
+
+        // a tick of rest is enough for the walk
+        world.tick();
+        assert!(matches!(
+            world.apply(&walk(Directions::South, 3)),
+            MoveResult::Moved { .. }
+        ));
+    }
+}